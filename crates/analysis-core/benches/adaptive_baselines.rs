@@ -0,0 +1,90 @@
+//! Repeated percentile / z-score queries against one rolling baseline: the free
+//! functions rescan the series on every call, `RollingStats` prepares it once
+//! (optionally with a sorted index for many queries).
+//!
+//! Run with `cargo bench -p analysis-core --bench adaptive_baselines`.
+
+use analysis_core::adaptive::{self, RollingStats};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+/// Deterministic pseudo-random series (LCG) the size of a long minute-bar baseline
+fn baseline(len: usize) -> Vec<f64> {
+    let mut state: u64 = 42;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        })
+        .collect()
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let series = baseline(5_000);
+    let mut group = c.benchmark_group("percentile_and_z_score");
+
+    for queries in [1usize, 10, 100] {
+        let values: Vec<f64> = (0..queries)
+            .map(|i| i as f64 / queries as f64 - 0.5)
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("free_functions", queries),
+            &values,
+            |b, values| {
+                b.iter(|| {
+                    values
+                        .iter()
+                        .map(|&v| {
+                            adaptive::percentile_rank(v, black_box(&series))
+                                + adaptive::z_score_of(v, black_box(&series))
+                        })
+                        .sum::<f64>()
+                })
+            },
+        );
+
+        // The rolling series is built by the caller either way; only preparing
+        // and querying it is timed.
+        group.bench_with_input(
+            BenchmarkId::new("rolling_stats", queries),
+            &values,
+            |b, values| {
+                b.iter_batched(
+                    || series.clone(),
+                    |owned| {
+                        let stats = RollingStats::new(owned);
+                        values
+                            .iter()
+                            .map(|&v| stats.percentile_rank(v) + stats.z_score(v))
+                            .sum::<f64>()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("rolling_stats_sorted", queries),
+            &values,
+            |b, values| {
+                b.iter_batched(
+                    || series.clone(),
+                    |owned| {
+                        let stats = RollingStats::new(owned).with_sorted_index();
+                        values
+                            .iter()
+                            .map(|&v| stats.percentile_rank(v) + stats.z_score(v))
+                            .sum::<f64>()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries);
+criterion_main!(benches);
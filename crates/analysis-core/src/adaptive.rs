@@ -125,6 +125,104 @@ pub fn adaptive_signal(value: f64, history: &[f64], invert: bool) -> (f64, i32,
     (score, weight, significant)
 }
 
+/// A historical distribution prepared once for repeated queries.
+///
+/// `percentile_rank` and `z_score_of` re-derive everything from their slice on
+/// every call (two scans for the rank, three for the z-score). `RollingStats`
+/// computes the mean and standard deviation once, so z-scores are O(1) and a
+/// percentile rank is a single scan. Callers querying the same baseline many
+/// times can also build a sorted index (`with_sorted_index`) to turn percentile
+/// ranks into binary searches. Results are identical to the free functions.
+#[derive(Debug, Clone, Default)]
+pub struct RollingStats {
+    values: Vec<f64>,
+    sorted: Option<Vec<f64>>,
+    mean: f64,
+    std_dev: f64,
+}
+
+impl RollingStats {
+    pub fn new(values: Vec<f64>) -> Self {
+        Self {
+            mean: mean(&values),
+            std_dev: std_dev(&values),
+            sorted: None,
+            values,
+        }
+    }
+
+    /// Evaluate `f` on every `window`-length slice of `data` (oldest first),
+    /// keeping the windows it returns a value for
+    pub fn from_windows(data: &[f64], window: usize, f: impl Fn(&[f64]) -> Option<f64>) -> Self {
+        if window == 0 {
+            return Self::default();
+        }
+        Self::new(data.windows(window).filter_map(f).collect())
+    }
+
+    /// Sort a copy of the series so `percentile_rank` is O(log n). Worth it
+    /// from roughly ten queries against the same baseline.
+    pub fn with_sorted_index(mut self) -> Self {
+        let mut sorted = self.values.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        self.sorted = Some(sorted);
+        self
+    }
+
+    /// The series in its original order
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample standard deviation
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Same result as `percentile_rank(value, self.values())`
+    pub fn percentile_rank(&self, value: f64) -> f64 {
+        if self.values.is_empty() {
+            return 0.5;
+        }
+        let (below, equal) = match &self.sorted {
+            Some(sorted) => {
+                // |x - value| < EPSILON is a contiguous run of the sorted values
+                #[allow(clippy::float_equality_without_abs)]
+                let equal = sorted.partition_point(|&x| x - value < f64::EPSILON)
+                    - sorted.partition_point(|&x| x - value <= -f64::EPSILON);
+                (sorted.partition_point(|&x| x < value), equal)
+            }
+            None => self.values.iter().fold((0, 0), |(below, equal), &x| {
+                (
+                    below + usize::from(x < value),
+                    equal + usize::from((x - value).abs() < f64::EPSILON),
+                )
+            }),
+        };
+        (below as f64 + 0.5 * equal as f64) / self.values.len() as f64
+    }
+
+    /// Same result as `z_score_of(value, self.values())`
+    pub fn z_score(&self, value: f64) -> f64 {
+        if self.std_dev < f64::EPSILON {
+            return 0.0;
+        }
+        (value - self.mean) / self.std_dev
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +269,26 @@ mod tests {
         assert!(weight >= 2);
         assert!(significant);
     }
+
+    #[test]
+    fn test_rolling_stats_matches_free_functions() {
+        let data: Vec<f64> = (0..200)
+            .map(|i| ((i * 37) % 101) as f64 / 10.0 - 3.0)
+            .collect();
+        let stats = RollingStats::new(data.clone());
+        let indexed = stats.clone().with_sorted_index();
+        for value in [-5.0, -3.0, 0.0, 0.7, 2.5, 7.0, 12.0] {
+            assert_eq!(stats.percentile_rank(value), percentile_rank(value, &data));
+            assert_eq!(
+                indexed.percentile_rank(value),
+                percentile_rank(value, &data)
+            );
+            assert!((stats.z_score(value) - z_score_of(value, &data)).abs() < 1e-12);
+        }
+        assert_eq!(stats.values(), data.as_slice());
+
+        let sums = RollingStats::from_windows(&[1.0, 2.0, 3.0, 4.0], 2, |w| Some(w[0] + w[1]));
+        assert_eq!(sums.values(), &[3.0, 5.0, 7.0]);
+        assert_eq!(RollingStats::new(Vec::new()).percentile_rank(1.0), 0.5);
+    }
 }
@@ -0,0 +1,358 @@
+use crate::types::{AnalysisResult, SignalStrength, UnifiedAnalysis};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Largest metric moves kept in an `AnalysisDiff`
+const DIFF_TOP_METRICS: usize = 10;
+
+/// Relative moves below this are treated as noise
+const DIFF_MIN_RELATIVE_CHANGE: f64 = 0.01;
+
+/// An engine whose signal changed, appeared or went missing between two analyses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalTransition {
+    pub engine: String,
+    pub from: Option<SignalStrength>,
+    pub to: Option<SignalStrength>,
+}
+
+/// A supplementary signal block present in both analyses whose `signal` label
+/// changed, including to or from null
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupplementarySignalChange {
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A numeric metric present in both analyses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricChange {
+    /// Engine name, or the supplementary signal the metric belongs to
+    pub source: String,
+    pub metric: String,
+    pub previous: f64,
+    pub current: f64,
+    /// `(current - previous) / max(|previous|, |current|)`, in [-2, 2]
+    pub relative_change: f64,
+}
+
+/// What changed between two analyses of the same symbol, from `UnifiedAnalysis::diff`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisDiff {
+    pub symbol: String,
+    pub previous_timestamp: DateTime<Utc>,
+    pub current_timestamp: DateTime<Utc>,
+    pub previous_signal: SignalStrength,
+    pub current_signal: SignalStrength,
+    /// Engines whose signal differs, in technical/fundamental/quant/sentiment order
+    pub engine_transitions: Vec<SignalTransition>,
+    pub confidence_delta: f64,
+    /// Percent price change, when both analyses have a price
+    pub price_change_pct: Option<f64>,
+    /// Supplementary signals (options, insiders, ...) present only in the newer analysis
+    pub added_signals: Vec<String>,
+    /// Supplementary signals present only in the older analysis
+    pub removed_signals: Vec<String>,
+    /// Supplementary signals present in both whose `signal` label differs
+    pub signal_changes: Vec<SupplementarySignalChange>,
+    /// Biggest relative moves across engine and supplementary metrics, largest first
+    pub metric_changes: Vec<MetricChange>,
+}
+
+impl AnalysisDiff {
+    pub fn signal_changed(&self) -> bool {
+        self.previous_signal != self.current_signal
+    }
+
+    /// One line for alerts, e.g. "AAPL moved from Neutral to Buy, driven by
+    /// fundamental (Neutral -> Buy), quant (Weak Sell -> Neutral)"
+    pub fn summary(&self) -> String {
+        let headline = if self.signal_changed() {
+            format!(
+                "{} moved from {} to {}",
+                self.symbol,
+                self.previous_signal.to_label(),
+                self.current_signal.to_label()
+            )
+        } else {
+            format!(
+                "{} held at {} (confidence {:+.0} pts)",
+                self.symbol,
+                self.current_signal.to_label(),
+                self.confidence_delta * 100.0
+            )
+        };
+        if self.engine_transitions.is_empty() {
+            return headline;
+        }
+        let label = |s: &Option<SignalStrength>| s.as_ref().map_or("n/a", |s| s.to_label());
+        let drivers: Vec<String> = self
+            .engine_transitions
+            .iter()
+            .map(|t| format!("{} ({} -> {})", t.engine, label(&t.from), label(&t.to)))
+            .collect();
+        format!("{}, driven by {}", headline, drivers.join(", "))
+    }
+}
+
+/// Top-level numeric fields of a JSON object
+fn numeric_fields(value: Option<&serde_json::Value>) -> Vec<(&str, f64)> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(k, v)| v.as_f64().map(|n| (k.as_str(), n)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The `signal` label of a supplementary block; null or missing as `None`
+fn signal_label(block: Option<&serde_json::Value>) -> Option<String> {
+    match block?.get("signal")? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn metric_changes(
+    source: &str,
+    previous: Option<&serde_json::Value>,
+    current: Option<&serde_json::Value>,
+    out: &mut Vec<MetricChange>,
+) {
+    let previous = numeric_fields(previous);
+    for (metric, now) in numeric_fields(current) {
+        let Some(&(_, before)) = previous.iter().find(|(k, _)| *k == metric) else {
+            continue;
+        };
+        let scale = before.abs().max(now.abs());
+        if !scale.is_finite() || scale < f64::EPSILON {
+            continue;
+        }
+        let relative_change = (now - before) / scale;
+        if relative_change.abs() >= DIFF_MIN_RELATIVE_CHANGE {
+            out.push(MetricChange {
+                source: source.to_string(),
+                metric: metric.to_string(),
+                previous: before,
+                current: now,
+                relative_change,
+            });
+        }
+    }
+}
+
+impl UnifiedAnalysis {
+    /// Compare with an earlier analysis of the same symbol: signal transitions
+    /// per engine, confidence and price moves, supplementary signals that
+    /// appeared or disappeared, and the largest metric changes.
+    pub fn diff(&self, previous: &UnifiedAnalysis) -> AnalysisDiff {
+        let engines: [(&str, &Option<AnalysisResult>, &Option<AnalysisResult>); 4] = [
+            ("technical", &previous.technical, &self.technical),
+            ("fundamental", &previous.fundamental, &self.fundamental),
+            ("quant", &previous.quantitative, &self.quantitative),
+            ("sentiment", &previous.sentiment, &self.sentiment),
+        ];
+
+        let mut engine_transitions = Vec::new();
+        let mut changes = Vec::new();
+        for (engine, before, now) in engines {
+            let from = before.as_ref().map(|r| r.signal);
+            let to = now.as_ref().map(|r| r.signal);
+            if from != to {
+                engine_transitions.push(SignalTransition {
+                    engine: engine.to_string(),
+                    from,
+                    to,
+                });
+            }
+            metric_changes(
+                engine,
+                before.as_ref().map(|r| &r.metrics),
+                now.as_ref().map(|r| &r.metrics),
+                &mut changes,
+            );
+        }
+
+        let signal_keys = |a: &UnifiedAnalysis| -> Vec<String> {
+            a.supplementary_signals
+                .as_ref()
+                .and_then(|s| s.as_object())
+                .map(|obj| obj.keys().cloned().collect())
+                .unwrap_or_default()
+        };
+        let (previous_keys, current_keys) = (signal_keys(previous), signal_keys(self));
+        let added_signals: Vec<String> = current_keys
+            .iter()
+            .filter(|k| !previous_keys.contains(k))
+            .cloned()
+            .collect();
+        let removed_signals: Vec<String> = previous_keys
+            .iter()
+            .filter(|k| !current_keys.contains(k))
+            .cloned()
+            .collect();
+        let mut signal_changes = Vec::new();
+        for key in current_keys.iter().filter(|k| previous_keys.contains(k)) {
+            let block = |a: &UnifiedAnalysis| a.supplementary_signals.as_ref()?.get(key).cloned();
+            let (before, now) = (block(previous), block(self));
+            let (from, to) = (signal_label(before.as_ref()), signal_label(now.as_ref()));
+            if from != to {
+                signal_changes.push(SupplementarySignalChange {
+                    name: key.clone(),
+                    from,
+                    to,
+                });
+            }
+            metric_changes(key, before.as_ref(), now.as_ref(), &mut changes);
+        }
+
+        changes.sort_by(|a, b| b.relative_change.abs().total_cmp(&a.relative_change.abs()));
+        changes.truncate(DIFF_TOP_METRICS);
+
+        let price_change_pct = match (previous.current_price, self.current_price) {
+            (Some(before), Some(now)) if before > 0.0 => Some((now / before - 1.0) * 100.0),
+            _ => None,
+        };
+
+        AnalysisDiff {
+            symbol: self.symbol.clone(),
+            previous_timestamp: previous.timestamp,
+            current_timestamp: self.timestamp,
+            previous_signal: previous.overall_signal,
+            current_signal: self.overall_signal,
+            engine_transitions,
+            confidence_delta: self.overall_confidence - previous.overall_confidence,
+            price_change_pct,
+            added_signals,
+            removed_signals,
+            signal_changes,
+            metric_changes: changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn analysis(
+        signal: SignalStrength,
+        fundamental: Option<SignalStrength>,
+        margin: f64,
+        supplementary: serde_json::Value,
+    ) -> UnifiedAnalysis {
+        let result = |signal: SignalStrength, metrics: serde_json::Value| AnalysisResult {
+            symbol: "AAPL".to_string(),
+            timestamp: Utc::now(),
+            signal,
+            confidence: 0.6,
+            reason: String::new(),
+            metrics,
+            confidence_components: json!({}),
+        };
+        UnifiedAnalysis {
+            symbol: "AAPL".to_string(),
+            name: None,
+            timestamp: Utc::now(),
+            current_price: Some(100.0),
+            technical: Some(result(SignalStrength::Buy, json!({"rsi": 55.0}))),
+            fundamental: fundamental
+                .map(|s| result(s, json!({"operating_margin": margin, "sector": "Tech"}))),
+            quantitative: None,
+            sentiment: None,
+            overall_signal: signal,
+            overall_confidence: 0.5,
+            recommendation: String::new(),
+            market_regime: None,
+            market_regime_detail: None,
+            conviction_tier: None,
+            time_horizon_signals: None,
+            supplementary_signals: Some(supplementary),
+            engine_errors: Vec::new(),
+            days_to_earnings: None,
+            earnings_blackout: false,
+            rs_rating: None,
+            has_sufficient_data: true,
+            multi_timeframe_alignment: None,
+            macro_context: None,
+            suggested_position_pct: None,
+            low_quality_universe: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_transitions_signals_and_metric_moves() {
+        let previous = analysis(
+            SignalStrength::Neutral,
+            Some(SignalStrength::Neutral),
+            0.20,
+            json!({"options": {"put_call_ratio": 0.9}}),
+        );
+        let mut current = analysis(
+            SignalStrength::Buy,
+            Some(SignalStrength::Buy),
+            0.25,
+            json!({"insiders": {"buy_count": 2}}),
+        );
+        current.overall_confidence = 0.65;
+        current.current_price = Some(105.0);
+
+        let diff = current.diff(&previous);
+        assert!(diff.signal_changed());
+        assert_eq!(
+            diff.engine_transitions,
+            vec![SignalTransition {
+                engine: "fundamental".to_string(),
+                from: Some(SignalStrength::Neutral),
+                to: Some(SignalStrength::Buy),
+            }]
+        );
+        assert!((diff.confidence_delta - 0.15).abs() < 1e-9);
+        assert!((diff.price_change_pct.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(diff.added_signals, vec!["insiders".to_string()]);
+        assert_eq!(diff.removed_signals, vec!["options".to_string()]);
+        // RSI is unchanged, the margin expansion is the only metric move
+        assert_eq!(diff.metric_changes.len(), 1);
+        assert_eq!(diff.metric_changes[0].metric, "operating_margin");
+        assert_eq!(
+            diff.summary(),
+            "AAPL moved from Neutral to Buy, driven by fundamental (Neutral -> Buy)"
+        );
+
+        assert!(!previous.diff(&previous).signal_changed());
+        assert!(previous.diff(&previous).metric_changes.is_empty());
+        assert!(previous.diff(&previous).signal_changes.is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_supplementary_signal_changes() {
+        let block = |signal: serde_json::Value| json!({"sentiment_price_divergence": {"signal": signal, "price_percentile": 0.3}});
+        let previous = analysis(SignalStrength::Neutral, None, 0.0, block(json!(null)));
+        let current = analysis(
+            SignalStrength::Neutral,
+            None,
+            0.0,
+            block(json!("Bullish Divergence")),
+        );
+
+        let diff = current.diff(&previous);
+        assert!(diff.added_signals.is_empty() && diff.removed_signals.is_empty());
+        assert_eq!(
+            diff.signal_changes,
+            vec![SupplementarySignalChange {
+                name: "sentiment_price_divergence".to_string(),
+                from: None,
+                to: Some("Bullish Divergence".to_string()),
+            }]
+        );
+        assert_eq!(
+            previous.diff(&current).signal_changes[0].from.as_deref(),
+            Some("Bullish Divergence")
+        );
+    }
+}
@@ -14,6 +14,21 @@ pub enum AnalysisError {
     #[error("API error: {0}")]
     ApiError(String),
 
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Parse error: {0}")]
+    Parse(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
     #[error("Cache error: {0}")]
     CacheError(String),
 
@@ -23,3 +38,23 @@ pub enum AnalysisError {
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
+
+impl AnalysisError {
+    /// Typed error for a non-success HTTP status; unrecognized statuses stay `ApiError`
+    pub fn from_http_status(status: u16, detail: String) -> Self {
+        match status {
+            429 => AnalysisError::RateLimited(detail),
+            401 | 403 => AnalysisError::Unauthorized(detail),
+            404 => AnalysisError::NotFound(detail),
+            _ => AnalysisError::ApiError(detail),
+        }
+    }
+
+    /// Whether retrying later may succeed (rate limits and network failures)
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AnalysisError::RateLimited(_) | AnalysisError::Network(_)
+        )
+    }
+}
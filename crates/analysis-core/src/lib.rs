@@ -1,8 +1,12 @@
 pub mod adaptive;
+pub mod clock;
+pub mod diff;
 pub mod error;
 pub mod traits;
 pub mod types;
 
+pub use clock::*;
+pub use diff::*;
 pub use error::*;
 pub use traits::*;
 pub use types::*;
@@ -23,6 +23,17 @@ pub trait QuantAnalyzer: Send + Sync {
     async fn analyze(&self, symbol: &str, bars: &[Bar]) -> Result<AnalysisResult, AnalysisError>;
 }
 
+/// Trait for pluggable news feeds (market data vendors, RSS, internal feeds)
+#[async_trait]
+pub trait NewsSource: Send + Sync {
+    /// Short identifier recorded as the `source` of the articles it returns
+    fn name(&self) -> &str;
+
+    /// Up to `limit` recent articles about `symbol`. Sources that aren't
+    /// configured should return an empty list rather than an error.
+    async fn fetch(&self, symbol: &str, limit: u32) -> Result<Vec<NewsArticle>, AnalysisError>;
+}
+
 /// Trait for sentiment analysis engines
 #[async_trait]
 pub trait SentimentAnalyzer: Send + Sync {
@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "openapi")]
@@ -44,6 +45,9 @@ pub struct Financials {
     pub symbol: String,
     pub fiscal_period: String,
     pub fiscal_year: i32,
+    /// Date the statement was filed with the SEC, when reported
+    #[serde(default)]
+    pub filing_date: Option<NaiveDate>,
     pub revenue: Option<f64>,
     pub gross_profit: Option<f64>,
     pub operating_income: Option<f64>,
@@ -55,6 +59,33 @@ pub struct Financials {
     pub cash_flow_operating: Option<f64>,
     pub cash_flow_investing: Option<f64>,
     pub cash_flow_financing: Option<f64>,
+    /// Capital expenditures as a positive outflow, when reported
+    #[serde(default)]
+    pub capital_expenditure: Option<f64>,
+    /// Cost of revenue (COGS) for the period
+    #[serde(default)]
+    pub cost_of_revenue: Option<f64>,
+    /// Accounts receivable at period end
+    #[serde(default)]
+    pub accounts_receivable: Option<f64>,
+    /// Inventory at period end
+    #[serde(default)]
+    pub inventory: Option<f64>,
+    /// Accounts payable at period end
+    #[serde(default)]
+    pub accounts_payable: Option<f64>,
+    /// Income tax expense (benefit when negative)
+    #[serde(default)]
+    pub income_tax_expense: Option<f64>,
+    /// Income from continuing operations before tax
+    #[serde(default)]
+    pub pretax_income: Option<f64>,
+    /// Weighted-average diluted share count for the period (basic when diluted isn't reported)
+    #[serde(default)]
+    pub weighted_average_shares: Option<f64>,
+    /// ISO code of the reporting currency (e.g. "USD", "EUR"), when known
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 /// Analyst consensus rating (aggregated from multiple analysts)
@@ -99,6 +130,9 @@ pub struct NewsArticle {
     pub description: Option<String>,
     pub keywords: Vec<String>,
     pub tickers: Vec<String>,
+    /// Feed the article was fetched from ("polygon", "finnhub"), when known
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 /// Signal strength
@@ -164,6 +198,45 @@ pub struct AnalysisResult {
     pub confidence: f64, // 0.0 to 1.0
     pub reason: String,
     pub metrics: serde_json::Value,
+    /// Inputs the confidence was derived from (agreement, data completeness, ...)
+    #[serde(default)]
+    pub confidence_components: serde_json::Value,
+}
+
+/// Market regime label together with the percentiles it was derived from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MarketRegimeDetail {
+    /// Composite label such as "high_vol_bear", or "unknown" without enough history
+    pub label: String,
+    /// Percentile (0–1) of recent volatility vs its own rolling history
+    pub vol_percentile: f64,
+    /// Percentile (0–1) of the 20-day momentum
+    pub momentum_percentile: f64,
+    /// Percentile (0–1) of the distance from the 50-day SMA
+    pub sma_distance_percentile: f64,
+    /// 0–1, high when any percentile sits near a regime threshold
+    pub transition_risk: f64,
+}
+
+/// Top-down macro backdrop read from rate, gold, dollar and volatility proxies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct MacroContext {
+    /// "rising", "falling" or "stable" (inverse of the TLT trend)
+    pub rates: String,
+    /// "strengthening", "weakening" or "stable" (UUP trend)
+    pub dollar: String,
+    /// "rising", "falling" or "stable" (GLD trend)
+    pub gold: String,
+    /// "rising", "falling" or "stable" (VIX proxy trend)
+    pub volatility: String,
+    /// "risk_on", "risk_off" or "neutral"
+    pub risk_appetite: String,
+    /// Recent return of each proxy that had enough history, keyed by ticker
+    pub proxy_returns: std::collections::HashMap<String, f64>,
+    /// Readable description such as "rising rates, strong dollar, risk-off"
+    pub summary: String,
 }
 
 /// Combined analysis from all engines
@@ -184,6 +257,9 @@ pub struct UnifiedAnalysis {
     pub recommendation: String,
     #[serde(default)]
     pub market_regime: Option<String>,
+    /// Percentiles behind `market_regime` and how close it is to flipping
+    #[serde(default)]
+    pub market_regime_detail: Option<MarketRegimeDetail>,
     /// Conviction tier: HIGH, MODERATE, LOW based on engine alignment + confidence
     #[serde(default)]
     pub conviction_tier: Option<String>,
@@ -193,6 +269,180 @@ pub struct UnifiedAnalysis {
     /// Supplementary signals from options, insiders, dividends, etc.
     #[serde(default)]
     pub supplementary_signals: Option<serde_json::Value>,
+    /// Engines that failed or could not run, as (engine name, error message)
+    #[serde(default)]
+    pub engine_errors: Vec<(String, String)>,
+    /// Whole days until the next scheduled earnings report, when known
+    #[serde(default)]
+    pub days_to_earnings: Option<i64>,
+    /// True when earnings are close enough that pre-report signals are unreliable
+    #[serde(default)]
+    pub earnings_blackout: bool,
+    /// 1–99 relative strength rank of the trailing multi-period return vs all US stocks
+    #[serde(default)]
+    pub rs_rating: Option<u8>,
+    /// False when no engine produced a result, so `overall_signal` is a placeholder
+    /// rather than a genuine Neutral
+    #[serde(default = "default_has_sufficient_data")]
+    pub has_sufficient_data: bool,
+    /// Daily vs weekly technical agreement: "aligned_bullish", "aligned_bearish",
+    /// "divergent", or "mixed"; `None` when weekly bars weren't analyzed
+    #[serde(default)]
+    pub multi_timeframe_alignment: Option<String>,
+    /// Rates, dollar, gold and volatility backdrop; `None` when no proxy had data
+    #[serde(default)]
+    pub macro_context: Option<MacroContext>,
+    /// Suggested long position as a percent of the portfolio, from fractional Kelly
+    /// scaled by confidence and volatility; `None` without a quant result
+    #[serde(default)]
+    pub suggested_position_pct: Option<f64>,
+    /// Market cap or price is below the orchestrator's quality floor, so the
+    /// signals rest on thin, noisy data and confidence has been capped
+    #[serde(default)]
+    pub low_quality_universe: bool,
+}
+
+fn default_has_sufficient_data() -> bool {
+    true
+}
+
+/// Columns of `UnifiedAnalysis::to_flat_record`, in export order. Engine columns
+/// are `<engine>_signal`, `<engine>_confidence` and `<engine>_<metric>`. New
+/// columns are appended, never reordered or removed, so exported spreadsheets
+/// keep their layout across versions.
+const FLAT_COLUMNS: &[&str] = &[
+    "symbol",
+    "name",
+    "timestamp",
+    "current_price",
+    "overall_signal",
+    "overall_score",
+    "overall_confidence",
+    "recommendation",
+    "conviction_tier",
+    "market_regime",
+    "multi_timeframe_alignment",
+    "risk_appetite",
+    "rs_rating",
+    "days_to_earnings",
+    "earnings_blackout",
+    "suggested_position_pct",
+    "has_sufficient_data",
+    "technical_signal",
+    "technical_confidence",
+    "technical_rsi",
+    "technical_macd_histogram",
+    "technical_adx",
+    "technical_sma_50",
+    "technical_bb_percent_b",
+    "technical_volume_ratio",
+    "technical_trend",
+    "fundamental_signal",
+    "fundamental_confidence",
+    "fundamental_pe_ratio",
+    "fundamental_peg_ratio",
+    "fundamental_roe",
+    "fundamental_roic",
+    "fundamental_debt_to_equity",
+    "fundamental_fcf_yield",
+    "fundamental_revenue_growth",
+    "fundamental_operating_margin",
+    "fundamental_piotroski_f_score",
+    "fundamental_altman_z_score",
+    "fundamental_quality_score",
+    "fundamental_price_to_fair_value",
+    "fundamental_sector",
+    "quant_signal",
+    "quant_confidence",
+    "quant_sharpe_ratio",
+    "quant_sortino_ratio",
+    "quant_volatility",
+    "quant_max_drawdown",
+    "quant_beta",
+    "quant_var_95",
+    "quant_hurst_exponent",
+    "quant_kelly_fraction",
+    "sentiment_signal",
+    "sentiment_confidence",
+    "sentiment_normalized_score",
+    "sentiment_article_count",
+    "sentiment_buzz_ratio",
+    "engine_errors",
+    "low_quality_universe",
+];
+
+/// Plain-text cell: strings unquoted, null as empty, anything nested as compact JSON
+fn flat_cell(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl UnifiedAnalysis {
+    /// Flat, column-oriented view for CSV/parquet export.
+    ///
+    /// Every record has the keys of `FLAT_COLUMNS` in that order whatever data
+    /// was available; missing values are empty strings.
+    pub fn to_flat_record(&self) -> IndexMap<String, String> {
+        FLAT_COLUMNS
+            .iter()
+            .map(|column| (column.to_string(), self.flat_value(column)))
+            .collect()
+    }
+
+    fn flat_value(&self, column: &str) -> String {
+        match column {
+            "symbol" => self.symbol.clone(),
+            "name" => opt(&self.name),
+            "timestamp" => self.timestamp.to_rfc3339(),
+            "current_price" => opt(&self.current_price),
+            "overall_signal" => format!("{:?}", self.overall_signal),
+            "overall_score" => self.overall_signal.to_score().to_string(),
+            "overall_confidence" => self.overall_confidence.to_string(),
+            "recommendation" => self.recommendation.clone(),
+            "conviction_tier" => opt(&self.conviction_tier),
+            "market_regime" => opt(&self.market_regime),
+            "multi_timeframe_alignment" => opt(&self.multi_timeframe_alignment),
+            "risk_appetite" => opt(&self.macro_context.as_ref().map(|m| &m.risk_appetite)),
+            "rs_rating" => opt(&self.rs_rating),
+            "days_to_earnings" => opt(&self.days_to_earnings),
+            "earnings_blackout" => self.earnings_blackout.to_string(),
+            "suggested_position_pct" => opt(&self.suggested_position_pct),
+            "has_sufficient_data" => self.has_sufficient_data.to_string(),
+            "low_quality_universe" => self.low_quality_universe.to_string(),
+            "engine_errors" => self
+                .engine_errors
+                .iter()
+                .map(|(engine, error)| format!("{}: {}", engine, error))
+                .collect::<Vec<_>>()
+                .join("; "),
+            _ => {
+                let engines = [
+                    ("technical_", &self.technical),
+                    ("fundamental_", &self.fundamental),
+                    ("quant_", &self.quantitative),
+                    ("sentiment_", &self.sentiment),
+                ];
+                let Some((result, field)) = engines
+                    .into_iter()
+                    .find_map(|(prefix, result)| Some((result, column.strip_prefix(prefix)?)))
+                else {
+                    return String::new();
+                };
+                match field {
+                    "signal" => opt(&result.as_ref().map(|r| format!("{:?}", r.signal))),
+                    "confidence" => opt(&result.as_ref().map(|r| r.confidence)),
+                    metric => flat_cell(result.as_ref().and_then(|r| r.metrics.get(metric))),
+                }
+            }
+        }
+    }
 }
 
 /// Timeframe for analysis
@@ -224,4 +474,167 @@ impl Timeframe {
             Timeframe::Month1 => 43200,
         }
     }
+
+    /// Number of bars per year used to annualize returns and volatility
+    /// (252 trading days of 6.5 regular-session hours for intraday bars)
+    pub fn periods_per_year(&self) -> f64 {
+        self.periods_per_year_for(AssetClass::Equity)
+    }
+
+    /// Bars per year for a market with `asset_class`'s trading calendar
+    pub fn periods_per_year_for(&self, asset_class: AssetClass) -> f64 {
+        let trading_days = asset_class.trading_days_per_year();
+        let session_hours = asset_class.session_hours();
+        match self {
+            Timeframe::Minute1 => trading_days * session_hours * 60.0,
+            Timeframe::Minute5 => trading_days * session_hours * 12.0,
+            Timeframe::Minute15 => trading_days * session_hours * 4.0,
+            Timeframe::Minute30 => trading_days * session_hours * 2.0,
+            Timeframe::Hour1 => trading_days * session_hours,
+            Timeframe::Hour4 => trading_days * session_hours / 4.0,
+            Timeframe::Day1 => trading_days,
+            Timeframe::Week1 => 52.0,
+            Timeframe::Month1 => 12.0,
+        }
+    }
+}
+
+/// Market a ticker trades in, following Polygon's ticker prefixes
+/// (`X:BTCUSD` for crypto, `C:EURUSD` for forex, bare symbols for stocks)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AssetClass {
+    #[default]
+    Equity,
+    Crypto,
+    Forex,
+}
+
+impl AssetClass {
+    pub fn from_symbol(symbol: &str) -> Self {
+        let symbol = symbol.trim();
+        let prefix = |p: &str| {
+            symbol.len() > 2 && symbol.get(..2).is_some_and(|s| s.eq_ignore_ascii_case(p))
+        };
+        if prefix("X:") {
+            AssetClass::Crypto
+        } else if prefix("C:") {
+            AssetClass::Forex
+        } else {
+            AssetClass::Equity
+        }
+    }
+
+    /// Trading days per year: crypto never closes, forex trades around the clock on weekdays
+    pub fn trading_days_per_year(&self) -> f64 {
+        match self {
+            AssetClass::Equity => 252.0,
+            AssetClass::Crypto => 365.0,
+            AssetClass::Forex => 260.0,
+        }
+    }
+
+    /// Hours per trading day covered by intraday bars
+    pub fn session_hours(&self) -> f64 {
+        match self {
+            AssetClass::Equity => 6.5,
+            AssetClass::Crypto | AssetClass::Forex => 24.0,
+        }
+    }
+
+    /// Whether company data applies (financials, analyst consensus, insiders,
+    /// dividends, options, earnings calendar, market sessions)
+    pub fn is_equity(&self) -> bool {
+        matches!(self, AssetClass::Equity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn analysis() -> UnifiedAnalysis {
+        UnifiedAnalysis {
+            symbol: "AAPL".to_string(),
+            name: None,
+            timestamp: Utc::now(),
+            current_price: None,
+            technical: None,
+            fundamental: None,
+            quantitative: None,
+            sentiment: None,
+            overall_signal: SignalStrength::Neutral,
+            overall_confidence: 0.0,
+            recommendation: String::new(),
+            market_regime: None,
+            market_regime_detail: None,
+            conviction_tier: None,
+            time_horizon_signals: None,
+            supplementary_signals: None,
+            engine_errors: Vec::new(),
+            days_to_earnings: None,
+            earnings_blackout: false,
+            rs_rating: None,
+            has_sufficient_data: false,
+            multi_timeframe_alignment: None,
+            macro_context: None,
+            suggested_position_pct: None,
+            low_quality_universe: false,
+        }
+    }
+
+    #[test]
+    fn test_flat_record_columns_fixed() {
+        let result = |metrics: serde_json::Value| AnalysisResult {
+            symbol: "AAPL".to_string(),
+            timestamp: Utc::now(),
+            signal: SignalStrength::Buy,
+            confidence: 0.7,
+            reason: String::new(),
+            metrics,
+            confidence_components: json!({}),
+        };
+        let mut full = analysis();
+        full.name = Some("Apple Inc.".to_string());
+        full.current_price = Some(190.0);
+        // Metrics outside the export list must not add or shift columns
+        full.technical = Some(result(json!({"rsi": 61.5, "adx": 25.0, "extra": 1.0})));
+        full.fundamental = Some(result(json!({"pe_ratio": 28.0, "sector": "Tech"})));
+        full.quantitative = Some(result(json!({"beta": 1.1, "extra": 1.0})));
+        full.sentiment = Some(result(json!({"article_count": 12})));
+        full.conviction_tier = Some("HIGH".to_string());
+        full.market_regime = Some("bull".to_string());
+        full.multi_timeframe_alignment = Some("aligned_bullish".to_string());
+        full.rs_rating = Some(88);
+        full.days_to_earnings = Some(20);
+        full.suggested_position_pct = Some(4.0);
+        full.engine_errors = vec![("quant".to_string(), "timeout".to_string())];
+        full.low_quality_universe = true;
+
+        let empty = analysis().to_flat_record();
+        let full = full.to_flat_record();
+        let keys = |record: &IndexMap<String, String>| record.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys(&empty), keys(&full));
+        assert_eq!(keys(&full), FLAT_COLUMNS);
+
+        assert_eq!(empty["technical_rsi"], "");
+        assert_eq!(full["technical_rsi"], "61.5");
+        assert_eq!(full["technical_signal"], "Buy");
+        assert_eq!(full["fundamental_sector"], "Tech");
+        assert_eq!(full["sentiment_article_count"], "12");
+        assert_eq!(full["engine_errors"], "quant: timeout");
+        assert_eq!(empty["low_quality_universe"], "false");
+        assert_eq!(full["low_quality_universe"], "true");
+    }
+
+    #[test]
+    fn test_asset_class_from_symbol() {
+        assert_eq!(AssetClass::from_symbol("X:BTCUSD"), AssetClass::Crypto);
+        assert_eq!(AssetClass::from_symbol(" c:eurusd"), AssetClass::Forex);
+        assert_eq!(AssetClass::from_symbol("AAPL"), AssetClass::Equity);
+        assert_eq!(AssetClass::from_symbol("X:"), AssetClass::Equity);
+        // Byte 2 falls inside a multi-byte character
+        assert_eq!(AssetClass::from_symbol("aé"), AssetClass::Equity);
+        assert_eq!(AssetClass::from_symbol("é:BTC"), AssetClass::Equity);
+    }
 }
@@ -0,0 +1,348 @@
+use super::{AnalysisOrchestrator, BATCH_CONCURRENCY};
+use crate::correlation::pearson;
+use analysis_core::{AnalysisError, Bar, SignalStrength, Timeframe};
+use chrono::{DateTime, Duration, Months, Utc};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Spacing of the as-of dates a backtest walks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BacktestStep {
+    Weekly,
+    Monthly,
+}
+
+impl BacktestStep {
+    fn next(self, date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            BacktestStep::Weekly => Some(date + Duration::days(7)),
+            BacktestStep::Monthly => date.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    pub step: BacktestStep,
+    /// Calendar days between the as-of date and the forward return measurement
+    pub horizon_days: i64,
+    /// Timeframe and history passed to each `analyze_as_of` call
+    pub timeframe: Timeframe,
+    pub days_back: i64,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            step: BacktestStep::Weekly,
+            horizon_days: 30,
+            timeframe: Timeframe::Day1,
+            days_back: 365,
+        }
+    }
+}
+
+/// One as-of analysis and what the price did afterwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestSample {
+    pub as_of: DateTime<Utc>,
+    pub signal: SignalStrength,
+    pub confidence: f64,
+    /// Last close at or before `as_of`
+    pub entry_price: Option<f64>,
+    /// Percent return from `entry_price` to the last close within the horizon;
+    /// `None` when the horizon has not elapsed yet or prices are missing
+    pub forward_return: Option<f64>,
+}
+
+/// Forward-return statistics for every sample that produced one signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalBucket {
+    pub signal: SignalStrength,
+    pub count: usize,
+    /// Mean forward return in percent
+    pub avg_forward_return: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub symbol: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub step: BacktestStep,
+    pub horizon_days: i64,
+    pub samples: Vec<BacktestSample>,
+    /// Samples with a measured forward return
+    pub scored_samples: usize,
+    /// Share of non-neutral signals whose direction matched the forward return
+    pub hit_rate: Option<f64>,
+    /// Strongest buy to strongest sell; signals never produced are omitted
+    pub buckets: Vec<SignalBucket>,
+    /// Spearman rank correlation between signal score and forward return
+    pub information_coefficient: Option<f64>,
+    /// As-of dates whose analysis failed, with the error
+    pub errors: Vec<(DateTime<Utc>, String)>,
+}
+
+impl BacktestReport {
+    fn from_samples(
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        config: &BacktestConfig,
+        samples: Vec<BacktestSample>,
+        errors: Vec<(DateTime<Utc>, String)>,
+    ) -> Self {
+        let scored: Vec<(i32, f64)> = samples
+            .iter()
+            .filter_map(|s| s.forward_return.map(|r| (s.signal.to_score(), r)))
+            .collect();
+
+        let directional: Vec<&(i32, f64)> =
+            scored.iter().filter(|(score, _)| *score != 0).collect();
+        let hits = directional
+            .iter()
+            .filter(|(score, ret)| (*score > 0 && *ret > 0.0) || (*score < 0 && *ret < 0.0))
+            .count();
+        let hit_rate = (!directional.is_empty()).then(|| hits as f64 / directional.len() as f64);
+
+        let buckets = [
+            SignalStrength::StrongBuy,
+            SignalStrength::Buy,
+            SignalStrength::WeakBuy,
+            SignalStrength::Neutral,
+            SignalStrength::WeakSell,
+            SignalStrength::Sell,
+            SignalStrength::StrongSell,
+        ]
+        .into_iter()
+        .filter_map(|signal| {
+            let returns: Vec<f64> = scored
+                .iter()
+                .filter(|(score, _)| *score == signal.to_score())
+                .map(|(_, r)| *r)
+                .collect();
+            (!returns.is_empty()).then(|| SignalBucket {
+                signal,
+                count: returns.len(),
+                avg_forward_return: returns.iter().sum::<f64>() / returns.len() as f64,
+            })
+        })
+        .collect();
+
+        let scores: Vec<f64> = scored.iter().map(|(s, _)| *s as f64).collect();
+        let returns: Vec<f64> = scored.iter().map(|(_, r)| *r).collect();
+        let information_coefficient = pearson(&average_ranks(&scores), &average_ranks(&returns));
+
+        Self {
+            symbol: symbol.to_string(),
+            start,
+            end,
+            step: config.step,
+            horizon_days: config.horizon_days,
+            scored_samples: scored.len(),
+            samples,
+            hit_rate,
+            buckets,
+            information_coefficient,
+            errors,
+        }
+    }
+}
+
+/// Walks `analyze_as_of` through a date range and scores the signals against
+/// the returns that followed.
+pub struct Backtester {
+    orchestrator: Arc<AnalysisOrchestrator>,
+    config: BacktestConfig,
+}
+
+impl Backtester {
+    pub fn new(orchestrator: Arc<AnalysisOrchestrator>) -> Self {
+        Self {
+            orchestrator,
+            config: BacktestConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: BacktestConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Analyze `symbol` as of every step from `start` through `end` and measure
+    /// each signal's forward return over the configured horizon. Prices come from
+    /// one daily bar fetch spanning the whole range plus the horizon.
+    pub async fn run(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<BacktestReport, AnalysisError> {
+        if end < start {
+            return Err(AnalysisError::InvalidData(format!(
+                "backtest end {} is before start {}",
+                end, start
+            )));
+        }
+        let horizon = Duration::days(self.config.horizon_days.max(1));
+
+        let mut dates = vec![start];
+        while let Some(next) = dates.last().and_then(|d| self.config.step.next(*d)) {
+            if next > end {
+                break;
+            }
+            dates.push(next);
+        }
+
+        let price_end = (end + horizon).min(Utc::now());
+        let bars = self
+            .orchestrator
+            .get_bars_as_of(
+                symbol,
+                Timeframe::Day1,
+                price_end,
+                (price_end - start).num_days() + 7,
+            )
+            .await?;
+
+        tracing::info!(
+            "Backtesting {} over {} as-of dates ({:?}, {}d horizon)",
+            symbol,
+            dates.len(),
+            self.config.step,
+            horizon.num_days()
+        );
+
+        let results: Vec<_> = stream::iter(dates)
+            .map(|as_of| async move {
+                let analysis = self
+                    .orchestrator
+                    .analyze_as_of(symbol, self.config.timeframe, as_of, self.config.days_back)
+                    .await;
+                (as_of, analysis)
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut samples = Vec::with_capacity(results.len());
+        let mut errors = Vec::new();
+        for (as_of, analysis) in results {
+            let analysis = match analysis {
+                Ok(a) => a,
+                Err(e) => {
+                    errors.push((as_of, e.to_string()));
+                    continue;
+                }
+            };
+            let entry = last_bar_at(&bars, as_of);
+            let exit = (as_of + horizon <= price_end)
+                .then(|| last_bar_at(&bars, as_of + horizon))
+                .flatten();
+            let forward_return = match (entry, exit) {
+                (Some(entry), Some(exit)) if exit.timestamp > entry.timestamp => {
+                    Some((exit.close / entry.close - 1.0) * 100.0)
+                }
+                _ => None,
+            };
+            samples.push(BacktestSample {
+                as_of,
+                signal: analysis.overall_signal,
+                confidence: analysis.overall_confidence,
+                entry_price: entry.map(|b| b.close),
+                forward_return,
+            });
+        }
+
+        Ok(BacktestReport::from_samples(
+            symbol,
+            start,
+            end,
+            &self.config,
+            samples,
+            errors,
+        ))
+    }
+}
+
+/// Last bar with a positive close at or before `at`
+fn last_bar_at(bars: &[Bar], at: DateTime<Utc>) -> Option<&Bar> {
+    bars.iter()
+        .filter(|b| b.timestamp <= at && b.close > 0.0)
+        .max_by_key(|b| b.timestamp)
+}
+
+/// 1-based ranks with ties sharing their average rank
+fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &idx in &order[i..=j] {
+            ranks[idx] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(signal: SignalStrength, forward_return: Option<f64>) -> BacktestSample {
+        BacktestSample {
+            as_of: Utc::now(),
+            signal,
+            confidence: 0.6,
+            entry_price: Some(100.0),
+            forward_return,
+        }
+    }
+
+    #[test]
+    fn test_report_scores_signals_against_forward_returns() {
+        let samples = vec![
+            sample(SignalStrength::Buy, Some(4.0)),
+            sample(SignalStrength::Buy, Some(2.0)),
+            sample(SignalStrength::Neutral, Some(0.5)),
+            sample(SignalStrength::Sell, Some(1.0)),
+            sample(SignalStrength::StrongSell, Some(-3.0)),
+            sample(SignalStrength::StrongBuy, None),
+        ];
+        let now = Utc::now();
+        let report = BacktestReport::from_samples(
+            "TEST",
+            now,
+            now,
+            &BacktestConfig::default(),
+            samples,
+            Vec::new(),
+        );
+
+        assert_eq!(report.scored_samples, 5);
+        // Buy, Buy, StrongSell right; Sell wrong; Neutral not counted
+        assert!((report.hit_rate.unwrap() - 0.75).abs() < 1e-9);
+        assert_eq!(report.buckets.len(), 4);
+        assert_eq!(report.buckets[0].signal, SignalStrength::Buy);
+        assert!((report.buckets[0].avg_forward_return - 3.0).abs() < 1e-9);
+        assert!(report.information_coefficient.unwrap() > 0.5);
+    }
+
+    #[test]
+    fn test_average_ranks_ties() {
+        assert_eq!(
+            average_ranks(&[3.0, 1.0, 3.0, 2.0]),
+            vec![3.5, 1.0, 3.5, 2.0]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use analysis_core::Bar;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Pairwise Pearson correlations of per-bar returns for a basket of symbols
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationMatrix {
+    /// Row/column order of `matrix`
+    pub symbols: Vec<String>,
+    /// Symmetric matrix with a unit diagonal; pairs with no return variance are 0.0
+    pub matrix: Vec<Vec<f64>>,
+    /// Number of aligned returns each correlation is computed from
+    pub sample_size: usize,
+}
+
+impl CorrelationMatrix {
+    /// Align every series on the timestamps they all share, then correlate
+    /// close-to-close returns over those common bars.
+    pub fn from_bars(series: &[(String, Vec<Bar>)]) -> Self {
+        let closes: Vec<HashMap<i64, f64>> = series
+            .iter()
+            .map(|(_, bars)| {
+                bars.iter()
+                    .filter(|b| b.close > 0.0)
+                    .map(|b| (b.timestamp.timestamp(), b.close))
+                    .collect()
+            })
+            .collect();
+
+        let common: BTreeSet<i64> = match closes.split_first() {
+            Some((first, rest)) => first
+                .keys()
+                .filter(|t| rest.iter().all(|c| c.contains_key(t)))
+                .copied()
+                .collect(),
+            None => BTreeSet::new(),
+        };
+
+        let returns: Vec<Vec<f64>> = closes
+            .iter()
+            .map(|c| {
+                let aligned: Vec<f64> = common.iter().map(|t| c[t]).collect();
+                aligned.windows(2).map(|w| w[1] / w[0] - 1.0).collect()
+            })
+            .collect();
+
+        let n = series.len();
+        let mut matrix = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            matrix[i][i] = 1.0;
+            for j in (i + 1)..n {
+                let rho = pearson(&returns[i], &returns[j]).unwrap_or(0.0);
+                matrix[i][j] = rho;
+                matrix[j][i] = rho;
+            }
+        }
+
+        Self {
+            symbols: series.iter().map(|(s, _)| s.clone()).collect(),
+            matrix,
+            sample_size: common.len().saturating_sub(1),
+        }
+    }
+}
+
+/// Pearson correlation, `None` when either side is too short or constant
+pub(crate) fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return None;
+    }
+    let mean_a = a[..n].iter().sum::<f64>() / n as f64;
+    let mean_b = b[..n].iter().sum::<f64>() / n as f64;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a[..n].iter().zip(&b[..n]) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return None;
+    }
+    Some((cov / (var_a.sqrt() * var_b.sqrt())).clamp(-1.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    /// Daily bars starting on 2024-01-`first_day` with the given closes
+    fn series(symbol: &str, first_day: u32, closes: &[f64]) -> (String, Vec<Bar>) {
+        let bars = closes
+            .iter()
+            .zip(first_day..)
+            .map(|(&close, day)| Bar {
+                timestamp: Utc.with_ymd_and_hms(2024, 1, day, 21, 0, 0).unwrap(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1_000.0,
+                vwap: None,
+            })
+            .collect();
+        (symbol.to_string(), bars)
+    }
+
+    const CLOSES: [f64; 6] = [100.0, 102.0, 101.0, 105.0, 104.0, 108.0];
+
+    #[test]
+    fn test_perfectly_correlated_and_anti_correlated() {
+        let doubled: Vec<f64> = CLOSES.iter().map(|c| c * 2.0).collect();
+        // Each return is the negative of CLOSES' return over the same bar
+        let mirrored: Vec<f64> = std::iter::once(50.0)
+            .chain(CLOSES.windows(2).scan(50.0, |price, w| {
+                *price *= 2.0 - w[1] / w[0];
+                Some(*price)
+            }))
+            .collect();
+
+        let corr = CorrelationMatrix::from_bars(&[
+            series("A", 1, &CLOSES),
+            series("B", 1, &doubled),
+            series("C", 1, &mirrored),
+        ]);
+        assert_eq!(corr.symbols, vec!["A", "B", "C"]);
+        assert_eq!(corr.sample_size, 5);
+        for i in 0..3 {
+            assert_eq!(corr.matrix[i][i], 1.0);
+        }
+        assert!((corr.matrix[0][1] - 1.0).abs() < 1e-9);
+        assert!((corr.matrix[0][2] + 1.0).abs() < 1e-9);
+        assert!((corr.matrix[1][2] + 1.0).abs() < 1e-9);
+        assert_eq!(corr.matrix[2][0], corr.matrix[0][2]);
+    }
+
+    #[test]
+    fn test_aligns_on_shared_timestamps() {
+        // B starts two days later and runs two days past A; only Jan 3–6 overlap
+        let mut later = CLOSES[2..].to_vec();
+        later.extend([90.0, 120.0]);
+
+        let corr = CorrelationMatrix::from_bars(&[series("A", 1, &CLOSES), series("B", 3, &later)]);
+        assert_eq!(corr.sample_size, 3);
+        assert!((corr.matrix[0][1] - 1.0).abs() < 1e-9);
+
+        // A missing bar in one series drops that day from the alignment
+        let (symbol, mut gappy) = series("B", 1, &CLOSES);
+        gappy.remove(3);
+        let corr = CorrelationMatrix::from_bars(&[series("A", 1, &CLOSES), (symbol, gappy)]);
+        assert_eq!(corr.sample_size, 4);
+
+        // No shared bars at all
+        let corr =
+            CorrelationMatrix::from_bars(&[series("A", 1, &CLOSES), series("B", 20, &CLOSES)]);
+        assert_eq!(corr.sample_size, 0);
+        assert_eq!(corr.matrix[0][1], 0.0);
+    }
+
+    #[test]
+    fn test_too_few_bars_correlate_as_zero() {
+        // Two bars make one return, too few for a correlation
+        let corr = CorrelationMatrix::from_bars(&[
+            series("A", 1, &CLOSES[..2]),
+            series("B", 1, &CLOSES[..2]),
+        ]);
+        assert_eq!(corr.sample_size, 1);
+        assert_eq!(corr.matrix, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        assert_eq!(pearson(&[0.01], &[0.02]), None);
+        // No variance on one side
+        assert_eq!(pearson(&[0.01, 0.01, 0.01], &[0.01, 0.02, 0.03]), None);
+    }
+}
@@ -1,22 +1,41 @@
 use analysis_core::{
-    adaptive, AnalysisError, AnalysisResult, AnalystConsensusData, Bar, Financials, NewsArticle,
-    SentimentAnalyzer, SignalStrength, Timeframe, UnifiedAnalysis,
+    adaptive, AnalysisError, AnalysisResult, AnalystConsensusData, AssetClass, Bar, Clock,
+    Financials, FixedClock, MacroContext, MarketRegimeDetail, NewsArticle, NewsSource,
+    SentimentAnalyzer, SignalStrength, SystemClock, Timeframe, UnifiedAnalysis,
 };
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use fundamental_analysis::FundamentalAnalysisEngine;
 use ml_client::SignalModelsClient;
-use polygon_client::{PolygonClient, TickerDetails};
+use polygon_client::{FinnhubNewsSource, PolygonClient, PolygonNewsSource, TickerDetails};
 use quant_analysis::QuantAnalysisEngine;
-use sentiment_analysis::SentimentAnalysisEngine;
+use sentiment_analysis::{SentimentAnalysisEngine, SentimentVelocityCalculator};
 use serde_json::json;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use technical_analysis::TechnicalAnalysisEngine;
 
+pub mod backtest;
+pub mod correlation;
+mod macro_overlay;
+mod ml_breaker;
+pub mod portfolio;
+mod premarket;
+pub mod regime_weights;
+pub mod rs_rating;
 pub mod screener;
+mod snapshot_cache;
+pub use backtest::{BacktestConfig, BacktestReport, BacktestStep, Backtester};
+pub use correlation::CorrelationMatrix;
+use ml_breaker::MlCircuitBreaker;
+pub use portfolio::{PortfolioAnalysis, PortfolioHolding};
+pub use regime_weights::{EngineWeights, RegimeWeights};
+pub use rs_rating::RsUniverse;
 pub use screener::{
     ScreenerFilters, ScreenerResult, StockScreener, StockSuggestion, StockUniverse,
 };
+use snapshot_cache::SnapshotCache;
 
 /// Internal cache entry with timestamp
 struct CacheEntry<T> {
@@ -30,25 +49,411 @@ pub struct AnalysisOrchestrator {
     fundamental_analyzer: FundamentalAnalysisEngine,
     quant_analyzer: QuantAnalysisEngine,
     sentiment_analyzer: SentimentAnalysisEngine,
+    /// Feeds queried concurrently by `get_news`
+    news_sources: Vec<Box<dyn NewsSource>>,
     /// Optional ML signal models client for dynamic weights
     signal_models_client: Option<SignalModelsClient>,
+    /// Skips the signal models client while it is failing or timing out
+    signal_models_breaker: MlCircuitBreaker,
     /// Optional database pool for logging analysis features
     db_pool: Option<sqlx::AnyPool>,
-    /// Cache news articles per symbol (5-min TTL)
+    /// Keep at most one feature row per symbol per day, the latest overwriting earlier ones
+    feature_dedup: bool,
+    /// Scale each engine's weight in the overall signal by that engine's confidence
+    confidence_weighting: bool,
+    /// Engine weights per market regime, used when ML weights are unavailable
+    regime_weights: RegimeWeights,
+    /// Cache TTLs per data type
+    cache_config: CacheConfig,
+    /// Data each engine needs before it runs
+    engine_minimums: EngineMinimums,
+    /// Confidence penalty curve for disagreeing engines
+    conflict_penalty: ConflictPenalty,
+    /// Kelly fraction, position cap and volatility target for suggested sizes
+    position_sizing: PositionSizing,
+    /// Microcap / penny-stock guard, off unless configured
+    quality_floor: Option<QualityFloor>,
+    /// Time source for analysis timestamps and day counts; cache TTLs and data
+    /// fetch windows always use the wall clock
+    clock: Arc<dyn Clock>,
+    /// Cache news articles per symbol
     news_cache: DashMap<String, CacheEntry<Vec<NewsArticle>>>,
-    /// Cache bars per (symbol, timeframe_key, days) (5-min TTL)
+    /// Cache bars per (symbol, timeframe_key, days)
     bars_cache: DashMap<String, CacheEntry<Vec<Bar>>>,
     /// Secondary index for fast superset lookup: "AAPL:1:day" -> [30, 90, 365]
     bars_days_index: DashMap<String, Vec<i64>>,
-    /// Cache ticker details per symbol (5-min TTL)
+    /// When each `bars_cache` entry last had its whole history fetched
+    bars_full_fetch_at: DashMap<String, DateTime<Utc>>,
+    /// Cache extended-hours intraday bars per symbol for the pre-market signal
+    premarket_bars_cache: DashMap<String, CacheEntry<Vec<Bar>>>,
+    /// Cache ticker details per symbol
     ticker_details_cache: DashMap<String, CacheEntry<TickerDetails>>,
-    /// Cache financials per symbol (5-min TTL)
+    /// Cache financials per symbol
     financials_cache: DashMap<String, CacheEntry<Vec<Financials>>>,
-    /// Cache analyst consensus per symbol (5-min TTL)
+    /// Cache analyst consensus per symbol
     consensus_cache: DashMap<String, CacheEntry<AnalystConsensusData>>,
+    /// Shared all-tickers snapshot for RS ranking and universe pre-filtering
+    snapshot_cache: SnapshotCache,
+    /// Market-wide relative strength ranking, rebuilt every `RS_UNIVERSE_TTL_SECS`
+    rs_universe: Arc<tokio::sync::RwLock<Option<Arc<RsUniverse>>>>,
+    /// Set while a background RS universe rebuild is in flight
+    rs_refreshing: Arc<AtomicBool>,
+    /// Let `analyze` kick off the market-wide RS universe rebuild when missing or stale
+    rs_background_refresh: bool,
 }
 
-const CACHE_TTL_SECS: i64 = 300; // 5 minutes
+/// Benchmark and macro inputs shared by every symbol in an analysis run
+struct MarketContext {
+    spy_bars: Option<Vec<Bar>>,
+    /// Symbol that beta and the relative-return metrics are measured against
+    benchmark_symbol: String,
+    /// Bars of a non-SPY benchmark, `None` when SPY is the benchmark
+    benchmark_bars: Option<Vec<Bar>>,
+    iwm_bars: Option<Vec<Bar>>,
+    iwd_bars: Option<Vec<Bar>>,
+    iwf_bars: Option<Vec<Bar>>,
+    risk_free_rate: Option<f64>,
+    market_regime: Option<String>,
+    market_regime_detail: Option<MarketRegimeDetail>,
+    macro_context: Option<MacroContext>,
+}
+
+/// Per-symbol API responses feeding a single analysis
+struct SymbolData {
+    bars_result: Result<Vec<Bar>, AnalysisError>,
+    financials_result: Result<Vec<Financials>, AnalysisError>,
+    news_result: Result<Vec<NewsArticle>, AnalysisError>,
+    /// Longer news window for the daily sentiment trend, empty when unavailable
+    trend_news: Vec<NewsArticle>,
+    ticker_details: Result<TickerDetails, AnalysisError>,
+    snapshot_result: Result<polygon_client::SnapshotTicker, AnalysisError>,
+    /// Weekly bars for multi-timeframe confirmation, only fetched for daily analysis
+    weekly_bars: Option<Vec<Bar>>,
+    /// Bar timeframe, which sets the annualization basis
+    timeframe: Timeframe,
+    /// Inferred from the ticker prefix; non-equities skip company data
+    asset_class: AssetClass,
+    /// Point-in-time cutoff, `None` for live analysis
+    as_of: Option<DateTime<Utc>>,
+}
+
+/// Maximum number of symbols analyzed concurrently by `analyze_batch`
+const BATCH_CONCURRENCY: usize = 10;
+
+/// Benchmark used for beta and relative metrics when none is requested
+const DEFAULT_BENCHMARK: &str = "SPY";
+
+/// Volatility percentiles above/below which the market is high/low vol
+const REGIME_HIGH_VOL_PCT: f64 = 0.85;
+const REGIME_LOW_VOL_PCT: f64 = 0.15;
+
+/// (SMA-distance, momentum) percentiles a bull trend must exceed
+const REGIME_BULL_PCTS: (f64, f64) = (0.70, 0.65);
+
+/// (SMA-distance, momentum) percentiles a bear trend must fall below
+const REGIME_BEAR_PCTS: (f64, f64) = (0.30, 0.35);
+
+/// Percentile distance from a regime threshold within which a flip is considered likely
+const REGIME_TRANSITION_BAND: f64 = 0.10;
+
+/// Rolling window (days) in which insider buys count as one cluster
+const INSIDER_CLUSTER_WINDOW_DAYS: i64 = 10;
+
+/// Distinct insiders buying inside one window needed to flag clustered buying
+const INSIDER_CLUSTER_MIN_INSIDERS: usize = 3;
+
+/// Price within this fraction of the 52-week high/low counts as "near" it
+const RANGE_PROXIMITY_PCT: f64 = 0.03;
+
+/// Earnings within this many days put the analysis in blackout
+const EARNINGS_BLACKOUT_DAYS: i64 = 5;
+
+/// Multiplier applied to overall confidence during an earnings blackout
+const EARNINGS_BLACKOUT_CONFIDENCE_FACTOR: f64 = 0.85;
+
+/// Calendar days of weekly bars fetched for multi-timeframe confirmation (~100 weeks,
+/// enough for the technical engine's 50-bar minimum)
+const WEEKLY_CONFIRMATION_DAYS: i64 = 730;
+
+/// Multiplier applied to overall confidence when daily and weekly technicals disagree
+const TIMEFRAME_DIVERGENCE_CONFIDENCE_FACTOR: f64 = 0.85;
+
+/// Bars of trailing return compared against news tone for sentiment divergence
+const SENTIMENT_DIVERGENCE_LOOKBACK_BARS: usize = 10;
+
+/// Minimum |normalized sentiment| (−100..100) for news to count as clearly good or bad
+const SENTIMENT_DIVERGENCE_MIN_SCORE: f64 = 30.0;
+
+/// Trailing-return percentiles (vs the stock's own rolling returns) below which
+/// price is failing and above which it is resilient
+const SENTIMENT_DIVERGENCE_RETURN_PCTS: (f64, f64) = (0.4, 0.6);
+
+/// Articles fetched to build the daily sentiment history behind velocity and
+/// narrative-shift detection
+const SENTIMENT_TREND_NEWS_LIMIT: u32 = 200;
+
+/// Newest articles, out of the trend fetch, behind the headline sentiment score
+const SENTIMENT_HEADLINE_NEWS_LIMIT: usize = 50;
+
+/// Dividend payments fetched for supplementary signals (~25 years of quarterlies)
+const DIVIDEND_HISTORY_LIMIT: u32 = 100;
+
+/// Most recent payments the dividend health (cut/increase) signals look at
+const DIVIDEND_HEALTH_PAYMENTS: usize = 20;
+
+/// Years over which dividend growth CAGR is measured for the Chowder Rule
+const DIVIDEND_CAGR_YEARS: usize = 5;
+
+/// Chowder number (yield % + 5-year dividend CAGR %) a non-utility should reach
+const CHOWDER_THRESHOLD: f64 = 12.0;
+
+/// Lower Chowder bar for utilities, whose regulated payouts grow slowly
+const UTILITY_CHOWDER_THRESHOLD: f64 = 8.0;
+
+/// Consecutive years of dividend increases that make an aristocrat candidate
+const DIVIDEND_ARISTOCRAT_MIN_STREAK: usize = 10;
+
+/// Trailing bars averaged for the dollar-volume liquidity tier
+const LIQUIDITY_LOOKBACK_BARS: usize = 20;
+
+/// Relative bid-ask spread (fraction of mid) above which a quote counts as wide
+const WIDE_SPREAD_PCT: f64 = 0.01;
+
+/// Average daily dollar volume below which a stock is too thin to trade size
+const MIN_LIQUID_DOLLAR_VOLUME: f64 = 1_000_000.0;
+
+/// Strikes further than this fraction from spot can't stand in for at-the-money
+const ATM_MAX_DISTANCE_PCT: f64 = 0.05;
+
+/// Contracts an expiry needs before its ATM IV counts toward the term structure
+const MIN_TERM_STRUCTURE_CONTRACTS: usize = 6;
+
+/// Volume / open interest above which a contract's trading counts as unusual
+const UNUSUAL_OPTIONS_VOL_OI_RATIO: f64 = 3.0;
+
+/// Minimum session volume for unusual activity, so one-lot trades in dead strikes don't count
+const UNUSUAL_OPTIONS_MIN_VOLUME: i64 = 500;
+
+/// Unusual contracts listed in the options signal
+const UNUSUAL_OPTIONS_TOP_N: usize = 5;
+
+/// IV change per 30 days of expiry inside which the term structure reads as flat
+const TERM_STRUCTURE_FLAT_BAND: f64 = 0.005;
+
+/// Per-data-type cache lifetimes, in seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheConfig {
+    pub news_ttl: i64,
+    pub bars_ttl: i64,
+    pub financials_ttl: i64,
+    pub consensus_ttl: i64,
+    pub ticker_details_ttl: i64,
+    pub all_snapshots_ttl: i64,
+    /// Stale bar entries missing at most this many bars are topped up with only
+    /// the new bars; longer gaps refetch the whole history
+    pub bars_incremental_max_bars: i64,
+    /// Topped-up bar entries are refetched in full once their last full fetch
+    /// is this old, so split and dividend restatements reach the cached history
+    pub bars_full_refresh_secs: i64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            news_ttl: 300, // 5 minutes
+            bars_ttl: 300,
+            financials_ttl: 300,
+            consensus_ttl: 300,
+            ticker_details_ttl: 300,
+            all_snapshots_ttl: 60, // Whole-market prices go stale fast during the session
+            bars_incremental_max_bars: 500,
+            bars_full_refresh_secs: 86_400, // Daily
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Where to resume a stale bar entry from, or `None` when it needs a full
+    /// refetch: its last full fetch is past `bars_full_refresh_secs`, or the
+    /// gap since its last bar exceeds `bars_incremental_max_bars`.
+    fn bars_top_up_from(
+        &self,
+        cached: &[Bar],
+        full_fetch_at: Option<DateTime<Utc>>,
+        timeframe: Timeframe,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let full_fetch_at = full_fetch_at?;
+        if (now - full_fetch_at).num_seconds() >= self.bars_full_refresh_secs {
+            return None;
+        }
+        let last = cached.last()?.timestamp;
+        let missing = (now - last).num_minutes() / timeframe.to_minutes();
+        (missing <= self.bars_incremental_max_bars).then_some(last)
+    }
+}
+
+/// Minimum input each engine needs before it runs. An engine short of its
+/// minimum is skipped and the shortfall recorded in `engine_errors`.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineMinimums {
+    pub technical_bars: usize,
+    pub quant_bars: usize,
+    pub fundamental_financials: usize,
+}
+
+impl Default for EngineMinimums {
+    fn default() -> Self {
+        Self {
+            technical_bars: 50, // 50-bar SMA and the longer oscillator lookbacks
+            quant_bars: 30,
+            fundamental_financials: 1,
+        }
+    }
+}
+
+impl EngineMinimums {
+    /// Skip reason such as "skipped: 40 bars < 50 required", `None` when there is enough
+    fn shortfall(have: usize, required: usize, unit: &str) -> Option<String> {
+        (have < required).then(|| format!("skipped: {} {} < {} required", have, unit, required))
+    }
+
+    fn technical(&self, bars: usize) -> Option<String> {
+        Self::shortfall(bars, self.technical_bars, "bars")
+    }
+
+    fn quant(&self, bars: usize) -> Option<String> {
+        Self::shortfall(bars, self.quant_bars, "bars")
+    }
+
+    fn fundamental(&self, financials: usize) -> Option<String> {
+        Self::shortfall(financials, self.fundamental_financials, "financial reports")
+    }
+}
+
+/// Confidence penalty applied when engines disagree: once at least one engine
+/// scores at or above `bullish_cutoff` and another at or below `bearish_cutoff`,
+/// confidence drops by `max_penalty` × (max score − min score) / 200.
+#[derive(Debug, Clone, Copy)]
+pub struct ConflictPenalty {
+    pub bullish_cutoff: i32,
+    pub bearish_cutoff: i32,
+    /// Penalty when one engine is a Strong Buy and another a Strong Sell
+    pub max_penalty: f64,
+}
+
+impl Default for ConflictPenalty {
+    fn default() -> Self {
+        Self {
+            bullish_cutoff: 30, // Buy or stronger
+            bearish_cutoff: -30,
+            max_penalty: 0.30,
+        }
+    }
+}
+
+impl ConflictPenalty {
+    /// Confidence to subtract for these engine scores (−100..100 each)
+    pub fn penalty(&self, scores: &[i32]) -> f64 {
+        let has_bullish = scores.iter().any(|&s| s >= self.bullish_cutoff);
+        let has_bearish = scores.iter().any(|&s| s <= self.bearish_cutoff);
+        if scores.len() < 2 || !has_bullish || !has_bearish {
+            return 0.0;
+        }
+        let max_score = scores.iter().max().copied().unwrap_or(0);
+        let min_score = scores.iter().min().copied().unwrap_or(0);
+        let spread = (max_score - min_score) as f64 / 200.0;
+        spread * self.max_penalty
+    }
+}
+
+/// Position sizing from the quant engine's Kelly fraction:
+///
+/// `size% = clamp(kelly_fraction × kelly × confidence × min(1, target_vol / vol) × 100,
+///                0, max_position_pct)`
+///
+/// where `kelly` is the full-Kelly fraction and `vol` the annualized volatility.
+/// Non-bullish signals and negative Kelly size to zero (no long position).
+#[derive(Debug, Clone, Copy)]
+pub struct PositionSizing {
+    /// Share of full Kelly to bet (0.5 = half-Kelly)
+    pub kelly_fraction: f64,
+    /// Cap on any single position, percent of portfolio
+    pub max_position_pct: f64,
+    /// Annualized volatility (percent) above which sizes shrink proportionally
+    pub target_volatility_pct: f64,
+}
+
+impl Default for PositionSizing {
+    fn default() -> Self {
+        Self {
+            kelly_fraction: 0.5,
+            max_position_pct: 10.0,
+            target_volatility_pct: 20.0,
+        }
+    }
+}
+
+impl PositionSizing {
+    /// Suggested position (percent of portfolio) for a combined analysis
+    pub fn suggested_position_pct(&self, analysis: &UnifiedAnalysis) -> Option<f64> {
+        let metrics = &analysis.quantitative.as_ref()?.metrics;
+        let kelly = metrics.get("kelly_fraction").and_then(|v| v.as_f64())?;
+        if analysis.overall_signal.to_score() <= 0 || kelly <= 0.0 {
+            return Some(0.0);
+        }
+        let vol_scale = match metrics.get("volatility").and_then(|v| v.as_f64()) {
+            Some(vol) if vol > self.target_volatility_pct => self.target_volatility_pct / vol,
+            _ => 1.0,
+        };
+        let size = self.kelly_fraction * kelly * analysis.overall_confidence * vol_scale * 100.0;
+        Some(size.clamp(0.0, self.max_position_pct))
+    }
+}
+
+/// Market cap and price floor for stocks worth analyzing. Below it, adaptive
+/// thresholds run on thin, erratic data (shell companies, sub-dollar names) and
+/// produce confident-looking noise. Only applies to equities.
+#[derive(Debug, Clone, Copy)]
+pub struct QualityFloor {
+    /// Minimum market capitalization in dollars; an unknown market cap passes.
+    /// Ticker details only carry today's market cap, so point-in-time runs
+    /// (`analyze_as_of`, backtests) are checked against the current value while
+    /// the price check uses the as-of price.
+    pub min_market_cap: f64,
+    pub min_price: f64,
+    /// Overall confidence ceiling for analyses below the floor
+    pub max_confidence: f64,
+    /// Fail the analysis instead of returning a tagged, confidence-capped one
+    pub reject: bool,
+}
+
+impl Default for QualityFloor {
+    fn default() -> Self {
+        Self {
+            min_market_cap: 50_000_000.0,
+            min_price: 1.0,
+            max_confidence: 0.3,
+            reject: false,
+        }
+    }
+}
+
+impl QualityFloor {
+    /// Why a stock falls below the floor, `None` when it clears it
+    pub fn violation(&self, price: Option<f64>, market_cap: Option<f64>) -> Option<String> {
+        if let Some(cap) = market_cap.filter(|&c| c < self.min_market_cap) {
+            return Some(format!(
+                "market cap ${:.1}M below ${:.1}M floor",
+                cap / 1e6,
+                self.min_market_cap / 1e6
+            ));
+        }
+        price
+            .filter(|&p| p < self.min_price)
+            .map(|p| format!("price ${:.2} below ${:.2} floor", p, self.min_price))
+    }
+}
 
 impl AnalysisOrchestrator {
     pub fn new(polygon_api_key: String) -> Self {
@@ -60,20 +465,43 @@ impl AnalysisOrchestrator {
             std::time::Duration::from_secs(5),
         ));
 
+        let polygon_client = PolygonClient::new(polygon_api_key);
+        let news_sources: Vec<Box<dyn NewsSource>> = vec![
+            Box::new(PolygonNewsSource::new(polygon_client.clone())),
+            Box::new(FinnhubNewsSource::new(polygon_client.clone())),
+        ];
+
         Self {
-            polygon_client: PolygonClient::new(polygon_api_key),
+            polygon_client,
             technical_analyzer: TechnicalAnalysisEngine::new(),
             fundamental_analyzer: FundamentalAnalysisEngine::new(),
             quant_analyzer: QuantAnalysisEngine::new(),
             sentiment_analyzer: SentimentAnalysisEngine::new(),
+            news_sources,
             signal_models_client,
+            signal_models_breaker: MlCircuitBreaker::default(),
             db_pool: None,
+            feature_dedup: false,
+            confidence_weighting: false,
+            regime_weights: RegimeWeights::default(),
+            cache_config: CacheConfig::default(),
+            engine_minimums: EngineMinimums::default(),
+            conflict_penalty: ConflictPenalty::default(),
+            position_sizing: PositionSizing::default(),
+            quality_floor: None,
+            clock: Arc::new(SystemClock),
             news_cache: DashMap::new(),
             bars_cache: DashMap::new(),
             bars_days_index: DashMap::new(),
+            bars_full_fetch_at: DashMap::new(),
+            premarket_bars_cache: DashMap::new(),
             ticker_details_cache: DashMap::new(),
             financials_cache: DashMap::new(),
             consensus_cache: DashMap::new(),
+            snapshot_cache: SnapshotCache::default(),
+            rs_universe: Arc::new(tokio::sync::RwLock::new(None)),
+            rs_refreshing: Arc::new(AtomicBool::new(false)),
+            rs_background_refresh: false,
         }
     }
 
@@ -83,6 +511,146 @@ impl AnalysisOrchestrator {
         self
     }
 
+    /// Replace, rather than add to, a symbol's feature row when it is analyzed
+    /// again the same day, so repeat runs don't flood the training table
+    pub fn with_feature_dedup(mut self, enabled: bool) -> Self {
+        self.feature_dedup = enabled;
+        self
+    }
+
+    /// Rebuild the market-wide RS ranking in the background whenever `analyze`
+    /// finds it missing or stale. Off by default: the rebuild pulls a year of bars
+    /// for the whole universe, so without it `rs_rating` is only filled once
+    /// `compute_rs_rating` has built the ranking.
+    pub fn with_rs_background_refresh(mut self, enabled: bool) -> Self {
+        self.rs_background_refresh = enabled;
+        self
+    }
+
+    /// Let each engine's confidence scale its weight in the overall signal, so a
+    /// low-confidence read counts for less than a high-confidence one
+    pub fn with_confidence_weighting(mut self, enabled: bool) -> Self {
+        self.confidence_weighting = enabled;
+        self
+    }
+
+    /// Replace the regime-conditional engine weight table
+    pub fn with_regime_weights(mut self, regime_weights: RegimeWeights) -> Self {
+        self.regime_weights = regime_weights;
+        self
+    }
+
+    /// Add a news feed alongside the default Polygon and Finnhub sources
+    pub fn with_news_source(mut self, source: Box<dyn NewsSource>) -> Self {
+        self.news_sources.push(source);
+        self
+    }
+
+    /// Replace the set of news feeds entirely
+    pub fn with_news_sources(mut self, sources: Vec<Box<dyn NewsSource>>) -> Self {
+        self.news_sources = sources;
+        self
+    }
+
+    /// Override the per-data-type cache TTLs
+    pub fn with_cache_config(mut self, config: CacheConfig) -> Self {
+        self.cache_config = config;
+        self
+    }
+
+    /// Override the minimum bars/financials each engine needs before it runs
+    pub fn with_engine_minimums(mut self, minimums: EngineMinimums) -> Self {
+        self.engine_minimums = minimums;
+        self
+    }
+
+    /// Override how much engine disagreement reduces overall confidence
+    pub fn with_conflict_penalty(mut self, conflict_penalty: ConflictPenalty) -> Self {
+        self.conflict_penalty = conflict_penalty;
+        self
+    }
+
+    /// Override the Kelly fraction, position cap and volatility target used for
+    /// `suggested_position_pct`
+    pub fn with_position_sizing(mut self, position_sizing: PositionSizing) -> Self {
+        self.position_sizing = position_sizing;
+        self
+    }
+
+    /// Flag (or, with `reject`, refuse) stocks below a market cap or price floor
+    pub fn with_quality_floor(mut self, quality_floor: QualityFloor) -> Self {
+        self.quality_floor = Some(quality_floor);
+        self
+    }
+
+    /// Replace the fundamental engine, e.g. to set its consensus weight or tax rate
+    pub fn with_fundamental_analyzer(
+        mut self,
+        fundamental_analyzer: FundamentalAnalysisEngine,
+    ) -> Self {
+        self.fundamental_analyzer = fundamental_analyzer;
+        self
+    }
+
+    /// Fundamental engine whose clock reads `as_of`, so filing and rating ages in
+    /// point-in-time runs are measured from the as-of date rather than today
+    fn fundamental_analyzer_at(
+        &self,
+        as_of: Option<DateTime<Utc>>,
+    ) -> std::borrow::Cow<'_, FundamentalAnalysisEngine> {
+        match as_of {
+            Some(as_of) => std::borrow::Cow::Owned(
+                self.fundamental_analyzer
+                    .clone()
+                    .with_clock(Arc::new(FixedClock(as_of))),
+            ),
+            None => std::borrow::Cow::Borrowed(&self.fundamental_analyzer),
+        }
+    }
+
+    /// Sentiment engine whose clock reads `as_of`, so recency decay, buzz and
+    /// momentum windows in point-in-time runs are measured from the as-of date
+    fn sentiment_analyzer_at(
+        &self,
+        as_of: Option<DateTime<Utc>>,
+    ) -> std::borrow::Cow<'_, SentimentAnalysisEngine> {
+        match as_of {
+            Some(as_of) => std::borrow::Cow::Owned(
+                self.sentiment_analyzer
+                    .clone()
+                    .with_clock(Arc::new(FixedClock(as_of))),
+            ),
+            None => std::borrow::Cow::Borrowed(&self.sentiment_analyzer),
+        }
+    }
+
+    /// The quality floor violated by an equity, if any
+    fn quality_violation(
+        &self,
+        asset_class: AssetClass,
+        price: Option<f64>,
+        ticker_details: Option<&TickerDetails>,
+    ) -> Option<(QualityFloor, String)> {
+        let floor = self.quality_floor.filter(|_| asset_class.is_equity())?;
+        let reason = floor.violation(price, ticker_details.and_then(|d| d.market_cap))?;
+        Some((floor, reason))
+    }
+
+    /// Inject the time source into the orchestrator and all four engines, e.g. a
+    /// `FixedClock` so regression tests get stable timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.technical_analyzer =
+            std::mem::take(&mut self.technical_analyzer).with_clock(Arc::clone(&clock));
+        self.fundamental_analyzer =
+            std::mem::take(&mut self.fundamental_analyzer).with_clock(Arc::clone(&clock));
+        self.quant_analyzer =
+            std::mem::take(&mut self.quant_analyzer).with_clock(Arc::clone(&clock));
+        self.sentiment_analyzer =
+            std::mem::take(&mut self.sentiment_analyzer).with_clock(Arc::clone(&clock));
+        self.clock = clock;
+        self
+    }
+
     /// Public accessor for the technical analysis engine (used by point-in-time backtesting)
     pub fn technical_engine(&self) -> &TechnicalAnalysisEngine {
         &self.technical_analyzer
@@ -94,10 +662,19 @@ impl AnalysisOrchestrator {
     }
 
     /// Enhanced market regime detection: combines trend direction (bull/bear) with volatility state.
-    /// Returns composite regimes like "high_vol_bear", "low_vol_bull", "normal_bull", etc.
-    fn detect_market_regime(&self, spy_bars: &[Bar]) -> String {
+    /// The label is a composite regime like "high_vol_bear", "low_vol_bull", "normal_bull", etc.,
+    /// reported with the vol, momentum, and SMA-distance percentiles behind it and a 0–1
+    /// `transition_risk` that rises as any of them nears a threshold.
+    pub fn detect_market_regime_detailed(&self, spy_bars: &[Bar]) -> MarketRegimeDetail {
+        let unknown = MarketRegimeDetail {
+            label: "unknown".to_string(),
+            vol_percentile: 0.5,
+            momentum_percentile: 0.5,
+            sma_distance_percentile: 0.5,
+            transition_risk: 1.0,
+        };
         if spy_bars.len() < 50 {
-            return "unknown".to_string();
+            return unknown;
         }
 
         let returns: Vec<f64> = spy_bars
@@ -106,7 +683,7 @@ impl AnalysisOrchestrator {
             .collect();
 
         if returns.len() < 50 {
-            return "unknown".to_string();
+            return unknown;
         }
 
         // Full-period volatility
@@ -142,9 +719,9 @@ impl AnalysisOrchestrator {
             1.0
         };
         let vol_pct = adaptive::percentile_rank(current_ratio, &vol_ratios);
-        let vol_regime = if vol_pct > 0.85 {
+        let vol_regime = if vol_pct > REGIME_HIGH_VOL_PCT {
             "high_vol"
-        } else if vol_pct < 0.15 {
+        } else if vol_pct < REGIME_LOW_VOL_PCT {
             "low_vol"
         } else {
             "normal"
@@ -193,32 +770,44 @@ impl AnalysisOrchestrator {
         }
         let sma_pct = adaptive::percentile_rank(sma_dist, &sma_dists);
 
-        let trend = if sma_pct > 0.70 && momentum_pct > 0.65 {
+        let trend = if sma_pct > REGIME_BULL_PCTS.0 && momentum_pct > REGIME_BULL_PCTS.1 {
             "bull"
-        } else if sma_pct < 0.30 && momentum_pct < 0.35 {
+        } else if sma_pct < REGIME_BEAR_PCTS.0 && momentum_pct < REGIME_BEAR_PCTS.1 {
             "bear"
         } else {
             "sideways"
         };
 
-        format!("{}_{}", vol_regime, trend)
+        // Closeness of each percentile to the nearest threshold it is compared against
+        let proximity = |pct: f64, thresholds: &[f64]| {
+            thresholds
+                .iter()
+                .map(|t| 1.0 - ((pct - t).abs() / REGIME_TRANSITION_BAND).min(1.0))
+                .fold(0.0, f64::max)
+        };
+        let transition_risk = proximity(vol_pct, &[REGIME_LOW_VOL_PCT, REGIME_HIGH_VOL_PCT])
+            .max(proximity(
+                sma_pct,
+                &[REGIME_BEAR_PCTS.0, REGIME_BULL_PCTS.0],
+            ))
+            .max(proximity(
+                momentum_pct,
+                &[REGIME_BEAR_PCTS.1, REGIME_BULL_PCTS.1],
+            ));
+
+        MarketRegimeDetail {
+            label: format!("{}_{}", vol_regime, trend),
+            vol_percentile: vol_pct,
+            momentum_percentile: momentum_pct,
+            sma_distance_percentile: sma_pct,
+            transition_risk,
+        }
     }
 
     /// Get regime-conditional default engine weights.
     /// Returns (technical, fundamental, quant, sentiment) as percentages.
-    fn regime_default_weights(&self, regime: &str) -> (i32, i32, i32, i32) {
-        match regime {
-            "high_vol_bear" => (15, 30, 35, 20), // Lean on risk/quant in volatile downtrends
-            "high_vol_bull" => (25, 25, 30, 20), // Quant risk still important in volatile uptrends
-            "high_vol_sideways" => (15, 30, 35, 20),
-            "low_vol_bull" => (30, 30, 15, 25), // Technical momentum + sentiment in calm uptrends
-            "low_vol_bear" => (20, 40, 20, 20), // Fundamental value focus in slow decline
-            "low_vol_sideways" => (25, 35, 20, 20),
-            "normal_bull" => (25, 35, 15, 25), // Balanced with slight fundamental tilt
-            "normal_bear" => (20, 35, 25, 20), // More quant risk-awareness
-            "normal_sideways" => (20, 40, 15, 25), // Standard balanced
-            _ => (20, 40, 15, 25),             // Fallback
-        }
+    fn regime_default_weights(&self, regime: &str) -> EngineWeights {
+        self.regime_weights.get(regime)
     }
 
     /// Compute conviction tier based on engine alignment and confidence.
@@ -325,6 +914,92 @@ impl AnalysisOrchestrator {
         serde_json::Value::Object(horizons)
     }
 
+    /// Fetch the benchmark/regime inputs (SPY, TLT, IWM, IWD, IWF) and the macro
+    /// proxies (GLD, UUP, VIXY), and derive the risk-free rate, market regime and
+    /// macro backdrop from them. With `as_of` set, only bars up to that instant are used.
+    async fn fetch_market_context(&self, as_of: Option<DateTime<Utc>>) -> MarketContext {
+        let (
+            (spy_bars_result, tlt_bars_result, iwm_bars_result, iwd_bars_result, iwf_bars_result),
+            (gld_bars, uup_bars, vix_bars),
+        ) = tokio::join!(
+            async {
+                tokio::join!(
+                    self.get_bars_at("SPY", Timeframe::Day1, 365, as_of),
+                    self.get_bars_at(
+                        macro_overlay::RATES_PROXY,
+                        Timeframe::Day1,
+                        macro_overlay::MACRO_HISTORY_DAYS,
+                        as_of
+                    ),
+                    self.get_bars_at("IWM", Timeframe::Day1, 365, as_of),
+                    self.get_bars_at("IWD", Timeframe::Day1, 365, as_of),
+                    self.get_bars_at("IWF", Timeframe::Day1, 365, as_of),
+                )
+            },
+            async {
+                let days = macro_overlay::MACRO_HISTORY_DAYS;
+                let (gld, uup, vix) = tokio::join!(
+                    self.get_bars_at(macro_overlay::GOLD_PROXY, Timeframe::Day1, days, as_of),
+                    self.get_bars_at(macro_overlay::DOLLAR_PROXY, Timeframe::Day1, days, as_of),
+                    self.get_bars_at(
+                        macro_overlay::VOLATILITY_PROXY,
+                        Timeframe::Day1,
+                        days,
+                        as_of
+                    ),
+                );
+                (gld.ok(), uup.ok(), vix.ok())
+            },
+        );
+        let tlt_bars = tlt_bars_result.ok();
+        let macro_context = macro_overlay::macro_context(
+            tlt_bars.as_deref(),
+            gld_bars.as_deref(),
+            uup_bars.as_deref(),
+            vix_bars.as_deref(),
+        );
+
+        // Derive dynamic risk-free rate from TLT price
+        let risk_free_rate = tlt_bars.and_then(|tlt_bars| {
+            if tlt_bars.len() >= 2 {
+                let first = tlt_bars.first().unwrap();
+                let last = tlt_bars.last().unwrap();
+                let tlt_return = (last.close - first.close) / first.close;
+                // TLT inversely tracks yields: if TLT fell, rates rose
+                let rate = (0.045 - tlt_return * 0.10).clamp(0.01, 0.08);
+                tracing::info!(
+                    "Dynamic risk-free rate from TLT: {:.3} (TLT return: {:.3})",
+                    rate,
+                    tlt_return
+                );
+                Some(rate)
+            } else {
+                None
+            }
+        });
+
+        let spy_bars = spy_bars_result.ok();
+
+        // Detect market regime from SPY bars (needed for regime-conditional weights)
+        let market_regime_detail = spy_bars
+            .as_deref()
+            .map(|spy_bars| self.detect_market_regime_detailed(spy_bars));
+        let market_regime = market_regime_detail.as_ref().map(|d| d.label.clone());
+
+        MarketContext {
+            spy_bars,
+            benchmark_symbol: DEFAULT_BENCHMARK.to_string(),
+            benchmark_bars: None,
+            iwm_bars: iwm_bars_result.ok(),
+            iwd_bars: iwd_bars_result.ok(),
+            iwf_bars: iwf_bars_result.ok(),
+            risk_free_rate,
+            market_regime,
+            market_regime_detail,
+            macro_context,
+        }
+    }
+
     /// Perform comprehensive analysis on a symbol
     pub async fn analyze(
         &self,
@@ -332,6 +1007,132 @@ impl AnalysisOrchestrator {
         timeframe: Timeframe,
         days_back: i64,
     ) -> Result<UnifiedAnalysis, AnalysisError> {
+        let (context, analysis) = tokio::join!(
+            self.fetch_market_context(None),
+            self.fetch_symbol_data(symbol, timeframe, days_back, None),
+        );
+        self.analyze_with_context(symbol, analysis, &context).await
+    }
+
+    /// Point-in-time analysis for backtesting: only data available at `as_of` is used.
+    ///
+    /// Bars end at `as_of` and the last bar close stands in for the live price.
+    /// Financials are limited to statements that would have been filed by then, news
+    /// to articles published by then, and analyst consensus is omitted. Supplementary
+    /// signals that only exist live (options snapshot, intraday and pre-market gaps,
+    /// earnings NLP) are skipped, and nothing is logged to the feature store.
+    pub async fn analyze_as_of(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        as_of: DateTime<Utc>,
+        days_back: i64,
+    ) -> Result<UnifiedAnalysis, AnalysisError> {
+        let (context, analysis) = tokio::join!(
+            self.fetch_market_context(Some(as_of)),
+            self.fetch_symbol_data(symbol, timeframe, days_back, Some(as_of)),
+        );
+        self.analyze_with_context(symbol, analysis, &context).await
+    }
+
+    /// Analyze a symbol with beta, information ratio, and the other relative
+    /// quant metrics measured against `benchmark` (e.g. XBI for a biotech)
+    /// instead of SPY. The market regime is still read from SPY. Falls back to
+    /// SPY when no benchmark is given or its bars can't be fetched.
+    pub async fn analyze_with_benchmark_symbol(
+        &self,
+        symbol: &str,
+        benchmark: Option<&str>,
+        timeframe: Timeframe,
+        days_back: i64,
+    ) -> Result<UnifiedAnalysis, AnalysisError> {
+        let benchmark = benchmark
+            .map(|b| b.trim().to_uppercase())
+            .filter(|b| !b.is_empty() && b != DEFAULT_BENCHMARK);
+        let Some(benchmark) = benchmark else {
+            return self.analyze(symbol, timeframe, days_back).await;
+        };
+
+        let (mut context, analysis, benchmark_bars) = tokio::join!(
+            self.fetch_market_context(None),
+            self.fetch_symbol_data(symbol, timeframe, days_back, None),
+            self.get_bars_at(&benchmark, Timeframe::Day1, 365, None),
+        );
+        match benchmark_bars {
+            Ok(bars) if !bars.is_empty() => {
+                context.benchmark_symbol = benchmark;
+                context.benchmark_bars = Some(bars);
+            }
+            Ok(_) => tracing::warn!("No bars for benchmark {}, using SPY", benchmark),
+            Err(e) => tracing::warn!("Benchmark {} unavailable ({}), using SPY", benchmark, e),
+        }
+        self.analyze_with_context(symbol, analysis, &context).await
+    }
+
+    /// Analyze many symbols against a single shared market context.
+    ///
+    /// Benchmark bars are fetched and the market regime detected exactly once,
+    /// instead of once per symbol as with repeated `analyze` calls. Results are
+    /// returned in the same order as `symbols`.
+    pub async fn analyze_batch(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        days_back: i64,
+    ) -> Vec<Result<UnifiedAnalysis, AnalysisError>> {
+        use futures_util::stream::{self, StreamExt};
+
+        tracing::info!("Starting batch analysis of {} symbols", symbols.len());
+        let context = self.fetch_market_context(None).await;
+
+        stream::iter(symbols.iter().copied())
+            .map(|symbol| {
+                let context = &context;
+                async move {
+                    let data = self
+                        .fetch_symbol_data(symbol, timeframe, days_back, None)
+                        .await;
+                    self.analyze_with_context(symbol, data, context).await
+                }
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Pairwise return correlations for a basket of symbols. Bars come from the
+    /// bars cache and are intersected on timestamp, so symbols with different
+    /// history lengths are compared only over the bars they share.
+    pub async fn correlation_matrix(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        days_back: i64,
+    ) -> Result<CorrelationMatrix, AnalysisError> {
+        use futures_util::stream::{self, StreamExt};
+
+        let fetched: Vec<Result<Vec<Bar>, AnalysisError>> = stream::iter(symbols.iter().copied())
+            .map(|symbol| self.get_bars(symbol, timeframe, days_back))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut series = Vec::with_capacity(symbols.len());
+        for (symbol, bars) in symbols.iter().zip(fetched) {
+            series.push((symbol.to_string(), bars?));
+        }
+        Ok(CorrelationMatrix::from_bars(&series))
+    }
+
+    /// Fetch the per-symbol inputs (bars, financials, news, details, snapshot) concurrently.
+    /// With `as_of` set, everything published after that instant is excluded.
+    async fn fetch_symbol_data(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        days_back: i64,
+        as_of: Option<DateTime<Utc>>,
+    ) -> SymbolData {
         tracing::info!(
             "Starting comprehensive analysis for {} (timeframe: {:?}, days: {})",
             symbol,
@@ -339,41 +1140,118 @@ impl AnalysisOrchestrator {
             days_back
         );
 
+        let asset_class = AssetClass::from_symbol(symbol);
+
         // Fire all API calls concurrently — Starter plan supports ~100 req/sec.
-        // Cached responses (SPY/TLT bars, repeat symbols) return instantly.
+        // Cached responses (repeat symbols) return instantly.
         let (
             bars_result,
             financials_result,
             news_result,
             ticker_details,
-            spy_bars_result,
-            tlt_bars_result,
             snapshot_result,
-            iwm_bars_result,
-            iwd_bars_result,
-            iwf_bars_result,
+            weekly_bars,
         ) = tokio::join!(
-            self.get_bars(symbol, timeframe, days_back),
-            self.get_financials(symbol),
-            self.get_news(symbol, 50),
+            self.get_bars_at(symbol, timeframe, days_back, as_of),
+            async {
+                if !asset_class.is_equity() {
+                    return Ok(Vec::new());
+                }
+                self.get_financials(symbol).await
+            },
+            async {
+                match as_of {
+                    Some(as_of) => {
+                        self.polygon_client
+                            .get_news_before(Some(symbol), SENTIMENT_TREND_NEWS_LIMIT, Some(as_of))
+                            .await
+                    }
+                    None => self.get_news(symbol, SENTIMENT_TREND_NEWS_LIMIT).await,
+                }
+            },
             self.get_ticker_details(symbol),
-            self.get_bars("SPY", Timeframe::Day1, 365),
-            self.get_bars("TLT", Timeframe::Day1, 90),
-            self.polygon_client.get_snapshot(symbol),
-            self.get_bars("IWM", Timeframe::Day1, 365),
-            self.get_bars("IWD", Timeframe::Day1, 365),
-            self.get_bars("IWF", Timeframe::Day1, 365),
+            async {
+                match as_of {
+                    // A live snapshot would leak the current price into a historical run
+                    Some(_) => Err(AnalysisError::InvalidData(
+                        "snapshot unavailable for point-in-time analysis".to_string(),
+                    )),
+                    None => self.polygon_client.get_snapshot(symbol).await,
+                }
+            },
+            async {
+                if !matches!(timeframe, Timeframe::Day1) {
+                    return None;
+                }
+                self.get_bars_at(symbol, Timeframe::Week1, WEEKLY_CONFIRMATION_DAYS, as_of)
+                    .await
+                    .map_err(|e| tracing::debug!("Weekly bars unavailable for {}: {}", symbol, e))
+                    .ok()
+            },
         );
 
-        // Use snapshot last trade price as primary current_price, fall back to last bar close
-        let snapshot_price = snapshot_result
-            .as_ref()
-            .ok()
-            .and_then(|snap| snap.last_trade.as_ref())
-            .and_then(|lt| lt.p);
+        // One fetch serves both: the full set feeds the daily sentiment history,
+        // the newest slice the headline score
+        let trend_news = news_result.as_ref().cloned().unwrap_or_default();
+        let news_result = news_result.map(|mut articles| {
+            articles.sort_by_key(|a| std::cmp::Reverse(a.published_utc));
+            articles.truncate(SENTIMENT_HEADLINE_NEWS_LIMIT);
+            articles
+        });
 
-        let bar_price = bars_result
-            .as_ref()
+        let financials_result = match as_of {
+            Some(as_of) => financials_result.map(|financials| {
+                financials
+                    .into_iter()
+                    .filter(|f| financials_filed_by(f, as_of))
+                    .collect()
+            }),
+            None => financials_result,
+        };
+
+        SymbolData {
+            bars_result,
+            financials_result,
+            news_result,
+            trend_news,
+            ticker_details,
+            snapshot_result,
+            weekly_bars,
+            timeframe,
+            asset_class,
+            as_of,
+        }
+    }
+
+    /// Run every engine for one symbol using pre-fetched symbol data and a shared market context.
+    async fn analyze_with_context(
+        &self,
+        symbol: &str,
+        data: SymbolData,
+        context: &MarketContext,
+    ) -> Result<UnifiedAnalysis, AnalysisError> {
+        let SymbolData {
+            bars_result,
+            financials_result,
+            news_result,
+            trend_news,
+            ticker_details,
+            snapshot_result,
+            weekly_bars,
+            timeframe,
+            asset_class,
+            as_of,
+        } = data;
+
+        // Use snapshot last trade price as primary current_price, fall back to last bar close
+        let snapshot_price = snapshot_result
+            .as_ref()
+            .ok()
+            .and_then(|snap| snap.last_trade.as_ref())
+            .and_then(|lt| lt.p);
+
+        let bar_price = bars_result
+            .as_ref()
             .ok()
             .and_then(|bars| {
                 tracing::info!("Bars count for {}: {}", symbol, bars.len());
@@ -401,10 +1279,11 @@ impl AnalysisOrchestrator {
             );
         }
 
-        let spy_bars_ok = spy_bars_result.ok();
-        let iwm_bars_ok = iwm_bars_result.ok();
-        let iwd_bars_ok = iwd_bars_result.ok();
-        let iwf_bars_ok = iwf_bars_result.ok();
+        let spy_bars_ok = context.spy_bars.as_deref();
+        let benchmark_bars_ok = context.benchmark_bars.as_deref().or(spy_bars_ok);
+        let iwm_bars_ok = context.iwm_bars.as_deref();
+        let iwd_bars_ok = context.iwd_bars.as_deref();
+        let iwf_bars_ok = context.iwf_bars.as_deref();
 
         // Extract shares outstanding from ticker details for DCF model
         let shares_outstanding = ticker_details.as_ref().ok().and_then(|d| {
@@ -412,109 +1291,224 @@ impl AnalysisOrchestrator {
                 .or(d.share_class_shares_outstanding)
         });
 
-        // Derive dynamic risk-free rate from TLT price
-        let dynamic_risk_free_rate = tlt_bars_result.ok().and_then(|tlt_bars| {
-            if tlt_bars.len() >= 2 {
-                let first = tlt_bars.first().unwrap();
-                let last = tlt_bars.last().unwrap();
-                let tlt_return = (last.close - first.close) / first.close;
-                // TLT inversely tracks yields: if TLT fell, rates rose
-                let rate = (0.045 - tlt_return * 0.10).clamp(0.01, 0.08);
-                tracing::info!(
-                    "Dynamic risk-free rate from TLT: {:.3} (TLT return: {:.3})",
-                    rate,
-                    tlt_return
-                );
-                Some(rate)
-            } else {
-                None
+        let dynamic_risk_free_rate = context.risk_free_rate;
+
+        let low_quality =
+            self.quality_violation(asset_class, current_price, ticker_details.as_ref().ok());
+        if let Some((floor, reason)) = &low_quality {
+            if floor.reject {
+                return Err(AnalysisError::InvalidData(format!(
+                    "{} is below the quality floor: {}",
+                    symbol, reason
+                )));
             }
-        });
+            tracing::warn!(
+                "{} below quality floor ({}), capping confidence",
+                symbol,
+                reason
+            );
+        }
 
         // Run all independent analysis engines concurrently.
         // Technical & quant are CPU-bound but fast (sub-ms on a few hundred bars).
         // Sentiment & consensus are async network calls to ML/Polygon services.
         // Running them in parallel overlaps the network latency.
+        let mut engine_errors: Vec<(String, String)> = Vec::new();
+        let sentiment_analyzer = self.sentiment_analyzer_at(as_of);
+
         let (technical_result, quant_result, consensus_data, sentiment_result) = tokio::join!(
             async {
-                if let Ok(bars) = &bars_result {
-                    if bars.len() >= 50 {
+                match &bars_result {
+                    Ok(bars) => {
+                        if let Some(reason) = self.engine_minimums.technical(bars.len()) {
+                            return Err(reason);
+                        }
                         tracing::info!(
                             "Running enhanced technical analysis with {} bars",
                             bars.len()
                         );
-                        match self.technical_analyzer.analyze_enhanced(
-                            symbol,
-                            bars,
-                            spy_bars_ok.as_deref(),
-                        ) {
-                            Ok(result) => return Some(result),
-                            Err(e) => tracing::warn!("Technical analysis failed: {:?}", e),
+                        match self
+                            .technical_analyzer
+                            .analyze_enhanced(symbol, bars, spy_bars_ok)
+                        {
+                            Ok(result) => Ok(Some(result)),
+                            Err(e) => {
+                                tracing::warn!("Technical analysis failed: {:?}", e);
+                                Err(e.to_string())
+                            }
                         }
                     }
+                    Err(e) => Err(format!("bars unavailable: {}", e)),
                 }
-                None
             },
             async {
-                if let Ok(bars) = &bars_result {
-                    if bars.len() >= 30 {
+                match &bars_result {
+                    Ok(bars) => {
+                        if let Some(reason) = self.engine_minimums.quant(bars.len()) {
+                            return Err(reason);
+                        }
                         tracing::info!("Running enhanced quantitative analysis");
                         match self.quant_analyzer.analyze_with_factors(
                             symbol,
                             bars,
-                            spy_bars_ok.as_deref(),
-                            iwm_bars_ok.as_deref(),
-                            iwd_bars_ok.as_deref(),
-                            iwf_bars_ok.as_deref(),
+                            benchmark_bars_ok,
+                            iwm_bars_ok,
+                            iwd_bars_ok,
+                            iwf_bars_ok,
                             dynamic_risk_free_rate,
+                            timeframe,
+                            asset_class,
                         ) {
-                            Ok(result) => return Some(result),
-                            Err(e) => tracing::warn!("Quant analysis failed: {:?}", e),
+                            Ok(result) => Ok(Some(result)),
+                            Err(e) => {
+                                tracing::warn!("Quant analysis failed: {:?}", e);
+                                Err(e.to_string())
+                            }
                         }
                     }
+                    Err(e) => Err(format!("bars unavailable: {}", e)),
+                }
+            },
+            async {
+                match as_of {
+                    // Only today's consensus is available, which would be lookahead
+                    Some(_) => AnalystConsensusData::default(),
+                    None if !asset_class.is_equity() => AnalystConsensusData::default(),
+                    None => self.get_analyst_consensus(symbol).await,
                 }
-                None
             },
-            self.get_analyst_consensus(symbol),
             async {
-                if let Ok(news) = &news_result {
-                    tracing::info!("Running sentiment analysis with {} articles", news.len());
-                    if let Ok(result) = self.sentiment_analyzer.analyze(symbol, news).await {
-                        return Some(result);
+                match &news_result {
+                    Ok(news) => {
+                        tracing::info!("Running sentiment analysis with {} articles", news.len());
+                        sentiment_analyzer
+                            .analyze(symbol, news)
+                            .await
+                            .map(Some)
+                            .map_err(|e| e.to_string())
                     }
+                    Err(e) => Err(format!("news unavailable: {}", e)),
                 }
-                None
             },
         );
 
+        let mut collect =
+            |engine: &str, result: Result<Option<AnalysisResult>, String>| match result {
+                Ok(result) => result,
+                Err(message) => {
+                    engine_errors.push((engine.to_string(), message));
+                    None
+                }
+            };
+        let technical_result = collect("technical", technical_result);
+        let mut quant_result = collect("quantitative", quant_result);
+        if let Some(metrics) = quant_result
+            .as_mut()
+            .and_then(|q| q.metrics.as_object_mut())
+        {
+            metrics.insert(
+                "benchmark_symbol".to_string(),
+                json!(context.benchmark_symbol),
+            );
+        }
+        let mut sentiment_result = collect("sentiment", sentiment_result);
+        if let Some(metrics) = sentiment_result
+            .as_mut()
+            .and_then(|s| s.metrics.as_object_mut())
+        {
+            let history = sentiment_analyzer.daily_sentiment_series(symbol, &trend_news);
+            let dynamics = SentimentVelocityCalculator::default().calculate(&history);
+            metrics.insert(
+                "narrative_shift".to_string(),
+                json!(dynamics.narrative_shift),
+            );
+            metrics.insert(
+                "sentiment_velocity".to_string(),
+                json!({
+                    "history_days": history.len(),
+                    "current_sentiment": dynamics.current_sentiment,
+                    "velocity": dynamics.velocity,
+                    "acceleration": dynamics.acceleration,
+                    "signal": dynamics.signal,
+                    "interpretation": dynamics.interpretation,
+                    "confidence": dynamics.confidence,
+                }),
+            );
+        }
+
         // Fundamental analysis depends on consensus data, so it runs after the parallel phase
         let mut fundamental_result = None;
+        if !asset_class.is_equity() {
+            engine_errors.push((
+                "fundamental".to_string(),
+                format!("skipped: no company fundamentals for {:?}", asset_class),
+            ));
+        } else if let Err(e) = &financials_result {
+            engine_errors.push((
+                "fundamental".to_string(),
+                format!("financials unavailable: {}", e),
+            ));
+        }
         if let Ok(financials_vec) = &financials_result {
-            if !financials_vec.is_empty() {
+            if let Some(reason) = self.engine_minimums.fundamental(financials_vec.len()) {
+                engine_errors.push(("fundamental".to_string(), reason));
+            } else {
                 tracing::info!("Running enhanced fundamental analysis with consensus data");
                 let sic_desc = ticker_details
                     .as_ref()
                     .ok()
                     .and_then(|d| d.sic_description.as_deref());
-                match self.fundamental_analyzer.analyze_with_consensus(
+                let mut mismatch =
+                    CurrencyMismatch::detect(financials_vec, ticker_details.as_ref().ok());
+                if let Some(mismatch) = mismatch.as_mut() {
+                    mismatch.fx_rate = self
+                        .fx_rate(
+                            &mismatch.financials_currency,
+                            &mismatch.price_currency,
+                            as_of,
+                        )
+                        .await;
+                }
+                let converted;
+                let (financials_vec, fundamental_price) = match &mismatch {
+                    Some(m) => match m.fx_rate {
+                        Some(rate) => {
+                            converted = fundamental_analysis::convert_financials_currency(
+                                financials_vec,
+                                rate,
+                                &m.price_currency,
+                            );
+                            (converted.as_slice(), current_price)
+                        }
+                        // Price-based ratios would mix currencies, so compute without the price
+                        None => (financials_vec.as_slice(), None),
+                    },
+                    None => (financials_vec.as_slice(), current_price),
+                };
+                match self.fundamental_analyzer_at(as_of).analyze_with_consensus(
                     symbol,
                     financials_vec,
-                    current_price,
+                    fundamental_price,
                     shares_outstanding,
                     &consensus_data,
                     dynamic_risk_free_rate,
                     sic_desc,
                 ) {
-                    Ok(result) => fundamental_result = Some(result),
-                    Err(e) => tracing::warn!("Fundamental analysis failed: {:?}", e),
+                    Ok(mut result) => {
+                        if let Some(m) = &mismatch {
+                            m.annotate(&mut result);
+                        }
+                        fundamental_result = Some(result)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Fundamental analysis failed: {:?}", e);
+                        engine_errors.push(("fundamental".to_string(), e.to_string()));
+                    }
                 }
             }
         }
 
-        // Detect market regime from SPY bars (needed for regime-conditional weights)
-        let market_regime = spy_bars_ok
-            .as_deref()
-            .map(|spy_bars| self.detect_market_regime(spy_bars));
+        let market_regime = context.market_regime.clone();
 
         // Combine results (now async — may fetch dynamic weights from ML service)
         let mut overall = self
@@ -528,18 +1522,89 @@ impl AnalysisOrchestrator {
             )
             .await;
         overall.current_price = current_price;
-        overall.name = ticker_details.ok().map(|d| d.name);
+        overall.name = ticker_details.as_ref().ok().map(|d| d.name.clone());
         overall.market_regime = market_regime;
+        overall.market_regime_detail = context.market_regime_detail.clone();
+        overall.macro_context = context.macro_context.clone();
+        overall.engine_errors = engine_errors;
+        if let Some(as_of) = as_of {
+            overall.timestamp = as_of;
+        }
+
+        // Confirm the daily technical read against the weekly trend
+        if let (Some(daily), Some(weekly_bars)) = (&technical_result, &weekly_bars) {
+            if weekly_bars.len() >= self.engine_minimums.technical_bars {
+                match self
+                    .technical_analyzer
+                    .analyze_enhanced(symbol, weekly_bars, None)
+                {
+                    Ok(weekly) => {
+                        let alignment = timeframe_alignment(&daily.signal, &weekly.signal);
+                        if alignment == "divergent" {
+                            overall.overall_confidence = (overall.overall_confidence
+                                * TIMEFRAME_DIVERGENCE_CONFIDENCE_FACTOR)
+                                .clamp(0.05, 0.98);
+                        }
+                        overall.multi_timeframe_alignment = Some(alignment.to_string());
+                    }
+                    Err(e) => tracing::debug!("Weekly technical analysis failed: {:?}", e),
+                }
+            }
+        }
 
         // Compute supplementary signals from options, insiders, dividends, snapshot
         let (supplementary, confidence_adj) = self
-            .compute_supplementary_signals(symbol, current_price, bars_result.as_ref().ok())
+            .compute_supplementary_signals(
+                symbol,
+                current_price,
+                bars_result.as_ref().ok(),
+                sentiment_result.as_ref(),
+                ticker_details
+                    .as_ref()
+                    .ok()
+                    .and_then(|d| d.sic_description.as_deref()),
+                asset_class,
+                as_of,
+            )
             .await;
         overall.supplementary_signals = Some(supplementary);
         overall.overall_confidence =
             (overall.overall_confidence + confidence_adj).clamp(0.05, 0.98);
 
-        // Log analysis features for future model training (fire-and-forget)
+        // Signals built on pre-report data are less reliable right before earnings.
+        // The calendar only covers upcoming reports, so historical runs skip it.
+        if as_of.is_none() && asset_class.is_equity() {
+            match self.polygon_client.get_next_earnings_date(symbol).await {
+                Ok(Some(next)) => {
+                    let days = (next - self.clock.now()).num_days().max(0);
+                    overall.days_to_earnings = Some(days);
+                    overall.earnings_blackout = days <= EARNINGS_BLACKOUT_DAYS;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Earnings date unavailable for {}: {}", symbol, e),
+            }
+        }
+        // RS ranks live prices against US stocks, so there's nothing meaningful
+        // for historical runs or non-equities
+        if as_of.is_none() && asset_class.is_equity() {
+            overall.rs_rating = self.cached_rs_rating(symbol);
+        }
+        if overall.earnings_blackout {
+            overall.overall_confidence = (overall.overall_confidence
+                * EARNINGS_BLACKOUT_CONFIDENCE_FACTOR)
+                .clamp(0.05, 0.98);
+        }
+        if let Some((floor, _)) = low_quality {
+            overall.low_quality_universe = true;
+            overall.overall_confidence = overall.overall_confidence.min(floor.max_confidence);
+        }
+        overall.suggested_position_pct = self.position_sizing.suggested_position_pct(&overall);
+
+        // Log analysis features for future model training (fire-and-forget).
+        // Historical runs are skipped so backtests don't pollute the training set.
+        if as_of.is_some() {
+            return Ok(overall);
+        }
         self.log_analysis_features(
             symbol,
             &technical_result,
@@ -555,6 +1620,133 @@ impl AnalysisOrchestrator {
         Ok(overall)
     }
 
+    /// Run every engine on caller-provided daily data, with no network access.
+    ///
+    /// Sentiment uses the word list only (no FinBERT), fundamentals run without
+    /// analyst consensus, and engine weights come from the SPY-derived regime
+    /// rather than the ML service. Network-dependent extras (supplementary
+    /// signals, earnings calendar, RS rating) are left unset, and nothing is
+    /// logged to the database. The result is stamped with the last bar's time.
+    pub fn analyze_offline(
+        &self,
+        symbol: &str,
+        bars: &[Bar],
+        spy_bars: Option<&[Bar]>,
+        financials: &[Financials],
+        news: &[NewsArticle],
+        ticker_details: Option<&TickerDetails>,
+    ) -> UnifiedAnalysis {
+        let mut engine_errors: Vec<(String, String)> = Vec::new();
+        let mut collect = |engine: &str, result: Result<AnalysisResult, String>| match result {
+            Ok(result) => Some(result),
+            Err(message) => {
+                engine_errors.push((engine.to_string(), message));
+                None
+            }
+        };
+
+        let current_price = bars.last().map(|b| b.close);
+        let minimums = self.engine_minimums;
+        let technical_result = collect(
+            "technical",
+            match minimums.technical(bars.len()) {
+                Some(reason) => Err(reason),
+                None => self
+                    .technical_analyzer
+                    .analyze_enhanced(symbol, bars, spy_bars)
+                    .map_err(|e| e.to_string()),
+            },
+        );
+        let quant_result = collect(
+            "quantitative",
+            match minimums.quant(bars.len()) {
+                Some(reason) => Err(reason),
+                None => self
+                    .quant_analyzer
+                    .analyze_with_factors(
+                        symbol,
+                        bars,
+                        spy_bars,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Timeframe::Day1,
+                        AssetClass::from_symbol(symbol),
+                    )
+                    .map_err(|e| e.to_string()),
+            },
+        );
+        let fundamental_result = match minimums.fundamental(financials.len()) {
+            Some(reason) => collect("fundamental", Err(reason)),
+            None => {
+                let shares_outstanding = ticker_details.and_then(|d| {
+                    d.weighted_shares_outstanding
+                        .or(d.share_class_shares_outstanding)
+                });
+                // No FX lookup offline: a currency mismatch drops the price instead
+                let mismatch = CurrencyMismatch::detect(financials, ticker_details);
+                let result = self.fundamental_analyzer.analyze_with_consensus(
+                    symbol,
+                    financials,
+                    current_price.filter(|_| mismatch.is_none()),
+                    shares_outstanding,
+                    &AnalystConsensusData::default(),
+                    None,
+                    ticker_details.and_then(|d| d.sic_description.as_deref()),
+                );
+                collect(
+                    "fundamental",
+                    result
+                        .map(|mut result| {
+                            if let Some(m) = &mismatch {
+                                m.annotate(&mut result);
+                            }
+                            result
+                        })
+                        .map_err(|e| e.to_string()),
+                )
+            }
+        };
+        let sentiment_result = collect(
+            "sentiment",
+            self.sentiment_analyzer
+                .analyze_lexicon_only(symbol, news)
+                .map_err(|e| e.to_string()),
+        );
+
+        let market_regime_detail = spy_bars.map(|spy| self.detect_market_regime_detailed(spy));
+        let market_regime = market_regime_detail.as_ref().map(|d| d.label.as_str());
+        let weights = self.regime_default_weights(market_regime.unwrap_or("unknown"));
+
+        let mut overall = self.combine_with_weights(
+            symbol,
+            &technical_result,
+            &fundamental_result,
+            &quant_result,
+            &sentiment_result,
+            market_regime,
+            weights,
+        );
+        overall.current_price = current_price;
+        overall.name = ticker_details.map(|d| d.name.clone());
+        overall.market_regime_detail = market_regime_detail;
+        overall.engine_errors = engine_errors;
+        if let Some(last) = bars.last() {
+            overall.timestamp = last.timestamp;
+        }
+        if let Some((floor, _)) = self.quality_violation(
+            AssetClass::from_symbol(symbol),
+            current_price,
+            ticker_details,
+        ) {
+            overall.low_quality_universe = true;
+            overall.overall_confidence = overall.overall_confidence.min(floor.max_confidence);
+        }
+        overall.suggested_position_pct = self.position_sizing.suggested_position_pct(&overall);
+        overall
+    }
+
     /// Combine individual analysis results into unified analysis.
     /// Uses: ML-predicted weights > regime-conditional weights > hardcoded defaults.
     async fn combine_results(
@@ -572,7 +1764,7 @@ impl AnalysisOrchestrator {
             .await;
 
         // Priority: ML weights > regime-conditional > hardcoded
-        let (w_tech, w_fund, w_quant, w_sent) = match &dynamic_weights {
+        let weights = match &dynamic_weights {
             Some(w) => {
                 let wt = (w.get("technical").copied().unwrap_or(0.20) * 100.0) as i32;
                 let wf = (w.get("fundamental").copied().unwrap_or(0.40) * 100.0) as i32;
@@ -583,41 +1775,79 @@ impl AnalysisOrchestrator {
             None => self.regime_default_weights(market_regime.unwrap_or("unknown")),
         };
 
-        let mut total_score = 0;
-        let mut total_weight = 0;
+        self.combine_with_weights(
+            symbol,
+            technical,
+            fundamental,
+            quantitative,
+            sentiment,
+            market_regime,
+            weights,
+        )
+    }
+
+    /// Combine engine results using explicit (technical, fundamental, quant, sentiment)
+    /// percentage weights.
+    #[allow(clippy::too_many_arguments)]
+    fn combine_with_weights(
+        &self,
+        symbol: &str,
+        technical: &Option<AnalysisResult>,
+        fundamental: &Option<AnalysisResult>,
+        quantitative: &Option<AnalysisResult>,
+        sentiment: &Option<AnalysisResult>,
+        market_regime: Option<&str>,
+        (w_tech, w_fund, w_quant, w_sent): EngineWeights,
+    ) -> UnifiedAnalysis {
+        // Overall score = Σ(wᵢ · scoreᵢ) / Σ wᵢ over the engines that produced a result,
+        // where wᵢ is the engine's base weight, or base weight × confidence when
+        // confidence weighting is enabled (renormalized by the Σ wᵢ division).
+        let signal_weight = |base: i32, confidence: f64| {
+            if self.confidence_weighting {
+                base as f64 * confidence
+            } else {
+                base as f64
+            }
+        };
+        let mut weighted_score = 0.0;
+        let mut total_weight = 0.0;
         let mut combined_confidence = 0.0;
         let mut count = 0;
 
         if let Some(tech) = technical {
-            total_score += tech.signal.to_score() * w_tech;
-            total_weight += w_tech;
+            let w = signal_weight(w_tech, tech.confidence);
+            weighted_score += tech.signal.to_score() as f64 * w;
+            total_weight += w;
             combined_confidence += tech.confidence * (w_tech as f64 / 100.0);
             count += 1;
         }
 
         if let Some(fund) = fundamental {
-            total_score += fund.signal.to_score() * w_fund;
-            total_weight += w_fund;
+            let w = signal_weight(w_fund, fund.confidence);
+            weighted_score += fund.signal.to_score() as f64 * w;
+            total_weight += w;
             combined_confidence += fund.confidence * (w_fund as f64 / 100.0);
             count += 1;
         }
 
         if let Some(quant) = quantitative {
-            total_score += quant.signal.to_score() * w_quant;
-            total_weight += w_quant;
+            let w = signal_weight(w_quant, quant.confidence);
+            weighted_score += quant.signal.to_score() as f64 * w;
+            total_weight += w;
             combined_confidence += quant.confidence * (w_quant as f64 / 100.0);
             count += 1;
         }
 
         if let Some(sent) = sentiment {
-            total_score += sent.signal.to_score() * w_sent;
-            total_weight += w_sent;
+            let w = signal_weight(w_sent, sent.confidence);
+            weighted_score += sent.signal.to_score() as f64 * w;
+            total_weight += w;
             combined_confidence += sent.confidence * (w_sent as f64 / 100.0);
             count += 1;
         }
 
-        let overall_signal = if total_weight > 0 {
-            SignalStrength::from_score((total_score as f64 / total_weight as f64) as i32)
+        let overall_signal = if total_weight > 0.0 {
+            SignalStrength::from_score((weighted_score / total_weight) as i32)
         } else {
             SignalStrength::Neutral
         };
@@ -637,20 +1867,7 @@ impl AnalysisOrchestrator {
             scores.push(sent.signal.to_score());
         }
 
-        let conflict_penalty = if scores.len() >= 2 {
-            let has_bullish = scores.iter().any(|&s| s >= 30);
-            let has_bearish = scores.iter().any(|&s| s <= -30);
-            if has_bullish && has_bearish {
-                let max_score = scores.iter().max().copied().unwrap_or(0);
-                let min_score = scores.iter().min().copied().unwrap_or(0);
-                let spread = (max_score - min_score) as f64 / 200.0;
-                spread * 0.30
-            } else {
-                0.0
-            }
-        } else {
-            0.0
-        };
+        let conflict_penalty = self.conflict_penalty.penalty(&scores);
 
         let overall_confidence = if count > 0 {
             (combined_confidence - conflict_penalty).max(0.05)
@@ -665,16 +1882,21 @@ impl AnalysisOrchestrator {
             self.build_time_horizon_signals(technical, fundamental, quantitative, sentiment);
 
         // Enhanced recommendation with conviction
-        let recommendation = format!(
-            "{} [{}]",
-            self.generate_recommendation(&overall_signal, overall_confidence),
-            conviction_tier,
-        );
+        let has_sufficient_data = count > 0;
+        let recommendation = if has_sufficient_data {
+            format!(
+                "{} [{}]",
+                self.generate_recommendation(&overall_signal, overall_confidence),
+                conviction_tier,
+            )
+        } else {
+            "Insufficient Data".to_string()
+        };
 
         UnifiedAnalysis {
             symbol: symbol.to_string(),
             name: None,
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             current_price: None,
             technical: technical.clone(),
             fundamental: fundamental.clone(),
@@ -684,9 +1906,19 @@ impl AnalysisOrchestrator {
             overall_confidence,
             recommendation,
             market_regime: market_regime.map(|s| s.to_string()),
+            market_regime_detail: None,
             conviction_tier: Some(conviction_tier),
             time_horizon_signals: Some(time_horizon_signals),
             supplementary_signals: None, // Set by caller after fetching options/insiders/dividends
+            engine_errors: Vec::new(),   // Set by caller, which knows why engines were skipped
+            days_to_earnings: None,
+            earnings_blackout: false,
+            rs_rating: None,
+            has_sufficient_data,
+            multi_timeframe_alignment: None, // Set by caller when weekly bars are available
+            macro_context: None,             // Set by caller from the shared market context
+            suggested_position_pct: None,    // Set by caller once confidence is final
+            low_quality_universe: false,
         }
     }
 
@@ -700,6 +1932,10 @@ impl AnalysisOrchestrator {
         sentiment: &Option<AnalysisResult>,
     ) -> Option<HashMap<String, f64>> {
         let client = self.signal_models_client.as_ref()?;
+        if !self.signal_models_breaker.allow_request() {
+            tracing::debug!("Signal models circuit open, using default weights");
+            return None;
+        }
 
         let mut features = HashMap::new();
         // Build minimal feature set for weight prediction
@@ -725,6 +1961,7 @@ impl AnalysisOrchestrator {
 
         match client.get_optimal_weights(&features).await {
             Ok(engine_weights) => {
+                self.signal_models_breaker.record_success();
                 tracing::info!(
                     "Using dynamic weights from signal models: {:?}",
                     engine_weights.weights
@@ -732,6 +1969,7 @@ impl AnalysisOrchestrator {
                 Some(engine_weights.weights)
             }
             Err(e) => {
+                self.signal_models_breaker.record_failure();
                 tracing::debug!("Signal models unavailable, using default weights: {}", e);
                 None
             }
@@ -820,7 +2058,11 @@ impl AnalysisOrchestrator {
 
         let signal_str = format!("{:?}", overall_signal);
         let symbol_owned = symbol.to_string();
-        let analysis_date = Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        let now = Utc::now();
+        let analysis_date = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+        let day_prefix = self
+            .feature_dedup
+            .then(|| now.format("%Y-%m-%d%%").to_string());
 
         // Build JSON with numeric features + string metadata for analytics
         let mut features_value = serde_json::to_value(&features).unwrap_or_default();
@@ -838,15 +2080,30 @@ impl AnalysisOrchestrator {
 
         // Fire-and-forget: spawn a task to insert into DB
         tokio::spawn(async move {
-            let result = sqlx::query(
-                "INSERT INTO analysis_features (symbol, analysis_date, features_json, overall_signal, overall_confidence) VALUES (?, ?, ?, ?, ?)"
-            )
-            .bind(&symbol_owned)
-            .bind(&analysis_date)
-            .bind(&features_json)
-            .bind(&signal_str)
-            .bind(overall_confidence)
-            .execute(&pool)
+            let result = async {
+                let mut tx = pool.begin().await?;
+                // Today's earlier rows for the symbol are superseded by this one
+                if let Some(day_prefix) = &day_prefix {
+                    sqlx::query(
+                        "DELETE FROM analysis_features WHERE symbol = ? AND analysis_date LIKE ?",
+                    )
+                    .bind(&symbol_owned)
+                    .bind(day_prefix)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                sqlx::query(
+                    "INSERT INTO analysis_features (symbol, analysis_date, features_json, overall_signal, overall_confidence) VALUES (?, ?, ?, ?, ?)"
+                )
+                .bind(&symbol_owned)
+                .bind(&analysis_date)
+                .bind(&features_json)
+                .bind(&signal_str)
+                .bind(overall_confidence)
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await
+            }
             .await;
 
             if let Err(e) = result {
@@ -855,23 +2112,144 @@ impl AnalysisOrchestrator {
         });
     }
 
+    /// Units of `to` per unit of `from` from the latest daily forex close, trying
+    /// the inverse pair when the direct one isn't listed
+    async fn fx_rate(&self, from: &str, to: &str, as_of: Option<DateTime<Utc>>) -> Option<f64> {
+        let last_close = |bars: Result<Vec<Bar>, AnalysisError>| {
+            bars.ok()
+                .and_then(|b| b.last().map(|bar| bar.close))
+                .filter(|c| c.is_finite() && *c > 0.0)
+        };
+        let direct = format!("C:{}{}", from, to);
+        if let Some(rate) = last_close(self.get_bars_at(&direct, Timeframe::Day1, 10, as_of).await)
+        {
+            return Some(rate);
+        }
+        let inverse = format!("C:{}{}", to, from);
+        let rate = last_close(self.get_bars_at(&inverse, Timeframe::Day1, 10, as_of).await)
+            .map(|r| 1.0 / r);
+        if rate.is_none() {
+            tracing::warn!("No FX rate for {}/{}", from, to);
+        }
+        rate
+    }
+
+    /// Persist the full analysis (signals, confidence, conviction, per-engine results)
+    /// to `analysis_results`. A no-op when no database pool is configured.
+    pub async fn store_analysis(&self, analysis: &UnifiedAnalysis) -> Result<(), AnalysisError> {
+        let Some(pool) = &self.db_pool else {
+            return Ok(());
+        };
+
+        let analysis_json = serde_json::to_string(analysis)
+            .map_err(|e| AnalysisError::InvalidData(e.to_string()))?;
+        sqlx::query(
+            "INSERT INTO analysis_results (symbol, analysis_date, overall_signal, overall_confidence, conviction_tier, market_regime, analysis_json) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&analysis.symbol)
+        .bind(analysis.timestamp.format("%Y-%m-%dT%H:%M:%S").to_string())
+        .bind(format!("{:?}", analysis.overall_signal))
+        .bind(analysis.overall_confidence)
+        .bind(analysis.conviction_tier.as_deref())
+        .bind(analysis.market_regime.as_deref())
+        .bind(&analysis_json)
+        .execute(pool)
+        .await
+        .map_err(|e| AnalysisError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Most recent analysis stored for `symbol` by `store_analysis`, if any
+    pub async fn get_latest_stored_analysis(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<UnifiedAnalysis>, AnalysisError> {
+        let Some(pool) = &self.db_pool else {
+            return Ok(None);
+        };
+
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT analysis_json FROM analysis_results WHERE symbol = ? ORDER BY analysis_date DESC, id DESC LIMIT 1",
+        )
+        .bind(symbol)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AnalysisError::DatabaseError(e.to_string()))?;
+
+        row.map(|(json,)| {
+            serde_json::from_str(&json).map_err(|e| AnalysisError::InvalidData(e.to_string()))
+        })
+        .transpose()
+    }
+
     /// Compute supplementary signals from options, insiders, dividends, and snapshot.
     /// Returns (signals_json, score_adjustment) where score_adjustment modifies overall confidence.
+    #[allow(clippy::too_many_arguments)]
     async fn compute_supplementary_signals(
         &self,
         symbol: &str,
         current_price: Option<f64>,
         bars: Option<&Vec<Bar>>,
+        sentiment: Option<&AnalysisResult>,
+        sic_description: Option<&str>,
+        asset_class: AssetClass,
+        as_of: Option<DateTime<Utc>>,
     ) -> (serde_json::Value, f64) {
         let mut signals = serde_json::Map::new();
         let mut score_adj = 0.0_f64;
 
-        // Fetch supplementary data concurrently (graceful errors)
-        let (options_result, insiders_result, dividends_result) = tokio::join!(
-            self.polygon_client.get_options_snapshot(symbol),
-            self.polygon_client.get_insider_transactions(symbol, 50),
-            self.polygon_client.get_dividends(symbol, 20),
+        // Fetch supplementary data concurrently (graceful errors).
+        // The options snapshot and extended-hours bars are live-only, so
+        // point-in-time runs skip them. Options, insiders, dividends and the
+        // pre-market session only exist for equities.
+        let now = self.clock.now();
+        let equity = asset_class.is_equity();
+        let (options_result, mut insiders_result, mut dividends_result, extended_bars) = tokio::join!(
+            async {
+                match as_of {
+                    Some(_) => Ok(Vec::new()),
+                    None if !equity => Ok(Vec::new()),
+                    None => self.polygon_client.get_options_snapshot(symbol).await,
+                }
+            },
+            async {
+                if !equity {
+                    return Ok(Vec::new());
+                }
+                self.polygon_client
+                    .get_insider_transactions(symbol, 50)
+                    .await
+            },
+            async {
+                if !equity {
+                    return Ok(Vec::new());
+                }
+                self.polygon_client
+                    .get_dividends(symbol, DIVIDEND_HISTORY_LIMIT)
+                    .await
+            },
+            async {
+                match as_of {
+                    Some(_) => Ok(Vec::new()),
+                    None if !equity => Ok(Vec::new()),
+                    None => self.get_premarket_bars(symbol).await,
+                }
+            },
         );
+        if let Some(as_of) = as_of {
+            let cutoff = as_of.format("%Y-%m-%d").to_string();
+            if let Ok(insiders) = &mut insiders_result {
+                insiders.retain(|t| t.filing_date.as_deref().is_some_and(|d| d <= &cutoff[..]));
+            }
+            if let Ok(dividends) = &mut dividends_result {
+                dividends.retain(|d| {
+                    d.declaration_date
+                        .as_deref()
+                        .or(d.ex_dividend_date.as_deref())
+                        .is_some_and(|d| d <= &cutoff[..])
+                });
+            }
+        }
 
         // --- Options-Implied Intelligence ---
         if let Ok(options) = &options_result {
@@ -883,6 +2261,10 @@ impl AnalysisOrchestrator {
                 let mut call_iv_count = 0u32;
                 let mut put_iv_count = 0u32;
                 let mut ivs: Vec<f64> = Vec::new();
+                // Dealer gamma exposure: dealers are assumed long calls / short puts
+                let mut gamma_exposure = 0.0_f64;
+                let mut gamma_contracts = 0u32;
+                let spot = current_price.filter(|&p| p > 0.0);
 
                 for opt in options {
                     let contract_type = opt
@@ -897,6 +2279,22 @@ impl AnalysisOrchestrator {
                         ivs.push(iv);
                     }
 
+                    let gamma = opt
+                        .greeks
+                        .as_ref()
+                        .and_then(|g| g.gamma)
+                        .filter(|g| g.is_finite());
+                    if let (Some(gamma), Some(spot)) = (gamma, spot) {
+                        let contract_gex = gamma * oi as f64 * 100.0 * spot * spot;
+                        if contract_type.eq_ignore_ascii_case("call") {
+                            gamma_exposure += contract_gex;
+                            gamma_contracts += 1;
+                        } else if contract_type.eq_ignore_ascii_case("put") {
+                            gamma_exposure -= contract_gex;
+                            gamma_contracts += 1;
+                        }
+                    }
+
                     if contract_type.eq_ignore_ascii_case("call") {
                         call_oi += oi;
                         if iv > 0.0 {
@@ -1014,7 +2412,7 @@ impl AnalysisOrchestrator {
                     score_adj += 0.02;
                 } // Low IV = expansion likely
 
-                // Max Pain (strike with most total OI)
+                // Legacy "max pain": strike with the most total OI (kept during transition)
                 let mut strike_oi: std::collections::HashMap<i64, i64> =
                     std::collections::HashMap::new();
                 for opt in options {
@@ -1023,10 +2421,13 @@ impl AnalysisOrchestrator {
                         *strike_oi.entry(key).or_insert(0) += opt.open_interest.unwrap_or(0);
                     }
                 }
-                let max_pain = strike_oi
+                let max_oi_strike = strike_oi
                     .into_iter()
                     .max_by_key(|(_, oi)| *oi)
                     .map(|(k, _)| k as f64 / 100.0);
+
+                // Max pain: expiry price that minimizes total payout to option holders
+                let max_pain = max_pain_strike(&strike_call_oi, &strike_put_oi);
                 let max_pain_convergence = if let (Some(mp), Some(p)) = (max_pain, current_price) {
                     if p > 0.0 {
                         ((mp - p) / p * 100.0).abs()
@@ -1037,6 +2438,90 @@ impl AnalysisOrchestrator {
                     100.0
                 };
 
+                // Gamma is missing on illiquid/expired contracts; no gamma at all means no estimate
+                let gamma_exposure = (gamma_contracts > 0).then_some(gamma_exposure);
+                let gex_signal = gamma_exposure.map(|gex| {
+                    if gex >= 0.0 {
+                        "Positive GEX (pinning likely)"
+                    } else {
+                        "Negative GEX (volatility amplified)"
+                    }
+                });
+
+                // Expected move into the nearest expiry from the ATM straddle IV
+                let today = self.clock.now().date_naive();
+                let atm_ivs = spot
+                    .map(|spot| atm_iv_by_expiry(options, spot))
+                    .unwrap_or_default();
+                let expected_move = atm_ivs.range(today..).next().zip(spot).map(
+                    |((&expiry, &(atm_iv, _)), spot)| {
+                        let days = (expiry - today).num_days().max(1) as f64;
+                        let pct = atm_iv * (days / 365.0).sqrt() * 100.0;
+                        (expiry, atm_iv, pct, spot * pct / 100.0)
+                    },
+                );
+
+                // Volatility term structure: front IV above back IV signals stress
+                let term_points: Vec<(f64, f64)> = atm_ivs
+                    .range(today..)
+                    .filter(|(_, &(_, contracts))| contracts >= MIN_TERM_STRUCTURE_CONTRACTS)
+                    .map(|(expiry, &(iv, _))| ((*expiry - today).num_days().max(1) as f64, iv))
+                    .collect();
+                let term_structure_slope = iv_term_slope(&term_points);
+                let term_structure_signal = term_structure_slope.and_then(|slope| {
+                    if slope < -TERM_STRUCTURE_FLAT_BAND {
+                        score_adj -= 0.02;
+                        Some("IV Backwardation (stress)")
+                    } else if slope > TERM_STRUCTURE_FLAT_BAND {
+                        score_adj += 0.01;
+                        Some("IV Contango (calm)")
+                    } else {
+                        None
+                    }
+                });
+
+                // Unusual activity: fresh positioning where volume swamps open interest
+                let unusual = unusual_options_activity(options);
+                let unusual_volume = |kind: &str| -> i64 {
+                    unusual
+                        .iter()
+                        .filter(|(opt, _)| {
+                            opt.details
+                                .as_ref()
+                                .and_then(|d| d.contract_type.as_deref())
+                                .is_some_and(|t| t.eq_ignore_ascii_case(kind))
+                        })
+                        .map(|(opt, _)| opt.volume().unwrap_or(0))
+                        .sum()
+                };
+                let (unusual_call_volume, unusual_put_volume) =
+                    (unusual_volume("call"), unusual_volume("put"));
+                let unusual_activity_signal = if unusual_call_volume > 2 * unusual_put_volume {
+                    score_adj += 0.01;
+                    Some("Unusual Call Activity")
+                } else if unusual_put_volume > 2 * unusual_call_volume {
+                    score_adj -= 0.01;
+                    Some("Unusual Put Activity")
+                } else {
+                    None
+                };
+                let unusual_contracts: Vec<serde_json::Value> = unusual
+                    .iter()
+                    .take(UNUSUAL_OPTIONS_TOP_N)
+                    .map(|(opt, ratio)| {
+                        let details = opt.details.as_ref();
+                        json!({
+                            "ticker": details.and_then(|d| d.ticker.clone()),
+                            "contract_type": details.and_then(|d| d.contract_type.clone()),
+                            "strike": details.and_then(|d| d.strike_price),
+                            "expiration_date": details.and_then(|d| d.expiration_date.clone()),
+                            "volume": opt.volume(),
+                            "open_interest": opt.open_interest,
+                            "volume_oi_ratio": ratio,
+                        })
+                    })
+                    .collect();
+
                 signals.insert(
                     "options".to_string(),
                     json!({
@@ -1046,10 +2531,25 @@ impl AnalysisOrchestrator {
                         "iv_skew_signal": skew_signal,
                         "iv_percentile": iv_percentile,
                         "max_pain": max_pain,
+                        "max_oi_strike": max_oi_strike,
                         "max_pain_distance_pct": max_pain_convergence,
                         "call_open_interest": call_oi,
                         "put_open_interest": put_oi,
                         "total_contracts": options.len(),
+                        "gamma_exposure": gamma_exposure,
+                        "gex_signal": gex_signal,
+                        "atm_iv": expected_move.map(|(_, iv, _, _)| iv),
+                        "implied_expected_move_pct": expected_move.map(|(_, _, pct, _)| pct),
+                        "implied_expected_move": expected_move.map(|(_, _, _, mv)| mv),
+                        "expected_move_expiry": expected_move.map(|(e, _, _, _)| e.to_string()),
+                        "term_structure_slope": term_structure_slope,
+                        "term_structure_expiries": term_points.len(),
+                        "term_structure_signal": term_structure_signal,
+                        "unusual_contracts": unusual_contracts,
+                        "unusual_contract_count": unusual.len(),
+                        "unusual_call_volume": unusual_call_volume,
+                        "unusual_put_volume": unusual_put_volume,
+                        "unusual_activity_signal": unusual_activity_signal,
                     }),
                 );
             }
@@ -1063,6 +2563,7 @@ impl AnalysisOrchestrator {
                 let mut buy_count = 0u32;
                 let mut sell_count = 0u32;
                 let mut executive_buys = 0u32;
+                let mut buy_events: Vec<(chrono::NaiveDate, &str)> = Vec::new();
 
                 for txn in insiders {
                     let is_buy = txn
@@ -1104,6 +2605,14 @@ impl AnalysisOrchestrator {
                         if is_executive {
                             executive_buys += 1;
                         }
+                        // Unnamed filers can't be told apart, so they never form a cluster
+                        let filed = txn.filing_date.as_deref().and_then(|d| {
+                            chrono::NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d")
+                                .ok()
+                        });
+                        if let (Some(date), Some(name)) = (filed, txn.name.as_deref()) {
+                            buy_events.push((date, name));
+                        }
                     } else if is_sell {
                         sell_value += value;
                         sell_count += 1;
@@ -1142,6 +2651,15 @@ impl AnalysisOrchestrator {
                     score_adj += 0.03; // Multiple C-suite buys is very bullish
                 }
 
+                // Several insiders buying within days of each other is far more
+                // informative than isolated purchases
+                let (cluster_insiders, cluster_start) =
+                    max_insider_buy_cluster(&mut buy_events, INSIDER_CLUSTER_WINDOW_DAYS);
+                let clustered_buying = cluster_insiders >= INSIDER_CLUSTER_MIN_INSIDERS;
+                if clustered_buying {
+                    score_adj += 0.06;
+                }
+
                 signals.insert(
                     "insiders".to_string(),
                     json!({
@@ -1152,6 +2670,10 @@ impl AnalysisOrchestrator {
                         "net_value": net_value,
                         "executive_buys": executive_buys,
                         "signal": insider_signal,
+                        "clustered_buying": clustered_buying,
+                        "cluster_signal": clustered_buying.then_some("Clustered Insider Buying"),
+                        "max_cluster_insiders": cluster_insiders,
+                        "cluster_start": cluster_start.map(|d| d.to_string()),
                     }),
                 );
             }
@@ -1159,8 +2681,9 @@ impl AnalysisOrchestrator {
 
         // --- Dividend Health Signals (adaptive thresholds) ---
         if let Ok(dividends) = &dividends_result {
-            if dividends.len() >= 2 {
-                let amounts: Vec<f64> = dividends.iter().filter_map(|d| d.cash_amount).collect();
+            let recent = &dividends[..dividends.len().min(DIVIDEND_HEALTH_PAYMENTS)];
+            if recent.len() >= 2 {
+                let amounts: Vec<f64> = recent.iter().filter_map(|d| d.cash_amount).collect();
 
                 if amounts.len() >= 2 {
                     let latest = amounts[0];
@@ -1205,7 +2728,7 @@ impl AnalysisOrchestrator {
                     };
 
                     // Annualized yield (if we have price)
-                    let annual_div = if let Some(freq) = dividends[0].frequency {
+                    let annual_div = if let Some(freq) = recent[0].frequency {
                         latest * freq as f64
                     } else {
                         latest * 4.0 // assume quarterly
@@ -1215,7 +2738,7 @@ impl AnalysisOrchestrator {
                         .map(|p| annual_div / p * 100.0);
 
                     // Special dividend detection
-                    let has_special = dividends.iter().any(|d| {
+                    let has_special = recent.iter().any(|d| {
                         d.dividend_type
                             .as_deref()
                             .map(|t| t.to_lowercase().contains("special"))
@@ -1225,6 +2748,24 @@ impl AnalysisOrchestrator {
                         score_adj += 0.02;
                     }
 
+                    // Dividend growth: CAGR, streak of annual increases, Chowder Rule
+                    let growth = dividend_growth(dividends);
+                    let cagr_pct = growth.as_ref().and_then(|g| g.cagr).map(|c| c * 100.0);
+                    let streak = growth.as_ref().map_or(0, |g| g.streak_years);
+                    let chowder = div_yield.zip(cagr_pct).map(|(y, c)| y + c);
+                    let chowder_min = chowder_threshold(sic_description);
+                    let growth_signal = if streak >= DIVIDEND_ARISTOCRAT_MIN_STREAK
+                        && growth.as_ref().is_some_and(|g| g.steady)
+                    {
+                        score_adj += 0.02;
+                        Some("Dividend Aristocrat Candidate")
+                    } else if chowder.is_some_and(|c| c >= chowder_min) {
+                        score_adj += 0.01;
+                        Some("Passes Chowder Rule")
+                    } else {
+                        None
+                    };
+
                     signals.insert(
                         "dividends".to_string(),
                         json!({
@@ -1234,6 +2775,13 @@ impl AnalysisOrchestrator {
                             "annual_yield_pct": div_yield,
                             "has_special_dividend": has_special,
                             "payment_count": amounts.len(),
+                            "growth_cagr_pct": cagr_pct,
+                            "growth_streak_years": streak,
+                            "years_of_history": growth.as_ref().map_or(0, |g| g.years),
+                            "chowder_number": chowder,
+                            "chowder_threshold": chowder_min,
+                            "passes_chowder_rule": chowder.map(|c| c >= chowder_min),
+                            "growth_signal": growth_signal,
                         }),
                     );
                 }
@@ -1241,7 +2789,11 @@ impl AnalysisOrchestrator {
         }
 
         // --- Snapshot / Intraday Gap Analysis (adaptive thresholds) ---
-        if let Ok(snapshot) = self.polygon_client.get_snapshot(symbol).await {
+        let snapshot_result = match as_of {
+            Some(_) => None,
+            None => self.polygon_client.get_snapshot(symbol).await.ok(),
+        };
+        if let Some(snapshot) = &snapshot_result {
             if let (Some(day), Some(prev)) = (&snapshot.day, &snapshot.prev_day) {
                 let today_open = day.o.unwrap_or(0.0);
                 let prev_close = prev.c.unwrap_or(0.0);
@@ -1307,26 +2859,130 @@ impl AnalysisOrchestrator {
             }
         }
 
-        // --- Smart Money Composite (adaptive thresholds) ---
-        // Combines insider buys + options positioning + volume accumulation
-        let mut smart_money_score = 0.0_f64;
-        if let Some(insider_sig) = signals.get("insiders") {
-            if insider_sig.get("signal").and_then(|s| s.as_str()) == Some("bullish") {
-                smart_money_score += 1.0;
-            }
-            if insider_sig
-                .get("executive_buys")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0)
-                >= 2
-            {
-                smart_money_score += 1.0;
+        // --- Pre-market gap and volume vs prior sessions' pre-market ---
+        match &extended_bars {
+            Ok(extended_bars) => {
+                if let Some((premarket, adj)) = premarket::premarket_signal(extended_bars, now) {
+                    score_adj += adj;
+                    signals.insert("premarket".to_string(), premarket);
+                }
             }
+            Err(e) => tracing::debug!("Extended-hours bars unavailable for {}: {}", symbol, e),
         }
-        if let Some(opt_sig) = signals.get("options") {
-            if opt_sig.get("put_call_signal").and_then(|s| s.as_str()) == Some("bullish") {
-                smart_money_score += 1.0;
-            }
+
+        // --- Liquidity: dollar-volume tier and quoted spread ---
+        // Signals are only actionable if the stock can be traded near its quote
+        let avg_dollar_volume = bars.filter(|b| !b.is_empty()).map(|bars| {
+            let recent = &bars[bars.len().saturating_sub(LIQUIDITY_LOOKBACK_BARS)..];
+            recent.iter().map(|b| b.close * b.volume).sum::<f64>() / recent.len() as f64
+        });
+        let relative_spread = snapshot_result
+            .as_ref()
+            .and_then(|s| s.last_quote.as_ref())
+            .and_then(|q| match (q.bid, q.ask) {
+                (Some(bid), Some(ask)) if bid > 0.0 && ask >= bid => {
+                    Some((ask - bid) / ((ask + bid) / 2.0))
+                }
+                _ => None,
+            });
+        if let Some(dollar_volume) = avg_dollar_volume {
+            let liquidity_tier = if dollar_volume >= 100_000_000.0 {
+                "High"
+            } else if dollar_volume >= 10_000_000.0 {
+                "Medium"
+            } else if dollar_volume >= MIN_LIQUID_DOLLAR_VOLUME {
+                "Low"
+            } else {
+                "Very Low"
+            };
+            let caution = dollar_volume < MIN_LIQUID_DOLLAR_VOLUME
+                || relative_spread.is_some_and(|s| s > WIDE_SPREAD_PCT);
+            if caution {
+                score_adj -= 0.05;
+            }
+
+            signals.insert(
+                "liquidity".to_string(),
+                json!({
+                    "avg_dollar_volume": dollar_volume,
+                    "relative_spread": relative_spread,
+                    "liquidity_tier": liquidity_tier,
+                    "signal": caution.then_some("Low Liquidity — Wide Spread"),
+                }),
+            );
+        }
+
+        // --- Sentiment vs Price Divergence (adaptive thresholds) ---
+        // Price shrugging off bad news (or fading good news) says more than either alone
+        let normalized_sentiment = sentiment
+            .and_then(|s| s.metrics.get("normalized_score"))
+            .and_then(|v| v.as_f64());
+        if let (Some(sentiment_score), Some(bars)) = (normalized_sentiment, bars) {
+            let lookback = SENTIMENT_DIVERGENCE_LOOKBACK_BARS;
+            if bars.len() > lookback * 2 {
+                let rolling_returns: Vec<f64> = bars
+                    .windows(lookback + 1)
+                    .filter(|w| w[0].close > 0.0)
+                    .map(|w| w[lookback].close / w[0].close - 1.0)
+                    .collect();
+                if let Some(&trailing_return) = rolling_returns.last() {
+                    let return_pct = adaptive::percentile_rank(trailing_return, &rolling_returns);
+                    let (failing_pct, resilient_pct) = SENTIMENT_DIVERGENCE_RETURN_PCTS;
+                    let divergence = if sentiment_score <= -SENTIMENT_DIVERGENCE_MIN_SCORE
+                        && return_pct >= resilient_pct
+                    {
+                        score_adj += 0.02;
+                        Some("Bullish Divergence (bad news, price resilient)")
+                    } else if sentiment_score >= SENTIMENT_DIVERGENCE_MIN_SCORE
+                        && return_pct <= failing_pct
+                    {
+                        score_adj -= 0.02;
+                        Some("Bearish Divergence (good news, price failing)")
+                    } else {
+                        None
+                    };
+
+                    signals.insert(
+                        "sentiment_price_divergence".to_string(),
+                        json!({
+                            "normalized_sentiment": sentiment_score,
+                            "trailing_return": trailing_return,
+                            "trailing_return_percentile": return_pct,
+                            "lookback_bars": lookback,
+                            "signal": divergence,
+                        }),
+                    );
+                }
+            }
+        }
+
+        // --- Smart Money Composite (adaptive thresholds) ---
+        // Combines insider buys + options positioning + volume accumulation
+        let mut smart_money_score = 0.0_f64;
+        if let Some(insider_sig) = signals.get("insiders") {
+            if insider_sig.get("signal").and_then(|s| s.as_str()) == Some("bullish") {
+                smart_money_score += 1.0;
+            }
+            if insider_sig
+                .get("executive_buys")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+                >= 2
+            {
+                smart_money_score += 1.0;
+            }
+            if insider_sig
+                .get("clustered_buying")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+            {
+                smart_money_score += 1.0;
+            }
+        }
+        if let Some(opt_sig) = signals.get("options") {
+            if opt_sig.get("put_call_signal").and_then(|s| s.as_str()) == Some("bullish") {
+                smart_money_score += 1.0;
+            }
         }
         // Check for quiet accumulation using adaptive volume decline threshold
         if let Some(bars) = bars {
@@ -1397,7 +3053,15 @@ impl AnalysisOrchestrator {
         let earnings_nlp_url = std::env::var("ML_EARNINGS_NLP_URL")
             .unwrap_or_else(|_| "http://localhost:8005".to_string());
         let earnings_client = ml_client::EarningsNlpClient::new(earnings_nlp_url);
-        match earnings_client.analyze_earnings(symbol).await {
+        let earnings_result = match as_of {
+            // The NLP service only scores the latest transcript
+            Some(_) => Err("skipped for point-in-time analysis".to_string()),
+            None => earnings_client
+                .analyze_earnings(symbol)
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        match earnings_result {
             Ok(nlp) if nlp.confidence > 0.0 && nlp.data_source != "none" => {
                 // Tone-based adjustment
                 match nlp.overall_tone.as_str() {
@@ -1455,7 +3119,7 @@ impl AnalysisOrchestrator {
                 };
 
                 // Compare vs SPY with adaptive thresholds
-                if let Ok(spy_bars) = self.get_bars("SPY", Timeframe::Day1, 30).await {
+                if let Ok(spy_bars) = self.get_bars_at("SPY", Timeframe::Day1, 30, as_of).await {
                     if spy_bars.len() >= 20 {
                         let spy_return_20d = {
                             let p0 = spy_bars[spy_bars.len() - 20].close;
@@ -1529,9 +3193,150 @@ impl AnalysisOrchestrator {
             }
         }
 
+        // --- 52-Week Range Position ---
+        // Always from a year of daily bars, whatever timeframe/lookback the caller used
+        // (the default Day1/365 analysis hits the same cache entry)
+        if let Ok(year_bars) = self.get_bars_at(symbol, Timeframe::Day1, 365, as_of).await {
+            if let Some((last, prior)) = year_bars.split_last() {
+                if !prior.is_empty() {
+                    let prior_high = prior.iter().map(|b| b.high).fold(f64::MIN, f64::max);
+                    let high_52w = prior_high.max(last.high);
+                    let low_52w = year_bars.iter().map(|b| b.low).fold(f64::MAX, f64::min);
+                    let price = current_price.filter(|&p| p > 0.0).unwrap_or(last.close);
+
+                    if high_52w > low_52w && low_52w > 0.0 {
+                        let pct_of_range =
+                            ((price - low_52w) / (high_52w - low_52w) * 100.0).clamp(0.0, 100.0);
+
+                        let range_signal = if price > prior_high {
+                            score_adj += 0.02;
+                            Some("Breaking Out of Range")
+                        } else if price >= high_52w * (1.0 - RANGE_PROXIMITY_PCT) {
+                            score_adj += 0.01;
+                            Some("Near 52-Week High")
+                        } else if price <= low_52w * (1.0 + RANGE_PROXIMITY_PCT) {
+                            score_adj -= 0.01;
+                            Some("Near 52-Week Low")
+                        } else {
+                            None
+                        };
+
+                        signals.insert(
+                            "range_52w".to_string(),
+                            json!({
+                                "high_52w": high_52w,
+                                "low_52w": low_52w,
+                                "pct_of_52w_range": pct_of_range,
+                                "pct_from_high": (price - high_52w) / high_52w * 100.0,
+                                "signal": range_signal,
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+
         (serde_json::Value::Object(signals), score_adj)
     }
 
+    /// Relative strength rating (1–99) of `symbol`'s weighted 3/6/9/12-month
+    /// return against every US stock. The universe ranking is cached and only
+    /// rebuilt once stale, so rating many symbols costs one market-wide fetch.
+    pub async fn compute_rs_rating(&self, symbol: &str) -> Result<u8, AnalysisError> {
+        self.rs_universe()
+            .await?
+            .rating(symbol)
+            .ok_or_else(|| AnalysisError::InvalidData(format!("{} not in RS universe", symbol)))
+    }
+
+    /// Current universe ranking, rebuilding it first when missing or stale
+    async fn rs_universe(&self) -> Result<Arc<RsUniverse>, AnalysisError> {
+        if let Some(universe) = self.rs_universe.read().await.as_ref() {
+            if !universe.is_stale(self.clock.as_ref()) {
+                return Ok(Arc::clone(universe));
+            }
+        }
+        let mut slot = self.rs_universe.write().await;
+        // Another caller may have rebuilt it while we waited for the lock
+        if let Some(universe) = slot.as_ref() {
+            if !universe.is_stale(self.clock.as_ref()) {
+                return Ok(Arc::clone(universe));
+            }
+        }
+        let snapshots = self.get_cached_all_snapshots().await?;
+        let universe = Arc::new(
+            RsUniverse::build(&self.polygon_client, &snapshots, self.clock.as_ref()).await?,
+        );
+        *slot = Some(Arc::clone(&universe));
+        Ok(universe)
+    }
+
+    /// All-tickers snapshot, reused for `CacheConfig::all_snapshots_ttl` seconds.
+    /// Concurrent callers with a stale cache share a single fetch.
+    pub async fn get_cached_all_snapshots(
+        &self,
+    ) -> Result<Arc<Vec<polygon_client::AllSnapshotsTicker>>, AnalysisError> {
+        self.snapshot_cache
+            .get(&self.polygon_client, self.cache_config.all_snapshots_ttl)
+            .await
+    }
+
+    /// Keep the all-tickers snapshot warm by refetching it every `interval`
+    /// (set it below `all_snapshots_ttl`). Abort the returned handle to stop.
+    pub fn spawn_snapshot_refresher(
+        &self,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let client = self.polygon_client.clone();
+        let cache = self.snapshot_cache.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = cache.refresh(&client).await {
+                    tracing::warn!("All-tickers snapshot refresh failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// RS rating from whatever ranking is cached, without waiting on the network.
+    /// With `with_rs_background_refresh`, a missing or stale ranking is rebuilt
+    /// in the background for later calls.
+    fn cached_rs_rating(&self, symbol: &str) -> Option<u8> {
+        let current = self
+            .rs_universe
+            .try_read()
+            .ok()
+            .and_then(|slot| slot.clone());
+
+        let needs_refresh = self.rs_background_refresh
+            && current
+                .as_ref()
+                .is_none_or(|u| u.is_stale(self.clock.as_ref()));
+        if needs_refresh && !self.rs_refreshing.swap(true, Ordering::SeqCst) {
+            let client = self.polygon_client.clone();
+            let snapshot_cache = self.snapshot_cache.clone();
+            let snapshots_ttl = self.cache_config.all_snapshots_ttl;
+            let slot = Arc::clone(&self.rs_universe);
+            let refreshing = Arc::clone(&self.rs_refreshing);
+            let clock = Arc::clone(&self.clock);
+            tokio::spawn(async move {
+                let built = match snapshot_cache.get(&client, snapshots_ttl).await {
+                    Ok(snapshots) => RsUniverse::build(&client, &snapshots, clock.as_ref()).await,
+                    Err(e) => Err(e),
+                };
+                match built {
+                    Ok(universe) => *slot.write().await = Some(Arc::new(universe)),
+                    Err(e) => tracing::warn!("RS universe refresh failed: {}", e),
+                }
+                refreshing.store(false, Ordering::SeqCst);
+            });
+        }
+
+        current.and_then(|u| u.rating(symbol))
+    }
+
     fn generate_recommendation(&self, signal: &SignalStrength, confidence: f64) -> String {
         let action = match signal {
             SignalStrength::StrongBuy => "Strong Buy",
@@ -1561,31 +3366,23 @@ impl AnalysisOrchestrator {
         )
     }
 
-    /// Get historical bars for a symbol (cached, 5-min TTL)
+    /// Get historical bars for a symbol (cached, see `CacheConfig`)
     pub async fn get_bars(
         &self,
         symbol: &str,
         timeframe: Timeframe,
         days_back: i64,
     ) -> Result<Vec<Bar>, AnalysisError> {
-        let (multiplier, span) = match timeframe {
-            Timeframe::Minute1 => (1, "minute"),
-            Timeframe::Minute5 => (5, "minute"),
-            Timeframe::Minute15 => (15, "minute"),
-            Timeframe::Minute30 => (30, "minute"),
-            Timeframe::Hour1 => (1, "hour"),
-            Timeframe::Hour4 => (4, "hour"),
-            Timeframe::Day1 => (1, "day"),
-            Timeframe::Week1 => (1, "week"),
-            Timeframe::Month1 => (1, "month"),
-        };
+        let (multiplier, span) = aggregate_params(timeframe);
 
         let cache_key = format!("{}:{}:{}:{}", symbol, multiplier, span, days_back);
+        let mut stale = None;
         if let Some(entry) = self.bars_cache.get(&cache_key) {
             let age = (Utc::now() - entry.cached_at).num_seconds();
-            if age < CACHE_TTL_SECS {
+            if age < self.cache_config.bars_ttl {
                 return Ok(entry.data.clone());
             }
+            stale = Some(entry.data.clone());
         }
 
         // Fast superset lookup via secondary index instead of scanning all cache entries.
@@ -1597,7 +3394,7 @@ impl AnalysisOrchestrator {
                     let superset_key = format!("{}:{}", prefix, cached_days);
                     if let Some(entry) = self.bars_cache.get(&superset_key) {
                         let age = (Utc::now() - entry.cached_at).num_seconds();
-                        if age < CACHE_TTL_SECS {
+                        if age < self.cache_config.bars_ttl {
                             let cutoff = Utc::now() - Duration::days(days_back);
                             let subset: Vec<Bar> = entry
                                 .data
@@ -1614,6 +3411,41 @@ impl AnalysisOrchestrator {
 
         let now = Utc::now();
         let start = now - Duration::days(days_back);
+
+        // A stale entry only lacks the bars since it was fetched: request those
+        // (from the last cached bar, which may still have been forming) and
+        // splice them in rather than downloading the whole history again.
+        if let Some(cached) = stale {
+            let full_fetch_at = self.bars_full_fetch_at.get(&cache_key).map(|t| *t);
+            if let Some(last) =
+                self.cache_config
+                    .bars_top_up_from(&cached, full_fetch_at, timeframe, now)
+            {
+                match self
+                    .polygon_client
+                    .get_aggregates(symbol, multiplier, span, last, now)
+                    .await
+                {
+                    Ok(fresh) => {
+                        let bars = merge_incremental_bars(cached, fresh, start);
+                        self.bars_cache.insert(
+                            cache_key,
+                            CacheEntry {
+                                data: bars.clone(),
+                                cached_at: Utc::now(),
+                            },
+                        );
+                        return Ok(bars);
+                    }
+                    Err(e) => tracing::debug!(
+                        "Incremental bar refresh failed for {}, refetching in full: {}",
+                        symbol,
+                        e
+                    ),
+                }
+            }
+        }
+
         let bars = self
             .polygon_client
             .get_aggregates(symbol, multiplier, span, start, now)
@@ -1626,6 +3458,7 @@ impl AnalysisOrchestrator {
                 cached_at: Utc::now(),
             },
         );
+        self.bars_full_fetch_at.insert(cache_key, Utc::now());
 
         // Update the secondary index
         self.bars_days_index
@@ -1636,12 +3469,108 @@ impl AnalysisOrchestrator {
         Ok(bars)
     }
 
-    /// Get ticker details (cached, 5-min TTL)
+    /// Get historical bars ending at `as_of` (cached, see `CacheConfig`).
+    /// Bars stamped after `as_of` are dropped so no lookahead data leaks in.
+    pub async fn get_bars_as_of(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        as_of: DateTime<Utc>,
+        days_back: i64,
+    ) -> Result<Vec<Bar>, AnalysisError> {
+        let (multiplier, span) = aggregate_params(timeframe);
+
+        let cache_key = format!(
+            "{}:{}:{}:{}@{}",
+            symbol,
+            multiplier,
+            span,
+            days_back,
+            as_of.timestamp()
+        );
+        if let Some(entry) = self.bars_cache.get(&cache_key) {
+            let age = (Utc::now() - entry.cached_at).num_seconds();
+            if age < self.cache_config.bars_ttl {
+                return Ok(entry.data.clone());
+            }
+        }
+
+        let start = as_of - Duration::days(days_back);
+        let mut bars = self
+            .polygon_client
+            .get_aggregates(symbol, multiplier, span, start, as_of)
+            .await?;
+        bars.retain(|b| b.timestamp <= as_of);
+
+        self.bars_cache.insert(
+            cache_key,
+            CacheEntry {
+                data: bars.clone(),
+                cached_at: Utc::now(),
+            },
+        );
+
+        Ok(bars)
+    }
+
+    /// Recent extended-hours intraday bars behind the pre-market signal (cached
+    /// for `CacheConfig::bars_ttl`). `get_bars` keeps only regular-session bars,
+    /// so these are cached separately.
+    async fn get_premarket_bars(&self, symbol: &str) -> Result<Vec<Bar>, AnalysisError> {
+        if let Some(entry) = self.premarket_bars_cache.get(symbol) {
+            let age = (Utc::now() - entry.cached_at).num_seconds();
+            if age < self.cache_config.bars_ttl {
+                return Ok(entry.data.clone());
+            }
+        }
+
+        let now = Utc::now();
+        let bars = self
+            .polygon_client
+            .get_aggregates_with_session(
+                symbol,
+                premarket::PREMARKET_BAR_MINUTES,
+                "minute",
+                now - Duration::days(premarket::PREMARKET_LOOKBACK_DAYS),
+                now,
+                true,
+            )
+            .await?;
+
+        self.premarket_bars_cache.insert(
+            symbol.to_string(),
+            CacheEntry {
+                data: bars.clone(),
+                cached_at: Utc::now(),
+            },
+        );
+
+        Ok(bars)
+    }
+
+    /// Dispatch to `get_bars` or `get_bars_as_of` depending on whether a cutoff is set
+    async fn get_bars_at(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        days_back: i64,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Bar>, AnalysisError> {
+        match as_of {
+            Some(as_of) => {
+                self.get_bars_as_of(symbol, timeframe, as_of, days_back)
+                    .await
+            }
+            None => self.get_bars(symbol, timeframe, days_back).await,
+        }
+    }
+
+    /// Get ticker details (cached, see `CacheConfig`)
     pub async fn get_ticker_details(&self, symbol: &str) -> Result<TickerDetails, AnalysisError> {
         let cache_key = symbol.to_uppercase();
         if let Some(entry) = self.ticker_details_cache.get(&cache_key) {
             let age = (Utc::now() - entry.cached_at).num_seconds();
-            if age < CACHE_TTL_SECS {
+            if age < self.cache_config.ticker_details_ttl {
                 return Ok(entry.data.clone());
             }
         }
@@ -1659,12 +3588,12 @@ impl AnalysisOrchestrator {
         Ok(details)
     }
 
-    /// Get company financials (cached, 5-min TTL)
+    /// Get company financials (cached, see `CacheConfig`)
     pub async fn get_financials(&self, symbol: &str) -> Result<Vec<Financials>, AnalysisError> {
         let cache_key = symbol.to_uppercase();
         if let Some(entry) = self.financials_cache.get(&cache_key) {
             let age = (Utc::now() - entry.cached_at).num_seconds();
-            if age < CACHE_TTL_SECS {
+            if age < self.cache_config.financials_ttl {
                 return Ok(entry.data.clone());
             }
         }
@@ -1708,7 +3637,10 @@ impl AnalysisOrchestrator {
             .await
     }
 
-    /// Get news articles for a symbol (cached, 5-min TTL)
+    /// Get news articles for a symbol (cached, see `CacheConfig`): up to `limit`
+    /// from each configured `NewsSource` (Polygon and Finnhub by default),
+    /// near-duplicates collapsed, newest first. Fails only when no source
+    /// returned articles and at least one errored.
     pub async fn get_news(
         &self,
         symbol: &str,
@@ -1717,12 +3649,44 @@ impl AnalysisOrchestrator {
         let cache_key = format!("news:{}:{}", symbol, limit);
         if let Some(entry) = self.news_cache.get(&cache_key) {
             let age = (Utc::now() - entry.cached_at).num_seconds();
-            if age < CACHE_TTL_SECS {
+            if age < self.cache_config.news_ttl {
                 return Ok(entry.data.clone());
             }
         }
 
-        let articles = self.polygon_client.get_news(Some(symbol), limit).await?;
+        let fetches = self
+            .news_sources
+            .iter()
+            .map(|source| async move { (source.name(), source.fetch(symbol, limit).await) });
+        let mut merged = Vec::new();
+        let mut first_error = None;
+        for (name, result) in futures_util::future::join_all(fetches).await {
+            match result {
+                Ok(articles) => merged.extend(articles.into_iter().map(|mut article| {
+                    article.source.get_or_insert_with(|| name.to_string());
+                    article
+                })),
+                Err(e) => {
+                    tracing::warn!("{} news unavailable for {}: {}", name, symbol, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if merged.is_empty() {
+            if let Some(e) = first_error {
+                return Err(e);
+            }
+        }
+
+        let (mut articles, duplicates) = self.sentiment_analyzer.dedup_articles(&merged);
+        if duplicates > 0 {
+            tracing::debug!(
+                "Merged news for {}: {} duplicates removed",
+                symbol,
+                duplicates
+            );
+        }
+        articles.sort_by_key(|a| std::cmp::Reverse(a.published_utc));
 
         self.news_cache.insert(
             cache_key,
@@ -1735,14 +3699,14 @@ impl AnalysisOrchestrator {
         Ok(articles)
     }
 
-    /// Get analyst consensus data (cached, 5-min TTL).
+    /// Get analyst consensus data (cached, see `CacheConfig`).
     /// Fetches both consensus ratings and recent individual ratings sequentially.
     /// Returns empty data on any error (graceful degradation).
     pub async fn get_analyst_consensus(&self, symbol: &str) -> AnalystConsensusData {
         let cache_key = symbol.to_uppercase();
         if let Some(entry) = self.consensus_cache.get(&cache_key) {
             let age = (Utc::now() - entry.cached_at).num_seconds();
-            if age < CACHE_TTL_SECS {
+            if age < self.cache_config.consensus_ttl {
                 return entry.data.clone();
             }
         }
@@ -1779,3 +3743,809 @@ impl AnalysisOrchestrator {
         data
     }
 }
+
+/// Polygon aggregate (multiplier, timespan) for a timeframe
+fn aggregate_params(timeframe: Timeframe) -> (u32, &'static str) {
+    match timeframe {
+        Timeframe::Minute1 => (1, "minute"),
+        Timeframe::Minute5 => (5, "minute"),
+        Timeframe::Minute15 => (15, "minute"),
+        Timeframe::Minute30 => (30, "minute"),
+        Timeframe::Hour1 => (1, "hour"),
+        Timeframe::Hour4 => (4, "hour"),
+        Timeframe::Day1 => (1, "day"),
+        Timeframe::Week1 => (1, "week"),
+        Timeframe::Month1 => (1, "month"),
+    }
+}
+
+/// Append `fresh` bars to a cached series. Cached bars at or after the first
+/// fresh bar are replaced (the last cached bar may have been incomplete), and
+/// bars from before `start`'s date fall out of the window.
+fn merge_incremental_bars(mut cached: Vec<Bar>, fresh: Vec<Bar>, start: DateTime<Utc>) -> Vec<Bar> {
+    if let Some(first) = fresh.first() {
+        cached.retain(|b| b.timestamp < first.timestamp);
+    }
+    cached.extend(fresh);
+    let first_day = start.date_naive();
+    cached.retain(|b| b.timestamp.date_naive() >= first_day);
+    cached
+}
+
+/// Largest number of distinct insiders buying within any rolling `window_days`
+/// span of filing dates, and the date that span starts on.
+fn max_insider_buy_cluster(
+    buys: &mut [(chrono::NaiveDate, &str)],
+    window_days: i64,
+) -> (usize, Option<chrono::NaiveDate>) {
+    buys.sort_by_key(|(date, _)| *date);
+    let mut best = (0, None);
+    for (i, (start, _)) in buys.iter().enumerate() {
+        let end = *start + Duration::days(window_days);
+        let insiders: std::collections::HashSet<String> = buys[i..]
+            .iter()
+            .take_while(|(date, _)| *date < end)
+            .map(|(_, name)| name.trim().to_lowercase())
+            .collect();
+        if insiders.len() > best.0 {
+            best = (insiders.len(), Some(*start));
+        }
+    }
+    best
+}
+
+/// Annual dividend growth derived from a payment history
+struct DividendGrowth {
+    /// Growth rate of the annualized dividend over the last `DIVIDEND_CAGR_YEARS`
+    /// (or the whole history when shorter), as a fraction
+    cagr: Option<f64>,
+    /// Consecutive calendar years, ending with the latest, of a higher dividend
+    streak_years: usize,
+    /// Whether every year in the streak grew by a similar amount
+    steady: bool,
+    /// Calendar years of regular dividends on record
+    years: usize,
+}
+
+/// Chowder number a dividend payer should reach: 8 for utilities, 12 otherwise
+fn chowder_threshold(sic_description: Option<&str>) -> f64 {
+    if fundamental_analysis::classify_sector(sic_description) == "utilities" {
+        UTILITY_CHOWDER_THRESHOLD
+    } else {
+        CHOWDER_THRESHOLD
+    }
+}
+
+/// Year-over-year growth of the regular dividend.
+///
+/// Special and one-off payments are dropped; each remaining payment is
+/// annualized by its `frequency` so a switch from semi-annual to quarterly
+/// payments doesn't read as a raise. A year's rate is the mean of its
+/// annualized payments.
+fn dividend_growth(dividends: &[polygon_client::DividendInfo]) -> Option<DividendGrowth> {
+    let mut by_year: std::collections::BTreeMap<i32, (f64, usize)> =
+        std::collections::BTreeMap::new();
+    for d in dividends {
+        let regular = d
+            .dividend_type
+            .as_deref()
+            .is_none_or(|t| t.eq_ignore_ascii_case("CD"));
+        let freq = d.frequency.unwrap_or(4);
+        let (Some(amount), Some(year)) = (
+            d.cash_amount,
+            d.ex_dividend_date
+                .as_deref()
+                .and_then(|date| date.get(..4))
+                .and_then(|y| y.parse::<i32>().ok()),
+        ) else {
+            continue;
+        };
+        if !regular || freq <= 0 || amount <= 0.0 {
+            continue;
+        }
+        let entry = by_year.entry(year).or_insert((0.0, 0));
+        entry.0 += amount * freq as f64;
+        entry.1 += 1;
+    }
+    let rates: Vec<(i32, f64)> = by_year
+        .into_iter()
+        .map(|(year, (sum, n))| (year, sum / n as f64))
+        .collect();
+    if rates.is_empty() {
+        return None;
+    }
+
+    let mut growths = Vec::new();
+    for pair in rates.windows(2).rev() {
+        let ((prev_year, prev), (year, rate)) = (pair[0], pair[1]);
+        if year - prev_year != 1 || rate <= prev {
+            break;
+        }
+        growths.push(rate / prev - 1.0);
+    }
+    let steady = !growths.is_empty() && {
+        let mean = growths.iter().sum::<f64>() / growths.len() as f64;
+        let var = growths.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / growths.len() as f64;
+        var.sqrt() <= mean
+    };
+
+    let (last_year, last) = rates[rates.len() - 1];
+    let cagr = rates
+        .iter()
+        .find(|(year, _)| last_year - year <= DIVIDEND_CAGR_YEARS as i32)
+        .filter(|(year, _)| *year < last_year)
+        .map(|&(year, first)| (last / first).powf(1.0 / (last_year - year) as f64) - 1.0);
+
+    Some(DividendGrowth {
+        cagr,
+        streak_years: growths.len(),
+        steady,
+        years: rates.len(),
+    })
+}
+
+/// Penny-keyed strike -> (call IV, put IV)
+type StrikeIvs = HashMap<i64, (Option<f64>, Option<f64>)>;
+
+/// At-the-money implied volatility and contract count per expiration date.
+///
+/// ATM IV is the mean call/put IV at the strike nearest `spot` that quotes
+/// both; when no strike does, the nearest single-sided IV is used. Expiries
+/// with no IV within `ATM_MAX_DISTANCE_PCT` of spot are left out.
+fn atm_iv_by_expiry(
+    options: &[polygon_client::OptionsContractSnapshot],
+    spot: f64,
+) -> std::collections::BTreeMap<chrono::NaiveDate, (f64, usize)> {
+    // expiry -> (contract count, strike ivs)
+    let mut chains: std::collections::BTreeMap<chrono::NaiveDate, (usize, StrikeIvs)> =
+        std::collections::BTreeMap::new();
+    for opt in options {
+        let Some(details) = &opt.details else {
+            continue;
+        };
+        let Some(expiry) = details
+            .expiration_date
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        else {
+            continue;
+        };
+        let chain = chains.entry(expiry).or_default();
+        chain.0 += 1;
+
+        let (Some(strike), Some(iv)) = (
+            details.strike_price,
+            opt.implied_volatility
+                .filter(|iv| *iv > 0.0 && iv.is_finite()),
+        ) else {
+            continue;
+        };
+        if (strike - spot).abs() > spot * ATM_MAX_DISTANCE_PCT {
+            continue;
+        }
+        let entry = chain.1.entry((strike * 100.0) as i64).or_default();
+        match details.contract_type.as_deref() {
+            Some(t) if t.eq_ignore_ascii_case("call") => entry.0 = Some(iv),
+            Some(t) if t.eq_ignore_ascii_case("put") => entry.1 = Some(iv),
+            _ => {}
+        }
+    }
+
+    let spot_key = spot * 100.0;
+    chains
+        .into_iter()
+        .filter_map(|(expiry, (count, strikes))| {
+            let nearest = |paired_only: bool| {
+                strikes
+                    .iter()
+                    .filter_map(|(&k, &ivs)| {
+                        let iv = match ivs {
+                            (Some(c), Some(p)) => (c + p) / 2.0,
+                            (Some(iv), None) | (None, Some(iv)) if !paired_only => iv,
+                            _ => return None,
+                        };
+                        Some(((k as f64 - spot_key).abs(), iv))
+                    })
+                    .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+                    .map(|(_, iv)| iv)
+            };
+            let iv = nearest(true).or_else(|| nearest(false))?;
+            Some((expiry, (iv, count)))
+        })
+        .collect()
+}
+
+/// Least-squares slope of ATM IV against days to expiry, per 30 days.
+/// Needs at least two expiries.
+fn iv_term_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_d = points.iter().map(|(d, _)| d).sum::<f64>() / n;
+    let mean_iv = points.iter().map(|(_, iv)| iv).sum::<f64>() / n;
+    let cov: f64 = points
+        .iter()
+        .map(|(d, iv)| (d - mean_d) * (iv - mean_iv))
+        .sum();
+    let var: f64 = points.iter().map(|(d, _)| (d - mean_d).powi(2)).sum();
+    (var > 0.0).then(|| cov / var * 30.0)
+}
+
+/// Contracts whose volume is at least `UNUSUAL_OPTIONS_VOL_OI_RATIO` times their
+/// open interest (and `UNUSUAL_OPTIONS_MIN_VOLUME`), highest ratio first.
+fn unusual_options_activity(
+    options: &[polygon_client::OptionsContractSnapshot],
+) -> Vec<(&polygon_client::OptionsContractSnapshot, f64)> {
+    let mut unusual: Vec<_> = options
+        .iter()
+        .filter(|opt| opt.volume().unwrap_or(0) >= UNUSUAL_OPTIONS_MIN_VOLUME)
+        .filter_map(|opt| {
+            opt.volume_oi_ratio()
+                .filter(|ratio| *ratio >= UNUSUAL_OPTIONS_VOL_OI_RATIO)
+                .map(|ratio| (opt, ratio))
+        })
+        .collect();
+    unusual.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    unusual
+}
+
+/// Strike (penny-keyed, as in the OI maps) at which the in-the-money value of
+/// all open calls and puts is smallest, i.e. where option writers pay out least.
+fn max_pain_strike(
+    call_oi: &std::collections::HashMap<i64, i64>,
+    put_oi: &std::collections::HashMap<i64, i64>,
+) -> Option<f64> {
+    let mut candidates: Vec<i64> = call_oi.keys().chain(put_oi.keys()).copied().collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .map(|settle| {
+            let call_payout: f64 = call_oi
+                .iter()
+                .map(|(&strike, &oi)| (settle - strike).max(0) as f64 * oi as f64)
+                .sum();
+            let put_payout: f64 = put_oi
+                .iter()
+                .map(|(&strike, &oi)| (strike - settle).max(0) as f64 * oi as f64)
+                .sum();
+            (settle, call_payout + put_payout)
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(settle, _)| settle as f64 / 100.0)
+}
+
+/// Whether a statement would have been public by `as_of`.
+///
+/// Uses the reported filing date when present; otherwise assumes a calendar fiscal
+/// year and the SEC's 45-day 10-Q deadline (90 days for the 10-K) after period end.
+fn financials_filed_by(financials: &Financials, as_of: DateTime<Utc>) -> bool {
+    if let Some(filed) = financials.filing_date {
+        return filed <= as_of.date_naive();
+    }
+    let (end_month, filing_lag_days) = match financials.fiscal_period.as_str() {
+        "Q1" => (3, 45),
+        "Q2" => (6, 45),
+        "Q3" => (9, 45),
+        // Fourth quarters are reported with the 10-K rather than a 10-Q
+        _ => (12, 90), // Q4 / FY / TTM
+    };
+    let period_end = chrono::NaiveDate::from_ymd_opt(financials.fiscal_year, end_month, 1)
+        .and_then(|d| d.checked_add_months(chrono::Months::new(1)))
+        .and_then(|d| d.pred_opt());
+    match period_end {
+        Some(end) => end + Duration::days(filing_lag_days) <= as_of.date_naive(),
+        None => false,
+    }
+}
+
+/// How daily and weekly technical signals relate: "aligned_bullish", "aligned_bearish",
+/// "divergent" (opposite directions), or "mixed" (at least one is neutral)
+fn timeframe_alignment(daily: &SignalStrength, weekly: &SignalStrength) -> &'static str {
+    match (daily.to_score().signum(), weekly.to_score().signum()) {
+        (1, 1) => "aligned_bullish",
+        (-1, -1) => "aligned_bearish",
+        (1, -1) | (-1, 1) => "divergent",
+        _ => "mixed",
+    }
+}
+
+/// Financials reported in a different currency than the one the stock is quoted in
+struct CurrencyMismatch {
+    financials_currency: String,
+    price_currency: String,
+    /// Units of `price_currency` per unit of `financials_currency`, when available
+    fx_rate: Option<f64>,
+}
+
+impl CurrencyMismatch {
+    /// Compare the financials' reporting currency with the ticker's quote currency;
+    /// `None` when they match or either is unknown
+    fn detect(financials: &[Financials], details: Option<&TickerDetails>) -> Option<Self> {
+        let financials_currency = financials
+            .iter()
+            .find_map(|f| f.currency.as_deref())?
+            .to_uppercase();
+        let price_currency = details?.currency_name.as_deref()?.to_uppercase();
+        (financials_currency != price_currency).then_some(Self {
+            financials_currency,
+            price_currency,
+            fx_rate: None,
+        })
+    }
+
+    /// Record the currencies and conversion in the fundamental metrics.
+    /// `currency_mismatch` stays true only when no rate could be applied.
+    fn annotate(&self, result: &mut AnalysisResult) {
+        if let Some(metrics) = result.metrics.as_object_mut() {
+            metrics.insert(
+                "financials_currency".to_string(),
+                json!(self.financials_currency),
+            );
+            metrics.insert("price_currency".to_string(), json!(self.price_currency));
+            metrics.insert("fx_rate".to_string(), json!(self.fx_rate));
+            metrics.insert(
+                "currency_mismatch".to_string(),
+                json!(self.fx_rate.is_none()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn result(signal: SignalStrength) -> Option<AnalysisResult> {
+        Some(AnalysisResult {
+            symbol: "TEST".to_string(),
+            timestamp: Utc::now(),
+            signal,
+            confidence: 0.8,
+            reason: String::new(),
+            metrics: json!({}),
+            confidence_components: json!({}),
+        })
+    }
+
+    #[test]
+    fn test_conflict_penalty_custom_curve() {
+        let split = |orchestrator: &AnalysisOrchestrator| {
+            orchestrator
+                .combine_with_weights(
+                    "TEST",
+                    &result(SignalStrength::StrongBuy),
+                    &result(SignalStrength::StrongSell),
+                    &result(SignalStrength::WeakBuy),
+                    &result(SignalStrength::WeakSell),
+                    None,
+                    (25, 25, 25, 25),
+                )
+                .overall_confidence
+        };
+
+        // Default curve: full Strong Buy vs Strong Sell spread costs 0.30
+        let default = AnalysisOrchestrator::new("test".to_string());
+        assert!((split(&default) - 0.5).abs() < 1e-9);
+
+        // Custom curve: harsher penalty, and the cutoffs still see the split
+        let harsh =
+            AnalysisOrchestrator::new("test".to_string()).with_conflict_penalty(ConflictPenalty {
+                bullish_cutoff: 60,
+                bearish_cutoff: -60,
+                max_penalty: 0.6,
+            });
+        assert!((split(&harsh) - 0.2).abs() < 1e-9);
+
+        // Cutoffs beyond any engine's score: no conflict is detected
+        let lenient = ConflictPenalty {
+            bullish_cutoff: 101,
+            bearish_cutoff: -101,
+            max_penalty: 0.6,
+        };
+        assert_eq!(lenient.penalty(&[100, -100, 30, -30]), 0.0);
+        assert_eq!(ConflictPenalty::default().penalty(&[30, 0, 10]), 0.0);
+    }
+
+    #[test]
+    fn test_quality_floor_violation() {
+        let floor = QualityFloor::default();
+        assert!(floor.violation(Some(150.0), Some(2.5e12)).is_none());
+        // Unknown market cap falls back to the price check
+        assert!(floor.violation(Some(12.0), None).is_none());
+        assert_eq!(
+            floor.violation(Some(0.30), None).unwrap(),
+            "price $0.30 below $1.00 floor"
+        );
+        assert_eq!(
+            floor.violation(Some(4.0), Some(8e6)).unwrap(),
+            "market cap $8.0M below $50.0M floor"
+        );
+    }
+
+    fn daily_bars(count: usize) -> Vec<Bar> {
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 1, 2, 21, 0, 0).unwrap();
+        (0..count)
+            .map(|i| {
+                let close = 100.0 + i as f64 * 0.2 + (i as f64 * 0.7).sin() * 2.0;
+                Bar {
+                    timestamp: start + Duration::days(i as i64),
+                    open: close - 0.5,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close,
+                    volume: 1_000_000.0,
+                    vwap: None,
+                }
+            })
+            .collect()
+    }
+
+    fn ticker_details(market_cap: Option<f64>) -> TickerDetails {
+        TickerDetails {
+            ticker: "TEST".to_string(),
+            name: "Test Corp".to_string(),
+            market: "stocks".to_string(),
+            locale: "us".to_string(),
+            primary_exchange: "XNAS".to_string(),
+            ticker_type: "CS".to_string(),
+            active: true,
+            currency_name: None,
+            market_cap,
+            share_class_shares_outstanding: None,
+            weighted_shares_outstanding: None,
+            description: None,
+            homepage_url: None,
+            sic_description: None,
+            total_employees: None,
+            list_date: None,
+        }
+    }
+
+    #[test]
+    fn test_quality_floor_caps_confidence() {
+        let floor = QualityFloor {
+            max_confidence: 0.01,
+            ..QualityFloor::default()
+        };
+        let orchestrator = AnalysisOrchestrator::new("test".to_string()).with_quality_floor(floor);
+        let bars = daily_bars(120);
+        let analyze = |market_cap| {
+            orchestrator.analyze_offline(
+                "TEST",
+                &bars,
+                None,
+                &[],
+                &[],
+                Some(&ticker_details(market_cap)),
+            )
+        };
+
+        let microcap = analyze(Some(8e6));
+        assert!(microcap.low_quality_universe);
+        assert_eq!(microcap.overall_confidence, 0.01);
+
+        let large_cap = analyze(Some(2.5e12));
+        assert!(!large_cap.low_quality_universe);
+        assert!(large_cap.overall_confidence > 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_quality_floor_reject_fails_analysis() {
+        let floor = QualityFloor {
+            reject: true,
+            ..QualityFloor::default()
+        };
+        let orchestrator = AnalysisOrchestrator::new("test".to_string()).with_quality_floor(floor);
+        let data = SymbolData {
+            bars_result: Ok(daily_bars(120)),
+            financials_result: Ok(Vec::new()),
+            news_result: Ok(Vec::new()),
+            trend_news: Vec::new(),
+            ticker_details: Ok(ticker_details(Some(8e6))),
+            snapshot_result: Err(AnalysisError::InvalidData("offline".to_string())),
+            weekly_bars: None,
+            timeframe: Timeframe::Day1,
+            asset_class: AssetClass::Equity,
+            as_of: None,
+        };
+        let context = MarketContext {
+            spy_bars: None,
+            benchmark_symbol: DEFAULT_BENCHMARK.to_string(),
+            benchmark_bars: None,
+            iwm_bars: None,
+            iwd_bars: None,
+            iwf_bars: None,
+            risk_free_rate: None,
+            market_regime: None,
+            market_regime_detail: None,
+            macro_context: None,
+        };
+
+        let result = orchestrator
+            .analyze_with_context("TEST", data, &context)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AnalysisError::InvalidData(ref message)) if message.contains("quality floor")
+        ));
+    }
+
+    fn financials(fiscal_period: &str, fiscal_year: i32, filed: Option<NaiveDate>) -> Financials {
+        Financials {
+            symbol: "TEST".to_string(),
+            fiscal_period: fiscal_period.to_string(),
+            fiscal_year,
+            filing_date: filed,
+            revenue: Some(1_000.0),
+            gross_profit: Some(400.0),
+            operating_income: Some(200.0),
+            net_income: Some(150.0),
+            eps: Some(1.5),
+            total_assets: Some(5_000.0),
+            total_liabilities: Some(2_000.0),
+            shareholders_equity: Some(3_000.0),
+            cash_flow_operating: Some(180.0),
+            cash_flow_investing: Some(-50.0),
+            cash_flow_financing: Some(-30.0),
+            capital_expenditure: Some(40.0),
+            cost_of_revenue: Some(600.0),
+            accounts_receivable: None,
+            inventory: None,
+            accounts_payable: None,
+            income_tax_expense: None,
+            pretax_income: None,
+            weighted_average_shares: None,
+            currency: None,
+        }
+    }
+
+    #[test]
+    fn test_as_of_filing_age_measured_from_as_of() {
+        let as_of = chrono::TimeZone::with_ymd_and_hms(&Utc, 2021, 5, 10, 16, 0, 0).unwrap();
+        let filed = NaiveDate::from_ymd_opt(2021, 4, 30);
+        let orchestrator = AnalysisOrchestrator::new("test".to_string());
+
+        let result = orchestrator
+            .fundamental_analyzer_at(Some(as_of))
+            .analyze_with_consensus(
+                "TEST",
+                &[financials("Q1", 2021, filed)],
+                Some(50.0),
+                Some(100.0),
+                &AnalystConsensusData::default(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.metrics["financials_age_days"], json!(10));
+        assert_eq!(result.timestamp, as_of);
+    }
+
+    #[test]
+    fn test_as_of_sentiment_measured_from_as_of() {
+        let as_of = chrono::TimeZone::with_ymd_and_hms(&Utc, 2021, 5, 10, 16, 0, 0).unwrap();
+        let article = |id: &str, title: &str, hours_before: i64| NewsArticle {
+            id: id.to_string(),
+            title: title.to_string(),
+            author: None,
+            published_utc: as_of - chrono::Duration::hours(hours_before),
+            article_url: format!("https://example.com/{}", id),
+            description: None,
+            keywords: Vec::new(),
+            tickers: vec!["TEST".to_string()],
+            source: None,
+        };
+        let news = vec![
+            article("a", "TEST beats estimates on record cloud revenue", 2),
+            article("b", "TEST raises full-year guidance", 3),
+            article("c", "Analysts upgrade TEST after strong quarter", 5),
+            article("d", "TEST announces new share buyback program", 240),
+        ];
+        let orchestrator = AnalysisOrchestrator::new("test".to_string());
+
+        let at = orchestrator
+            .sentiment_analyzer_at(Some(as_of))
+            .analyze_lexicon_only("TEST", &news)
+            .unwrap();
+        assert_eq!(at.timestamp, as_of);
+        // Three of the four articles fall in the 24h before as_of
+        assert_eq!(at.metrics["buzz_ratio"], json!(6.0));
+
+        // Measured from today, the same articles are years old and draw no buzz
+        let now = orchestrator
+            .sentiment_analyzer_at(None)
+            .analyze_lexicon_only("TEST", &news)
+            .unwrap();
+        assert_eq!(now.metrics["buzz_ratio"], json!(0.0));
+    }
+
+    #[test]
+    fn test_supplied_tax_rate_used_for_roic_and_epv() {
+        let analyze = |orchestrator: &AnalysisOrchestrator| {
+            orchestrator
+                .fundamental_analyzer
+                .analyze_with_consensus(
+                    "TEST",
+                    &[financials("Q1", 2021, None)],
+                    Some(50.0),
+                    Some(100.0),
+                    &AnalystConsensusData::default(),
+                    None,
+                    None,
+                )
+                .unwrap()
+                .metrics
+        };
+        let default = analyze(&AnalysisOrchestrator::new("test".to_string()));
+        let supplied = analyze(
+            &AnalysisOrchestrator::new("test".to_string()).with_fundamental_analyzer(
+                FundamentalAnalysisEngine::new().with_tax_rate(Some(0.35)),
+            ),
+        );
+
+        assert_eq!(default["roic_tax_rate_source"], json!("default"));
+        assert_eq!(default["epv_tax_rate"], json!(0.21));
+        assert_eq!(supplied["roic_tax_rate_source"], json!("provided"));
+        assert_eq!(supplied["epv_tax_rate"], json!(0.35));
+        assert_eq!(supplied["epv_tax_rate_source"], json!("provided"));
+
+        let ratio = |key: &str| supplied[key].as_f64().unwrap() / default[key].as_f64().unwrap();
+        assert!((ratio("roic") - 0.65 / 0.79).abs() < 1e-9);
+        // The bigger debt tax shield lowers the WACC, so EPV falls by less than
+        // the after-tax earnings
+        assert!(ratio("epv") > 0.65 / 0.79 && ratio("epv") < 1.0);
+    }
+
+    #[test]
+    fn test_chowder_threshold_lower_for_utilities() {
+        // 4% yield + 6% dividend growth
+        let chowder = 10.0;
+        assert!(chowder >= chowder_threshold(Some("ELECTRIC SERVICES")));
+        assert!(chowder >= chowder_threshold(Some("NATURAL GAS DISTRIBUTION")));
+        assert!(chowder < chowder_threshold(Some("SERVICES-PREPACKAGED SOFTWARE")));
+        assert!(chowder < chowder_threshold(None));
+    }
+
+    #[test]
+    fn test_unusual_options_activity() {
+        let contract = |volume: Option<i64>, open_interest: Option<i64>, strike: f64| {
+            polygon_client::OptionsContractSnapshot {
+                details: Some(polygon_client::OptionsDetails {
+                    contract_type: Some("call".to_string()),
+                    strike_price: Some(strike),
+                    expiration_date: None,
+                    ticker: None,
+                }),
+                greeks: None,
+                implied_volatility: None,
+                open_interest,
+                day: Some(polygon_client::OptionsDay {
+                    volume,
+                    open: None,
+                    close: None,
+                    high: None,
+                    low: None,
+                }),
+            }
+        };
+        let options = vec![
+            contract(Some(1_200), Some(300), 100.0),
+            contract(Some(5_000), Some(100), 110.0), // 50x open interest
+            contract(Some(1_000), Some(500), 120.0), // Only 2x
+            contract(Some(400), Some(10), 130.0),    // Too few contracts traded
+            contract(Some(5_000), Some(0), 140.0),   // Brand-new contract, no OI
+            contract(Some(5_000), None, 150.0),
+            contract(None, Some(100), 160.0),
+            polygon_client::OptionsContractSnapshot {
+                day: None,
+                ..contract(None, Some(100), 170.0)
+            },
+        ];
+
+        let unusual = unusual_options_activity(&options);
+        let found: Vec<(f64, f64)> = unusual
+            .iter()
+            .map(|(opt, ratio)| (opt.details.as_ref().unwrap().strike_price.unwrap(), *ratio))
+            .collect();
+        assert_eq!(found, vec![(110.0, 50.0), (100.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_rs_universe_not_rebuilt_unless_enabled() {
+        let orchestrator = AnalysisOrchestrator::new("test".to_string());
+        assert_eq!(orchestrator.cached_rs_rating("AAPL"), None);
+        assert!(!orchestrator.rs_refreshing.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_undated_q4_not_filed_until_10k_deadline() {
+        // Sixty days after the fiscal year end: past the 10-Q deadline, before the 10-K's
+        let as_of = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 1, 16, 0, 0).unwrap();
+        assert!(!financials_filed_by(&financials("Q4", 2023, None), as_of));
+        assert!(!financials_filed_by(&financials("FY", 2023, None), as_of));
+        assert!(financials_filed_by(&financials("Q3", 2023, None), as_of));
+        assert!(financials_filed_by(
+            &financials("Q4", 2023, NaiveDate::from_ymd_opt(2024, 2, 20)),
+            as_of
+        ));
+    }
+
+    #[test]
+    fn test_fixed_clock_reaches_orchestrator_and_engines() {
+        let fixed = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 15, 16, 0, 0).unwrap();
+        let orchestrator =
+            AnalysisOrchestrator::new("test".to_string()).with_clock(Arc::new(FixedClock(fixed)));
+
+        let combined = orchestrator.combine_with_weights(
+            "TEST",
+            &result(SignalStrength::Buy),
+            &None,
+            &None,
+            &None,
+            None,
+            (25, 25, 25, 25),
+        );
+        assert_eq!(combined.timestamp, fixed);
+
+        let sentiment = orchestrator
+            .sentiment_analyzer
+            .analyze_lexicon_only("TEST", &[])
+            .unwrap();
+        assert_eq!(sentiment.timestamp, fixed);
+    }
+
+    #[test]
+    fn test_merge_incremental_bars_replaces_overlap_and_trims() {
+        let day = |d: u32, close: f64| Bar {
+            timestamp: chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, d, 5, 0, 0).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1.0,
+            vwap: None,
+        };
+        let cached = vec![day(1, 10.0), day(4, 11.0), day(5, 11.5)];
+        // Day 5 was still forming when cached; the refresh restates it
+        let fresh = vec![day(5, 12.0), day(6, 13.0)];
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 2, 12, 0, 0).unwrap();
+
+        let merged = merge_incremental_bars(cached.clone(), fresh, start);
+        let closes: Vec<f64> = merged.iter().map(|b| b.close).collect();
+        assert_eq!(closes, vec![11.0, 12.0, 13.0]);
+
+        // Nothing new yet: the cached bars stand
+        assert_eq!(merge_incremental_bars(cached, Vec::new(), start).len(), 2);
+    }
+
+    #[test]
+    fn test_bars_top_up_forces_periodic_full_refresh() {
+        let config = CacheConfig::default();
+        let now = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 6, 15, 0, 0).unwrap();
+        let last = now - Duration::days(1);
+        let cached = vec![Bar {
+            timestamp: last,
+            open: 10.0,
+            high: 10.0,
+            low: 10.0,
+            close: 10.0,
+            volume: 1.0,
+            vwap: None,
+        }];
+
+        let top_up =
+            |full_fetch_at| config.bars_top_up_from(&cached, full_fetch_at, Timeframe::Day1, now);
+        assert_eq!(top_up(Some(now - Duration::hours(2))), Some(last));
+        // A day of top-ups since the last full fetch: refetch to pick up restatements
+        assert_eq!(top_up(Some(now - Duration::days(1))), None);
+        assert_eq!(top_up(None), None);
+        assert_eq!(
+            config.bars_top_up_from(&[], Some(now), Timeframe::Day1, now),
+            None
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use analysis_core::{adaptive, Bar, MacroContext};
+use std::collections::HashMap;
+
+/// Rates proxy (long Treasuries; price falls as yields rise)
+pub(crate) const RATES_PROXY: &str = "TLT";
+/// Gold proxy
+pub(crate) const GOLD_PROXY: &str = "GLD";
+/// US dollar index proxy
+pub(crate) const DOLLAR_PROXY: &str = "UUP";
+/// VIX proxy (short-term VIX futures)
+pub(crate) const VOLATILITY_PROXY: &str = "VIXY";
+
+/// Calendar days of history fetched for each proxy
+pub(crate) const MACRO_HISTORY_DAYS: i64 = 90;
+
+/// Bars in the trailing return that sets each proxy's trend
+const MACRO_TREND_BARS: usize = 20;
+
+/// Rolling returns a proxy needs before its latest return can be ranked
+const MIN_TREND_SAMPLES: usize = 10;
+
+/// Percentiles of the latest return (vs the proxy's own rolling returns)
+/// below which it is falling and above which it is rising
+const TREND_PCTS: (f64, f64) = (0.3, 0.7);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trend {
+    Rising,
+    Falling,
+    Stable,
+}
+
+/// Latest `MACRO_TREND_BARS` return and its direction, ranked against every
+/// rolling return of the same length in the window
+fn trend(bars: Option<&[Bar]>) -> Option<(f64, Trend)> {
+    let closes: Vec<f64> = bars?.iter().map(|b| b.close).filter(|c| *c > 0.0).collect();
+    let returns: Vec<f64> = closes
+        .windows(MACRO_TREND_BARS + 1)
+        .map(|w| w[MACRO_TREND_BARS] / w[0] - 1.0)
+        .collect();
+    if returns.len() < MIN_TREND_SAMPLES {
+        return None;
+    }
+    let latest = *returns.last()?;
+    let pct = adaptive::percentile_rank(latest, &returns);
+    let direction = if pct >= TREND_PCTS.1 && latest > 0.0 {
+        Trend::Rising
+    } else if pct <= TREND_PCTS.0 && latest < 0.0 {
+        Trend::Falling
+    } else {
+        Trend::Stable
+    };
+    Some((latest, direction))
+}
+
+fn label(trend: Option<Trend>, rising: &'static str, falling: &'static str) -> &'static str {
+    match trend {
+        Some(Trend::Rising) => rising,
+        Some(Trend::Falling) => falling,
+        _ => "stable",
+    }
+}
+
+/// `MacroContext` labels for one combination of proxy trends
+#[derive(Debug, PartialEq)]
+struct MacroLabels {
+    rates: &'static str,
+    dollar: &'static str,
+    gold: &'static str,
+    volatility: &'static str,
+    risk_appetite: &'static str,
+    summary: String,
+}
+
+/// Label the backdrop from the TLT, GLD, UUP and VIX-proxy trends (`None` when
+/// a proxy lacked history, read as stable)
+fn classify(
+    tlt: Option<Trend>,
+    gld: Option<Trend>,
+    uup: Option<Trend>,
+    vix: Option<Trend>,
+) -> MacroLabels {
+    // Bond prices move inversely to yields
+    let rates_label = label(tlt, "falling", "rising");
+    let vol_label = label(vix, "rising", "falling");
+    let gold_label = label(gld, "rising", "falling");
+    let dollar_label = label(uup, "strengthening", "weakening");
+
+    // Rising volatility, or gold and Treasuries bid together, is a flight to safety
+    let risk_appetite = if vix == Some(Trend::Rising)
+        || (gld == Some(Trend::Rising) && tlt == Some(Trend::Rising))
+    {
+        "risk_off"
+    } else if vix == Some(Trend::Falling) && gld != Some(Trend::Rising) {
+        "risk_on"
+    } else {
+        "neutral"
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    match rates_label {
+        "rising" => parts.push("rising rates"),
+        "falling" => parts.push("falling rates"),
+        _ => {}
+    }
+    match dollar_label {
+        "strengthening" => parts.push("strong dollar"),
+        "weakening" => parts.push("weak dollar"),
+        _ => {}
+    }
+    if gold_label == "rising" {
+        parts.push("gold bid");
+    }
+    match risk_appetite {
+        "risk_off" => parts.push("risk-off"),
+        "risk_on" => parts.push("risk-on"),
+        _ => {}
+    }
+    let summary = if parts.is_empty() {
+        "neutral macro backdrop".to_string()
+    } else {
+        parts.join(", ")
+    };
+
+    MacroLabels {
+        rates: rates_label,
+        dollar: dollar_label,
+        gold: gold_label,
+        volatility: vol_label,
+        risk_appetite,
+        summary,
+    }
+}
+
+/// Describe the macro backdrop from the four proxies. Returns `None` when no
+/// proxy has enough history.
+pub(crate) fn macro_context(
+    rates: Option<&[Bar]>,
+    gold: Option<&[Bar]>,
+    dollar: Option<&[Bar]>,
+    volatility: Option<&[Bar]>,
+) -> Option<MacroContext> {
+    let proxies = [
+        (RATES_PROXY, trend(rates)),
+        (GOLD_PROXY, trend(gold)),
+        (DOLLAR_PROXY, trend(dollar)),
+        (VOLATILITY_PROXY, trend(volatility)),
+    ];
+    let proxy_returns: HashMap<String, f64> = proxies
+        .iter()
+        .filter_map(|(ticker, t)| t.map(|(ret, _)| (ticker.to_string(), ret)))
+        .collect();
+    if proxy_returns.is_empty() {
+        return None;
+    }
+    let [tlt, gld, uup, vix] = proxies.map(|(_, t)| t.map(|(_, direction)| direction));
+    let labels = classify(tlt, gld, uup, vix);
+
+    Some(MacroContext {
+        rates: labels.rates.to_string(),
+        dollar: labels.dollar.to_string(),
+        gold: labels.gold.to_string(),
+        volatility: labels.volatility.to_string(),
+        risk_appetite: labels.risk_appetite.to_string(),
+        proxy_returns,
+        summary: labels.summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    const UP: Option<Trend> = Some(Trend::Rising);
+    const DOWN: Option<Trend> = Some(Trend::Falling);
+    const FLAT: Option<Trend> = Some(Trend::Stable);
+
+    #[test]
+    fn test_classify_trends() {
+        // (TLT, GLD, UUP, VIX) -> (rates, dollar, gold, volatility, risk appetite, summary)
+        let cases = [
+            (
+                [FLAT, FLAT, FLAT, FLAT],
+                ("stable", "stable", "stable", "stable", "neutral"),
+                "neutral macro backdrop",
+            ),
+            (
+                [None, None, None, None],
+                ("stable", "stable", "stable", "stable", "neutral"),
+                "neutral macro backdrop",
+            ),
+            (
+                [DOWN, FLAT, UP, UP],
+                ("rising", "strengthening", "stable", "rising", "risk_off"),
+                "rising rates, strong dollar, risk-off",
+            ),
+            (
+                [UP, UP, FLAT, FLAT],
+                ("falling", "stable", "rising", "stable", "risk_off"),
+                "falling rates, gold bid, risk-off",
+            ),
+            (
+                [FLAT, FLAT, DOWN, DOWN],
+                ("stable", "weakening", "stable", "falling", "risk_on"),
+                "weak dollar, risk-on",
+            ),
+            // Gold bid keeps falling volatility from reading as risk-on
+            (
+                [FLAT, UP, FLAT, DOWN],
+                ("stable", "stable", "rising", "falling", "neutral"),
+                "gold bid",
+            ),
+            (
+                [UP, DOWN, FLAT, None],
+                ("falling", "stable", "falling", "stable", "neutral"),
+                "falling rates",
+            ),
+        ];
+
+        for ([tlt, gld, uup, vix], (rates, dollar, gold, volatility, risk), summary) in cases {
+            let labels = classify(tlt, gld, uup, vix);
+            assert_eq!(
+                labels,
+                MacroLabels {
+                    rates,
+                    dollar,
+                    gold,
+                    volatility,
+                    risk_appetite: risk,
+                    summary: summary.to_string(),
+                },
+                "trends {:?}",
+                (tlt, gld, uup, vix)
+            );
+        }
+    }
+
+    #[test]
+    fn test_trend_needs_history() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 2, 21, 0, 0).unwrap();
+        let bars = |closes: &[f64]| -> Vec<Bar> {
+            closes
+                .iter()
+                .enumerate()
+                .map(|(i, &close)| Bar {
+                    timestamp: start + Duration::days(i as i64),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1.0,
+                    vwap: None,
+                })
+                .collect()
+        };
+
+        let short: Vec<f64> = (0..MACRO_TREND_BARS + MIN_TREND_SAMPLES - 1)
+            .map(|i| 100.0 + i as f64)
+            .collect();
+        assert_eq!(trend(Some(&bars(&short))), None);
+        assert!(macro_context(Some(&bars(&short)), None, None, None).is_none());
+
+        // Steady gains, then a sharp acceleration: the latest return tops the window
+        let rally: Vec<f64> = (0..60)
+            .map(|i| 100.0 * 1.001f64.powi(i.min(40)) * 1.02f64.powi((i - 40).max(0)))
+            .collect();
+        assert_eq!(trend(Some(&bars(&rally))).unwrap().1, Trend::Rising);
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::Utc;
+use std::sync::atomic::{AtomicI64, AtomicU32, Ordering};
+
+/// Consecutive signal-models failures that open the breaker
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Seconds the breaker stays open before a single probe call is let through
+const COOLDOWN_SECS: i64 = 60;
+
+/// Circuit breaker around the ML signal-models sidecar.
+///
+/// After `FAILURE_THRESHOLD` consecutive failures or timeouts, calls are skipped
+/// for `COOLDOWN_SECS`. Once the cooldown lapses exactly one caller probes the
+/// service: success closes the breaker, failure opens it for another cooldown.
+#[derive(Default)]
+pub struct MlCircuitBreaker {
+    consecutive_failures: AtomicU32,
+    /// Unix seconds until which calls are skipped, 0 while closed
+    open_until: AtomicI64,
+}
+
+impl MlCircuitBreaker {
+    /// Whether the caller may hit the service now
+    pub fn allow_request(&self) -> bool {
+        if self.consecutive_failures.load(Ordering::Relaxed) < FAILURE_THRESHOLD {
+            return true;
+        }
+        let now = Utc::now().timestamp();
+        let open_until = self.open_until.load(Ordering::Relaxed);
+        if now < open_until {
+            return false;
+        }
+        // Claim the probe slot; concurrent callers keep skipping until it resolves
+        self.open_until
+            .compare_exchange(
+                open_until,
+                now + COOLDOWN_SECS,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    pub fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        self.open_until.store(0, Ordering::Relaxed);
+    }
+
+    pub fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= FAILURE_THRESHOLD {
+            self.open_until
+                .store(Utc::now().timestamp() + COOLDOWN_SECS, Ordering::Relaxed);
+            if failures == FAILURE_THRESHOLD {
+                tracing::warn!(
+                    "Signal models failed {} times in a row; skipping for {}s",
+                    failures,
+                    COOLDOWN_SECS
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use super::AnalysisOrchestrator;
+use crate::correlation::CorrelationMatrix;
+use analysis_core::{SignalStrength, Timeframe, UnifiedAnalysis};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One position of a portfolio with the per-symbol read it contributes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioHolding {
+    pub symbol: String,
+    /// Weight after normalizing the analyzed holdings to sum to 1
+    pub weight: f64,
+    pub signal: SignalStrength,
+    pub confidence: f64,
+    /// Beta vs the benchmark, from the quant engine
+    pub beta: Option<f64>,
+    /// Annualized volatility in percent, from the quant engine
+    pub volatility: Option<f64>,
+    pub sector: String,
+}
+
+/// Weighted roll-up of per-symbol analyses for a set of holdings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioAnalysis {
+    pub holdings: Vec<PortfolioHolding>,
+    /// Σ weight × signal score, on the −100..100 scale
+    pub weighted_score: f64,
+    pub overall_signal: SignalStrength,
+    pub overall_confidence: f64,
+    /// Σ weight × beta over holdings with a beta (renormalized)
+    pub portfolio_beta: Option<f64>,
+    /// Annualized volatility in percent, √(wᵀ Σ w) using the return correlations;
+    /// `None` unless every holding has a volatility and correlations are available
+    pub portfolio_volatility: Option<f64>,
+    /// Total weight per sector
+    pub sector_weights: HashMap<String, f64>,
+    /// Largest single-sector weight
+    pub max_sector_weight: f64,
+    /// 1 / Σ weight², the number of equal-weight positions with the same concentration
+    pub effective_holdings: f64,
+    /// Weighted average pairwise return correlation between distinct holdings
+    pub average_correlation: Option<f64>,
+    /// 0–100: (1 − Σ weight²) × (1 − average correlation, floored at 0) × 100
+    pub diversification_score: f64,
+    pub correlation: Option<CorrelationMatrix>,
+    /// Holdings left out, as (symbol, reason)
+    pub errors: Vec<(String, String)>,
+}
+
+impl PortfolioAnalysis {
+    /// Aggregate analyzed holdings (weights need not sum to 1) with their
+    /// return correlations, if available.
+    fn from_holdings(
+        analyzed: Vec<(UnifiedAnalysis, f64)>,
+        correlation: Option<CorrelationMatrix>,
+        errors: Vec<(String, String)>,
+    ) -> Self {
+        let total_weight: f64 = analyzed.iter().map(|(_, w)| w).sum();
+        let holdings: Vec<PortfolioHolding> = analyzed
+            .into_iter()
+            .map(|(analysis, weight)| {
+                let quant_metric = |key: &str| {
+                    analysis
+                        .quantitative
+                        .as_ref()
+                        .and_then(|q| q.metrics.get(key))
+                        .and_then(|v| v.as_f64())
+                };
+                PortfolioHolding {
+                    weight: weight / total_weight,
+                    signal: analysis.overall_signal,
+                    confidence: analysis.overall_confidence,
+                    beta: quant_metric("beta"),
+                    volatility: quant_metric("volatility"),
+                    sector: analysis
+                        .fundamental
+                        .as_ref()
+                        .and_then(|f| f.metrics.get("sector"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    symbol: analysis.symbol,
+                }
+            })
+            .collect();
+
+        let weighted_score: f64 = holdings
+            .iter()
+            .map(|h| h.weight * h.signal.to_score() as f64)
+            .sum();
+        let overall_confidence: f64 = holdings.iter().map(|h| h.weight * h.confidence).sum();
+
+        let beta_weight: f64 = holdings
+            .iter()
+            .filter(|h| h.beta.is_some())
+            .map(|h| h.weight)
+            .sum();
+        let portfolio_beta = (beta_weight > 0.0).then(|| {
+            holdings
+                .iter()
+                .filter_map(|h| h.beta.map(|b| h.weight * b))
+                .sum::<f64>()
+                / beta_weight
+        });
+
+        let mut sector_weights: HashMap<String, f64> = HashMap::new();
+        for h in &holdings {
+            *sector_weights.entry(h.sector.clone()).or_insert(0.0) += h.weight;
+        }
+        let max_sector_weight = sector_weights.values().copied().fold(0.0, f64::max);
+
+        let hhi: f64 = holdings.iter().map(|h| h.weight * h.weight).sum();
+        let effective_holdings = if hhi > 0.0 { 1.0 / hhi } else { 0.0 };
+
+        // Correlation lookup by symbol, so holdings and matrix order needn't match
+        let rho = |a: &str, b: &str| -> Option<f64> {
+            let matrix = correlation.as_ref()?;
+            let i = matrix.symbols.iter().position(|s| s == a)?;
+            let j = matrix.symbols.iter().position(|s| s == b)?;
+            Some(matrix.matrix[i][j])
+        };
+
+        let (mut pair_weight, mut pair_corr) = (0.0, 0.0);
+        for (i, a) in holdings.iter().enumerate() {
+            for b in &holdings[i + 1..] {
+                if let Some(r) = rho(&a.symbol, &b.symbol) {
+                    pair_weight += a.weight * b.weight;
+                    pair_corr += a.weight * b.weight * r;
+                }
+            }
+        }
+        let average_correlation = (pair_weight > 0.0).then(|| pair_corr / pair_weight);
+
+        let portfolio_volatility = if holdings.iter().all(|h| h.volatility.is_some()) {
+            let mut variance = 0.0;
+            let mut complete = true;
+            for a in &holdings {
+                for b in &holdings {
+                    let r = if a.symbol == b.symbol {
+                        Some(1.0)
+                    } else {
+                        rho(&a.symbol, &b.symbol)
+                    };
+                    match (r, a.volatility, b.volatility) {
+                        (Some(r), Some(va), Some(vb)) => {
+                            variance += a.weight * b.weight * va * vb * r
+                        }
+                        _ => complete = false,
+                    }
+                }
+            }
+            (complete && !holdings.is_empty()).then(|| variance.max(0.0).sqrt())
+        } else {
+            None
+        };
+
+        let diversification_score =
+            (1.0 - hhi) * (1.0 - average_correlation.unwrap_or(0.0).max(0.0)) * 100.0;
+
+        Self {
+            holdings,
+            weighted_score,
+            overall_signal: SignalStrength::from_score(weighted_score.round() as i32),
+            overall_confidence,
+            portfolio_beta,
+            portfolio_volatility,
+            sector_weights,
+            max_sector_weight,
+            effective_holdings,
+            average_correlation,
+            diversification_score: diversification_score.clamp(0.0, 100.0),
+            correlation,
+            errors,
+        }
+    }
+}
+
+impl AnalysisOrchestrator {
+    /// Analyze a set of (symbol, weight) holdings as one portfolio.
+    ///
+    /// Each symbol is analyzed with `analyze_batch` (one shared market context),
+    /// then combined into a weighted signal, portfolio beta and volatility,
+    /// sector concentration and a diversification score. Holdings with a
+    /// non-positive weight or a failed analysis are reported in `errors` and the
+    /// remaining weights renormalized.
+    pub async fn analyze_portfolio(
+        &self,
+        holdings: &[(String, f64)],
+        timeframe: Timeframe,
+        days_back: i64,
+    ) -> PortfolioAnalysis {
+        let mut errors = Vec::new();
+        let valid: Vec<(&str, f64)> = holdings
+            .iter()
+            .filter_map(|(symbol, weight)| {
+                if weight.is_finite() && *weight > 0.0 {
+                    Some((symbol.as_str(), *weight))
+                } else {
+                    errors.push((symbol.clone(), format!("invalid weight {}", weight)));
+                    None
+                }
+            })
+            .collect();
+        let symbols: Vec<&str> = valid.iter().map(|(s, _)| *s).collect();
+
+        let (results, correlation) = tokio::join!(
+            self.analyze_batch(&symbols, timeframe, days_back),
+            self.correlation_matrix(&symbols, timeframe, days_back),
+        );
+        let correlation = correlation
+            .map_err(|e| tracing::warn!("Portfolio correlations unavailable: {}", e))
+            .ok();
+
+        let mut analyzed = Vec::with_capacity(valid.len());
+        for ((symbol, weight), result) in valid.into_iter().zip(results) {
+            match result {
+                Ok(analysis) => analyzed.push((analysis, weight)),
+                Err(e) => errors.push((symbol.to_string(), e.to_string())),
+            }
+        }
+
+        PortfolioAnalysis::from_holdings(analyzed, correlation, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analysis_core::AnalysisResult;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn analysis(
+        symbol: &str,
+        signal: SignalStrength,
+        confidence: f64,
+        quant: serde_json::Value,
+        sector: &str,
+    ) -> UnifiedAnalysis {
+        let result = |metrics: serde_json::Value| AnalysisResult {
+            symbol: symbol.to_string(),
+            timestamp: Utc::now(),
+            signal,
+            confidence,
+            reason: String::new(),
+            metrics,
+            confidence_components: json!({}),
+        };
+        UnifiedAnalysis {
+            symbol: symbol.to_string(),
+            name: None,
+            timestamp: Utc::now(),
+            current_price: Some(100.0),
+            technical: None,
+            fundamental: Some(result(json!({ "sector": sector }))),
+            quantitative: Some(result(quant)),
+            sentiment: None,
+            overall_signal: signal,
+            overall_confidence: confidence,
+            recommendation: String::new(),
+            market_regime: None,
+            market_regime_detail: None,
+            conviction_tier: None,
+            time_horizon_signals: None,
+            supplementary_signals: None,
+            engine_errors: Vec::new(),
+            days_to_earnings: None,
+            earnings_blackout: false,
+            rs_rating: None,
+            has_sufficient_data: true,
+            multi_timeframe_alignment: None,
+            macro_context: None,
+            suggested_position_pct: None,
+            low_quality_universe: false,
+        }
+    }
+
+    fn holdings() -> Vec<(UnifiedAnalysis, f64)> {
+        vec![
+            (
+                analysis(
+                    "AAA",
+                    SignalStrength::Buy,
+                    0.8,
+                    json!({"beta": 1.2, "volatility": 20.0}),
+                    "Tech",
+                ),
+                2.0,
+            ),
+            (
+                analysis(
+                    "BBB",
+                    SignalStrength::Sell,
+                    0.4,
+                    json!({"beta": 0.8, "volatility": 10.0}),
+                    "Tech",
+                ),
+                1.0,
+            ),
+            (
+                analysis(
+                    "CCC",
+                    SignalStrength::Neutral,
+                    0.6,
+                    json!({"volatility": 30.0}),
+                    "Energy",
+                ),
+                1.0,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_from_holdings_weights_and_correlations() {
+        // Matrix order differs from the holdings on purpose
+        let correlation = CorrelationMatrix {
+            symbols: vec!["CCC".to_string(), "AAA".to_string(), "BBB".to_string()],
+            matrix: vec![
+                vec![1.0, 0.0, -0.2],
+                vec![0.0, 1.0, 0.5],
+                vec![-0.2, 0.5, 1.0],
+            ],
+            sample_size: 60,
+        };
+        let portfolio = PortfolioAnalysis::from_holdings(holdings(), Some(correlation), vec![]);
+
+        let weights: Vec<f64> = portfolio.holdings.iter().map(|h| h.weight).collect();
+        assert_eq!(weights, vec![0.5, 0.25, 0.25]);
+        // 0.5 × 60 − 0.25 × 60
+        assert!((portfolio.weighted_score - 15.0).abs() < 1e-9);
+        assert_eq!(portfolio.overall_signal, SignalStrength::WeakBuy);
+        assert!((portfolio.overall_confidence - 0.65).abs() < 1e-9);
+        // CCC has no beta, so the other two are renormalized: 0.8 / 0.75
+        assert!((portfolio.portfolio_beta.unwrap() - 0.8 / 0.75).abs() < 1e-9);
+        assert!((portfolio.sector_weights["Tech"] - 0.75).abs() < 1e-9);
+        assert!((portfolio.max_sector_weight - 0.75).abs() < 1e-9);
+        // Σw² = 0.375
+        assert!((portfolio.effective_holdings - 1.0 / 0.375).abs() < 1e-9);
+        // (0.125 × 0.5 + 0.125 × 0 + 0.0625 × −0.2) / 0.3125
+        assert!((portfolio.average_correlation.unwrap() - 0.16).abs() < 1e-9);
+        // Diagonal 162.5, AAA/BBB 2 × 12.5, BBB/CCC 2 × −3.75
+        assert!((portfolio.portfolio_volatility.unwrap() - 180.0_f64.sqrt()).abs() < 1e-9);
+        assert!((portfolio.diversification_score - 52.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_holdings_without_correlations() {
+        let errors = vec![("DDD".to_string(), "invalid weight 0".to_string())];
+        let portfolio = PortfolioAnalysis::from_holdings(holdings(), None, errors);
+
+        assert_eq!(portfolio.average_correlation, None);
+        assert_eq!(portfolio.portfolio_volatility, None);
+        // Concentration alone drives the score when correlations are unknown
+        assert!((portfolio.diversification_score - 62.5).abs() < 1e-9);
+        assert_eq!(portfolio.errors.len(), 1);
+
+        // Perfectly correlated holdings have no diversification benefit
+        let correlation = CorrelationMatrix {
+            symbols: vec!["AAA".to_string(), "BBB".to_string(), "CCC".to_string()],
+            matrix: vec![vec![1.0; 3]; 3],
+            sample_size: 60,
+        };
+        let portfolio = PortfolioAnalysis::from_holdings(holdings(), Some(correlation), vec![]);
+        assert_eq!(portfolio.diversification_score, 0.0);
+        assert!((portfolio.portfolio_volatility.unwrap() - 20.0).abs() < 1e-9);
+    }
+}
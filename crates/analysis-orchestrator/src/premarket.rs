@@ -0,0 +1,253 @@
+use analysis_core::{adaptive, Bar};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use polygon_client::{eastern_date_time, market_session, MarketSession};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Calendar days of extended-hours bars fetched for the pre-market baseline
+pub(crate) const PREMARKET_LOOKBACK_DAYS: i64 = 30;
+
+/// Minutes per intraday bar used for the pre-market comparison
+pub(crate) const PREMARKET_BAR_MINUTES: u32 = 5;
+
+/// Prior sessions needed before the gap is ranked against history
+const MIN_PREMARKET_HISTORY: usize = 5;
+
+/// Pre-market volume at this multiple of the usual level counts as heavy
+const HEAVY_PREMARKET_VOLUME_RATIO: f64 = 2.0;
+
+/// Gap percentiles (vs prior pre-market gaps) beyond which the gap is notable
+const PREMARKET_GAP_PCTS: (f64, f64) = (0.10, 0.90);
+
+/// Pre-market gap and volume of one session, measured up to a time of day
+struct PremarketSession {
+    gap_pct: f64,
+    volume: f64,
+    last_price: f64,
+    prior_close: f64,
+}
+
+/// Pre-market move of `date` vs the prior session's regular close, using only
+/// pre-market bars that start before `until` (Eastern time)
+fn session_premarket(
+    sessions: &BTreeMap<NaiveDate, Vec<&Bar>>,
+    date: NaiveDate,
+    until: NaiveTime,
+) -> Option<PremarketSession> {
+    let prior_close = sessions
+        .range(..date)
+        .rev()
+        .find_map(|(_, bars)| {
+            bars.iter()
+                .rev()
+                .find(|b| market_session(b.timestamp) == MarketSession::Regular)
+        })?
+        .close;
+    let premarket: Vec<&&Bar> = sessions
+        .get(&date)?
+        .iter()
+        .filter(|b| {
+            market_session(b.timestamp) == MarketSession::PreMarket
+                && eastern_date_time(b.timestamp).1 < until
+        })
+        .collect();
+    let last_price = premarket.last()?.close;
+    if prior_close <= 0.0 || last_price <= 0.0 {
+        return None;
+    }
+    Some(PremarketSession {
+        gap_pct: (last_price - prior_close) / prior_close * 100.0,
+        volume: premarket.iter().map(|b| b.volume).sum(),
+        last_price,
+        prior_close,
+    })
+}
+
+/// Compare today's pre-market gap and volume with the same window of the
+/// previous sessions in `bars` (intraday bars including extended hours).
+///
+/// Mid-way through the pre-market, earlier sessions are cut at the same time
+/// of day so partial volume is compared like for like. Returns the JSON block
+/// and a score adjustment: a notable gap on heavy volume leans with the gap.
+pub(crate) fn premarket_signal(
+    bars: &[Bar],
+    now: DateTime<Utc>,
+) -> Option<(serde_json::Value, f64)> {
+    let mut sessions: BTreeMap<NaiveDate, Vec<&Bar>> = BTreeMap::new();
+    for bar in bars {
+        sessions
+            .entry(eastern_date_time(bar.timestamp).0)
+            .or_default()
+            .push(bar);
+    }
+
+    let (today, now_time) = eastern_date_time(now);
+    let open = NaiveTime::from_hms_opt(9, 30, 0)?;
+    let until = if market_session(now) == MarketSession::PreMarket {
+        now_time
+    } else {
+        open
+    };
+
+    let current = session_premarket(&sessions, today, until)?;
+    let history: Vec<PremarketSession> = sessions
+        .range(..today)
+        .filter_map(|(date, _)| session_premarket(&sessions, *date, until))
+        .collect();
+
+    let historical_volumes: Vec<f64> = history.iter().map(|s| s.volume).collect();
+    let avg_volume = (!historical_volumes.is_empty())
+        .then(|| historical_volumes.iter().sum::<f64>() / historical_volumes.len() as f64)
+        .filter(|v| *v > 0.0);
+    let volume_ratio = avg_volume.map(|avg| current.volume / avg);
+
+    let gap_signal = if history.len() >= MIN_PREMARKET_HISTORY {
+        let gaps: Vec<f64> = history.iter().map(|s| s.gap_pct).collect();
+        let rank = adaptive::percentile_rank(current.gap_pct, &gaps);
+        if rank > PREMARKET_GAP_PCTS.1 {
+            "gap_up"
+        } else if rank < PREMARKET_GAP_PCTS.0 {
+            "gap_down"
+        } else {
+            "flat"
+        }
+    } else if current.gap_pct > 2.0 {
+        "gap_up"
+    } else if current.gap_pct < -2.0 {
+        "gap_down"
+    } else {
+        "flat"
+    };
+    let heavy_volume = volume_ratio.is_some_and(|r| r >= HEAVY_PREMARKET_VOLUME_RATIO);
+
+    // Gaps on heavy pre-market volume carry conviction into the open
+    let score_adj = match (gap_signal, heavy_volume) {
+        ("gap_up", true) => 0.01,
+        ("gap_down", true) => -0.01,
+        _ => 0.0,
+    };
+
+    Some((
+        json!({
+            "premarket_gap_pct": current.gap_pct,
+            "premarket_volume": current.volume,
+            "premarket_volume_ratio": volume_ratio,
+            "premarket_last": current.last_price,
+            "prior_close": current.prior_close,
+            "gap_signal": gap_signal,
+            "heavy_volume": heavy_volume,
+            "historical_sessions": history.len(),
+            "measured_until_et": until.format("%H:%M").to_string(),
+        }),
+        score_adj,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// July 2024 is on EDT (UTC-4)
+    fn et(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 7, day, hour + 4, minute, 0)
+            .unwrap()
+    }
+
+    fn bar(timestamp: DateTime<Utc>, close: f64, volume: f64) -> Bar {
+        Bar {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            vwap: None,
+        }
+    }
+
+    /// Six quiet sessions (Jul 5–12), the last five with a prior close: small
+    /// pre-market gaps on 1,000 shares, then a regular-session close at 100.
+    /// A heavy 09:00 print is past the 08:30 cut-off used below.
+    fn history() -> Vec<Bar> {
+        [5, 8, 9, 10, 11, 12]
+            .into_iter()
+            .zip([0.0, 0.1, -0.2, 0.3, -0.1, 0.2])
+            .flat_map(|(day, gap)| {
+                [
+                    bar(et(day, 8, 0), 100.0 + gap, 1_000.0),
+                    bar(et(day, 9, 0), 100.0, 50_000.0),
+                    bar(et(day, 15, 55), 100.0, 20_000.0),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_premarket_gap_up_on_heavy_volume() {
+        let mut bars = history();
+        bars.push(bar(et(15, 8, 0), 105.0, 5_000.0));
+
+        let (json, score_adj) = premarket_signal(&bars, et(15, 8, 30)).unwrap();
+        assert_eq!(json["gap_signal"], "gap_up");
+        assert_eq!(json["heavy_volume"], true);
+        assert_eq!(json["historical_sessions"], 5);
+        assert_eq!(json["measured_until_et"], "08:30");
+        // Earlier sessions are cut at 08:30 too, so their 09:00 volume is left out
+        assert_eq!(json["premarket_volume_ratio"], 5.0);
+        assert!((json["premarket_gap_pct"].as_f64().unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(score_adj, 0.01);
+    }
+
+    #[test]
+    fn test_premarket_gap_down_on_heavy_volume() {
+        let mut bars = history();
+        bars.push(bar(et(15, 8, 0), 95.0, 5_000.0));
+
+        let (json, score_adj) = premarket_signal(&bars, et(15, 8, 30)).unwrap();
+        assert_eq!(json["gap_signal"], "gap_down");
+        assert_eq!(score_adj, -0.01);
+
+        // The same gap on ordinary volume doesn't move the score
+        let mut bars = history();
+        bars.push(bar(et(15, 8, 0), 95.0, 1_000.0));
+        let (json, score_adj) = premarket_signal(&bars, et(15, 8, 30)).unwrap();
+        assert_eq!(json["gap_signal"], "gap_down");
+        assert_eq!(json["heavy_volume"], false);
+        assert_eq!(score_adj, 0.0);
+    }
+
+    #[test]
+    fn test_premarket_without_premarket_bars() {
+        let mut bars = history();
+        bars.push(bar(et(15, 9, 45), 105.0, 5_000.0));
+        assert!(premarket_signal(&bars, et(15, 10, 0)).is_none());
+        assert!(premarket_signal(&history(), et(15, 8, 30)).is_none());
+    }
+
+    #[test]
+    fn test_premarket_ignores_bars_outside_window() {
+        let mut bars = history();
+        bars.extend([
+            // Overnight, before the 04:00 pre-market open
+            bar(et(15, 3, 30), 150.0, 90_000.0),
+            bar(et(15, 8, 0), 101.0, 1_000.0),
+            // Regular session
+            bar(et(15, 9, 45), 150.0, 90_000.0),
+        ]);
+
+        let (json, _) = premarket_signal(&bars, et(15, 11, 0)).unwrap();
+        assert_eq!(json["measured_until_et"], "09:30");
+        assert_eq!(json["premarket_last"], 101.0);
+        assert_eq!(json["prior_close"], 100.0);
+        assert_eq!(json["premarket_volume"], 1_000.0);
+
+        // Only the overnight and regular bars: no pre-market session at all
+        let mut bars = history();
+        bars.extend([
+            bar(et(15, 3, 30), 150.0, 90_000.0),
+            bar(et(15, 9, 45), 150.0, 90_000.0),
+        ]);
+        assert!(premarket_signal(&bars, et(15, 11, 0)).is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use analysis_core::AnalysisError;
+use std::collections::HashMap;
+
+/// (technical, fundamental, quant, sentiment) engine weights as percentages
+pub type EngineWeights = (i32, i32, i32, i32);
+
+/// Built-in per-regime weights
+const DEFAULT_REGIME_WEIGHTS: &[(&str, EngineWeights)] = &[
+    ("high_vol_bear", (15, 30, 35, 20)), // Lean on risk/quant in volatile downtrends
+    ("high_vol_bull", (25, 25, 30, 20)), // Quant risk still important in volatile uptrends
+    ("high_vol_sideways", (15, 30, 35, 20)),
+    ("low_vol_bull", (30, 30, 15, 25)), // Technical momentum + sentiment in calm uptrends
+    ("low_vol_bear", (20, 40, 20, 20)), // Fundamental value focus in slow decline
+    ("low_vol_sideways", (25, 35, 20, 20)),
+    ("normal_bull", (25, 35, 15, 25)), // Balanced with slight fundamental tilt
+    ("normal_bear", (20, 35, 25, 20)), // More quant risk-awareness
+    ("normal_sideways", (20, 40, 15, 25)), // Standard balanced
+];
+
+/// Weights for regimes missing from the table (including "unknown")
+const DEFAULT_FALLBACK_WEIGHTS: EngineWeights = (20, 40, 15, 25);
+
+/// Regime-conditional engine weights, keyed by regime label ("high_vol_bear", ...).
+/// Every entry is validated to sum to 100.
+#[derive(Debug, Clone)]
+pub struct RegimeWeights {
+    by_regime: HashMap<String, EngineWeights>,
+    fallback: EngineWeights,
+}
+
+impl RegimeWeights {
+    /// Build a table from scratch; regimes not listed use `fallback`
+    pub fn new(
+        regimes: impl IntoIterator<Item = (String, EngineWeights)>,
+        fallback: EngineWeights,
+    ) -> Result<Self, AnalysisError> {
+        validate("fallback", fallback)?;
+        let mut by_regime = HashMap::new();
+        for (regime, weights) in regimes {
+            validate(&regime, weights)?;
+            by_regime.insert(regime, weights);
+        }
+        Ok(Self {
+            by_regime,
+            fallback,
+        })
+    }
+
+    /// Override the weights for one regime
+    pub fn with_regime(
+        mut self,
+        regime: &str,
+        weights: EngineWeights,
+    ) -> Result<Self, AnalysisError> {
+        validate(regime, weights)?;
+        self.by_regime.insert(regime.to_string(), weights);
+        Ok(self)
+    }
+
+    /// Weights for `regime`, or the fallback when it has no entry
+    pub fn get(&self, regime: &str) -> EngineWeights {
+        self.by_regime.get(regime).copied().unwrap_or(self.fallback)
+    }
+}
+
+impl Default for RegimeWeights {
+    fn default() -> Self {
+        Self {
+            by_regime: DEFAULT_REGIME_WEIGHTS
+                .iter()
+                .map(|&(regime, weights)| (regime.to_string(), weights))
+                .collect(),
+            fallback: DEFAULT_FALLBACK_WEIGHTS,
+        }
+    }
+}
+
+fn validate(regime: &str, (t, f, q, s): EngineWeights) -> Result<(), AnalysisError> {
+    if [t, f, q, s].iter().any(|w| *w < 0) || t + f + q + s != 100 {
+        return Err(AnalysisError::InvalidData(format!(
+            "weights for regime {:?} must be non-negative and sum to 100, got ({}, {}, {}, {})",
+            regime, t, f, q, s
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use analysis_core::{AnalysisError, Clock};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use polygon_client::{AllSnapshotsTicker, PolygonClient};
+use std::collections::HashMap;
+
+/// How long a universe ranking is reused before it is rebuilt, in seconds
+pub(crate) const RS_UNIVERSE_TTL_SECS: i64 = 6 * 3600;
+
+/// Lookbacks (calendar days) and weights of the composite return: the most
+/// recent quarter counts double, as in IBD's RS rating.
+const RS_PERIODS: &[(i64, f64)] = &[(91, 0.4), (182, 0.2), (273, 0.2), (365, 0.2)];
+
+/// Days to step back from a lookback date to find a session with data
+const MAX_HOLIDAY_STEPS: i64 = 5;
+
+/// Weighted multi-period return of every US ticker, ranked into 1–99 ratings
+pub struct RsUniverse {
+    ratings: HashMap<String, u8>,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl RsUniverse {
+    /// Rank the whole market: current prices from the all-tickers snapshot,
+    /// historical closes from grouped daily aggregates. Lookbacks are counted
+    /// back from `clock`'s date, which is also stamped as `computed_at`.
+    pub async fn build(
+        client: &PolygonClient,
+        snapshots: &[AllSnapshotsTicker],
+        clock: &dyn Clock,
+    ) -> Result<Self, AnalysisError> {
+        let current: HashMap<String, f64> = snapshots
+            .iter()
+            .filter_map(|t| {
+                let price = t
+                    .last_trade
+                    .as_ref()
+                    .and_then(|lt| lt.p)
+                    .or_else(|| t.day.as_ref().and_then(|d| d.c))
+                    .or_else(|| t.prev_day.as_ref().and_then(|d| d.c))?;
+                (price > 0.0).then_some((t.ticker.clone(), price))
+            })
+            .collect();
+        if current.is_empty() {
+            return Err(AnalysisError::InvalidData(
+                "empty market snapshot".to_string(),
+            ));
+        }
+
+        let now = clock.now();
+        let today = now.date_naive();
+        let mut composite: HashMap<&str, (f64, f64)> = HashMap::new();
+        for &(days, weight) in RS_PERIODS {
+            let closes = Self::closes_near(client, today - Duration::days(days)).await?;
+            for (ticker, price) in &current {
+                if let Some(&past) = closes.get(ticker) {
+                    let entry = composite.entry(ticker.as_str()).or_insert((0.0, 0.0));
+                    entry.0 += weight * (price / past - 1.0);
+                    entry.1 += weight;
+                }
+            }
+        }
+
+        // Tickers listed for less than a quarter have no comparable history
+        let scores: Vec<(&str, f64)> = composite
+            .into_iter()
+            .filter(|(_, (_, w))| *w >= RS_PERIODS[0].1)
+            .map(|(ticker, (sum, w))| (ticker, sum / w))
+            .collect();
+
+        Ok(Self {
+            ratings: rank_to_ratings(&scores),
+            computed_at: now,
+        })
+    }
+
+    /// 1–99 relative strength rating, if the symbol was in the universe
+    pub fn rating(&self, symbol: &str) -> Option<u8> {
+        self.ratings.get(&symbol.to_uppercase()).copied()
+    }
+
+    /// Whether the ranking is at least `RS_UNIVERSE_TTL_SECS` old by `clock`
+    pub fn is_stale(&self, clock: &dyn Clock) -> bool {
+        (clock.now() - self.computed_at).num_seconds() >= RS_UNIVERSE_TTL_SECS
+    }
+
+    /// Grouped closes for `date`, stepping back over weekends and holidays
+    async fn closes_near(
+        client: &PolygonClient,
+        date: NaiveDate,
+    ) -> Result<HashMap<String, f64>, AnalysisError> {
+        for step in 0..=MAX_HOLIDAY_STEPS {
+            let closes = client
+                .get_grouped_daily_closes(date - Duration::days(step))
+                .await?;
+            if !closes.is_empty() {
+                return Ok(closes);
+            }
+        }
+        Err(AnalysisError::InvalidData(format!(
+            "no trading session found near {}",
+            date
+        )))
+    }
+}
+
+/// Percentile rank of each score mapped onto 1..=99
+fn rank_to_ratings(scores: &[(&str, f64)]) -> HashMap<String, u8> {
+    let mut sorted: Vec<f64> = scores.iter().map(|(_, s)| *s).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = sorted.len();
+
+    scores
+        .iter()
+        .map(|(ticker, score)| {
+            let below = sorted.partition_point(|s| s < score);
+            let pct = if n > 1 {
+                below as f64 / (n - 1) as f64
+            } else {
+                0.5
+            };
+            let rating = (1.0 + pct * 98.0).round().clamp(1.0, 99.0) as u8;
+            (ticker.to_string(), rating)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analysis_core::FixedClock;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_staleness_measured_by_clock() {
+        let computed_at = Utc.with_ymd_and_hms(2021, 5, 10, 12, 0, 0).unwrap();
+        let universe = RsUniverse {
+            ratings: rank_to_ratings(&[("AAPL", 0.3), ("MSFT", 0.1)]),
+            computed_at,
+        };
+        let at = |hours| FixedClock(computed_at + Duration::hours(hours));
+
+        assert!(!universe.is_stale(&at(0)));
+        assert!(!universe.is_stale(&at(5)));
+        assert!(universe.is_stale(&at(6)));
+        assert_eq!(universe.rating("aapl"), Some(99));
+        assert_eq!(universe.rating("MSFT"), Some(1));
+    }
+}
@@ -1,6 +1,9 @@
 use super::AnalysisOrchestrator;
 use analysis_core::{SignalStrength, UnifiedAnalysis};
+use futures_util::stream::{self, StreamExt};
+use polygon_client::AllSnapshotsTicker;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 
@@ -19,6 +22,12 @@ pub struct ScreenerResult {
     pub suggestions: Vec<StockSuggestion>,
     pub total_analyzed: usize,
     pub total_passed_filters: usize,
+    /// Symbols dropped by the snapshot pre-filter before any deep analysis
+    #[serde(default)]
+    pub total_prefiltered: usize,
+    /// Listed symbols the all-tickers snapshot had no entry for
+    #[serde(default)]
+    pub total_missing_snapshot: usize,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -65,6 +74,21 @@ pub struct ScreenerFilters {
     pub min_confidence: f64,
     pub min_signal_strength: i32, // -3 to 3 (StrongSell to StrongBuy)
     pub limit: usize,
+    /// Fundamental quality composite (0-5)
+    pub min_quality_score: Option<f64>,
+    pub max_quality_score: Option<f64>,
+    /// Piotroski F-Score (0-9)
+    pub min_piotroski_f_score: Option<f64>,
+    pub max_piotroski_f_score: Option<f64>,
+    pub min_altman_z_score: Option<f64>,
+    pub max_altman_z_score: Option<f64>,
+    /// Inclusive (metric name, min, max) ranges on any engine metric.
+    /// A stock missing a filtered metric does not pass.
+    pub metric_ranges: Vec<(String, Option<f64>, Option<f64>)>,
+    /// Snapshot pre-filters, applied by `screen_universe` before deep analysis
+    pub min_price: Option<f64>,
+    pub min_volume: Option<f64>,
+    pub max_abs_change_pct: Option<f64>,
 }
 
 impl Default for ScreenerFilters {
@@ -73,10 +97,160 @@ impl Default for ScreenerFilters {
             min_confidence: 0.5,
             min_signal_strength: 0, // Neutral or better
             limit: 10,
+            min_quality_score: None,
+            max_quality_score: None,
+            min_piotroski_f_score: None,
+            max_piotroski_f_score: None,
+            min_altman_z_score: None,
+            max_altman_z_score: None,
+            metric_ranges: Vec::new(),
+            min_price: Some(5.0),
+            min_volume: Some(500_000.0),
+            max_abs_change_pct: None,
         }
     }
 }
 
+impl ScreenerFilters {
+    /// All metric range constraints, composite scores included
+    fn ranges(&self) -> impl Iterator<Item = (&str, Option<f64>, Option<f64>)> {
+        [
+            (
+                "quality_score",
+                self.min_quality_score,
+                self.max_quality_score,
+            ),
+            (
+                "piotroski_f_score",
+                self.min_piotroski_f_score,
+                self.max_piotroski_f_score,
+            ),
+            (
+                "altman_z_score",
+                self.min_altman_z_score,
+                self.max_altman_z_score,
+            ),
+        ]
+        .into_iter()
+        .chain(
+            self.metric_ranges
+                .iter()
+                .map(|(name, min, max)| (name.as_str(), *min, *max)),
+        )
+        .filter(|(_, min, max)| min.is_some() || max.is_some())
+    }
+
+    /// Whether the analysis clears the confidence, signal, and metric filters
+    fn passes(&self, analysis: &UnifiedAnalysis) -> bool {
+        analysis.overall_confidence >= self.min_confidence
+            && analysis.overall_signal.to_score() >= self.min_signal_strength
+            && self.metrics_pass(analysis)
+    }
+
+    /// Cheap price/volume/change check on a market snapshot
+    fn snapshot_passes(&self, ticker: &AllSnapshotsTicker) -> bool {
+        let price = snapshot_price(ticker);
+        let volume = snapshot_volume(ticker);
+
+        self.min_price
+            .is_none_or(|min| price.is_some_and(|p| p >= min))
+            && self
+                .min_volume
+                .is_none_or(|min| volume.is_some_and(|v| v >= min))
+            && self
+                .max_abs_change_pct
+                .is_none_or(|max| ticker.todays_change_perc.is_none_or(|c| c.abs() <= max))
+    }
+
+    /// Whether every metric range holds for the analysis
+    fn metrics_pass(&self, analysis: &UnifiedAnalysis) -> bool {
+        self.ranges().all(|(name, min, max)| {
+            metric_value(analysis, name)
+                .is_some_and(|v| min.is_none_or(|min| v >= min) && max.is_none_or(|max| v <= max))
+        })
+    }
+}
+
+/// Latest trade, else today's or the previous session's close
+fn snapshot_price(ticker: &AllSnapshotsTicker) -> Option<f64> {
+    ticker
+        .last_trade
+        .as_ref()
+        .and_then(|t| t.p)
+        .or_else(|| ticker.day.as_ref().and_then(|d| d.c))
+        .or_else(|| ticker.prev_day.as_ref().and_then(|d| d.c))
+}
+
+/// Today's volume, falling back to the previous session when today's is not in yet
+fn snapshot_volume(ticker: &AllSnapshotsTicker) -> Option<f64> {
+    ticker
+        .day
+        .as_ref()
+        .and_then(|d| d.v)
+        .filter(|&v| v > 0.0)
+        .or_else(|| ticker.prev_day.as_ref().and_then(|d| d.v))
+}
+
+/// Symbols `screen_universe` analyzes, with what was dropped along the way
+#[derive(Debug, PartialEq)]
+struct UniverseSelection {
+    survivors: Vec<String>,
+    prefiltered: usize,
+    missing_snapshot: usize,
+}
+
+/// Keep the `max_symbols` listed tickers with the highest dollar volume, then
+/// apply the snapshot pre-filters. Listed tickers without a snapshot are
+/// counted separately, as they were never ranked.
+fn select_universe(
+    listed: &[String],
+    snapshots: &[AllSnapshotsTicker],
+    max_symbols: usize,
+    filters: &ScreenerFilters,
+) -> UniverseSelection {
+    let listed: HashSet<&str> = listed.iter().map(String::as_str).collect();
+    let mut ranked: Vec<(&AllSnapshotsTicker, f64)> = snapshots
+        .iter()
+        .filter(|t| listed.contains(t.ticker.as_str()))
+        .map(|t| {
+            let dollar_volume =
+                snapshot_price(t).unwrap_or(0.0) * snapshot_volume(t).unwrap_or(0.0);
+            (t, dollar_volume)
+        })
+        .collect();
+    let missing_snapshot = listed.len().saturating_sub(ranked.len());
+    ranked.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| a.0.ticker.cmp(&b.0.ticker))
+    });
+    ranked.truncate(max_symbols);
+
+    let candidates = ranked.len();
+    let survivors: Vec<String> = ranked
+        .into_iter()
+        .filter(|(t, _)| filters.snapshot_passes(t))
+        .map(|(t, _)| t.ticker.clone())
+        .collect();
+    UniverseSelection {
+        prefiltered: candidates - survivors.len(),
+        survivors,
+        missing_snapshot,
+    }
+}
+
+/// Look a metric up by name across the engine results, fundamental first
+fn metric_value(analysis: &UnifiedAnalysis, name: &str) -> Option<f64> {
+    [
+        &analysis.fundamental,
+        &analysis.quantitative,
+        &analysis.technical,
+        &analysis.sentiment,
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|result| result.metrics.get(name).and_then(|v| v.as_f64()))
+}
+
 pub struct StockScreener {
     orchestrator: Arc<AnalysisOrchestrator>,
 }
@@ -116,9 +290,7 @@ impl StockScreener {
             match result {
                 Ok((_symbol, Ok(analysis))) => {
                     // Apply filters
-                    if analysis.overall_confidence >= filters.min_confidence
-                        && analysis.overall_signal.to_score() >= filters.min_signal_strength
-                    {
+                    if filters.passes(&analysis) {
                         if let Some(suggestion) = self.create_suggestion(analysis) {
                             suggestions.push(suggestion);
                         }
@@ -133,6 +305,80 @@ impl StockScreener {
             }
         }
 
+        Ok(Self::rank(suggestions, &filters, total_analyzed, 0, 0))
+    }
+
+    /// Screen the `max_symbols` most liquid (by dollar volume) US common stocks.
+    ///
+    /// One all-tickers snapshot ranks the universe and pre-filters on price,
+    /// volume, and daily change, so the ~10 API calls of a full analysis are
+    /// only spent on survivors, at most `concurrency` at a time.
+    pub async fn screen_universe(
+        &self,
+        max_symbols: usize,
+        filters: ScreenerFilters,
+        concurrency: usize,
+    ) -> Result<ScreenerResult, anyhow::Error> {
+        let (tickers, snapshots) = tokio::try_join!(
+            self.orchestrator.polygon_client.list_tickers(usize::MAX),
+            self.orchestrator.get_cached_all_snapshots()
+        )?;
+        let selection = select_universe(&tickers, &snapshots, max_symbols, &filters);
+        let total_analyzed = selection.survivors.len();
+
+        tracing::info!(
+            "📊 Universe screen: {} tickers ({} without a snapshot), top {} by dollar volume, {} pre-filtered, {} to analyze",
+            tickers.len(),
+            selection.missing_snapshot,
+            max_symbols,
+            selection.prefiltered,
+            total_analyzed
+        );
+
+        let analyses: Vec<_> = stream::iter(selection.survivors)
+            .map(|symbol| {
+                let orchestrator = Arc::clone(&self.orchestrator);
+                async move {
+                    let result = orchestrator
+                        .analyze(&symbol, analysis_core::Timeframe::Day1, 365)
+                        .await;
+                    (symbol, result)
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut suggestions = Vec::new();
+        for (symbol, result) in analyses {
+            match result {
+                Ok(analysis) if filters.passes(&analysis) => {
+                    if let Some(suggestion) = self.create_suggestion(analysis) {
+                        suggestions.push(suggestion);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to analyze {}: {}", symbol, e),
+            }
+        }
+
+        Ok(Self::rank(
+            suggestions,
+            &filters,
+            total_analyzed,
+            selection.prefiltered,
+            selection.missing_snapshot,
+        ))
+    }
+
+    /// Sort suggestions by score and keep the top `filters.limit`
+    fn rank(
+        mut suggestions: Vec<StockSuggestion>,
+        filters: &ScreenerFilters,
+        total_analyzed: usize,
+        total_prefiltered: usize,
+        total_missing_snapshot: usize,
+    ) -> ScreenerResult {
         let total_passed_filters = suggestions.len();
 
         // Sort by score (highest first)
@@ -152,12 +398,14 @@ impl StockScreener {
             suggestions.len()
         );
 
-        Ok(ScreenerResult {
+        ScreenerResult {
             suggestions,
             total_analyzed,
             total_passed_filters,
+            total_prefiltered,
+            total_missing_snapshot,
             timestamp: chrono::Utc::now(),
-        })
+        }
     }
 
     fn create_suggestion(&self, analysis: UnifiedAnalysis) -> Option<StockSuggestion> {
@@ -219,3 +467,58 @@ impl StockScreener {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polygon_client::SnapshotDay;
+
+    fn snapshot(ticker: &str, close: f64, volume: f64) -> AllSnapshotsTicker {
+        AllSnapshotsTicker {
+            ticker: ticker.to_string(),
+            day: Some(SnapshotDay {
+                o: None,
+                h: None,
+                l: None,
+                c: Some(close),
+                v: Some(volume),
+            }),
+            last_trade: None,
+            prev_day: None,
+            todays_change: None,
+            todays_change_perc: None,
+        }
+    }
+
+    #[test]
+    fn test_select_universe_ranks_by_dollar_volume() {
+        let listed: Vec<String> = ["AAA", "BBB", "CCC", "DDD", "ZZZ"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let snapshots = vec![
+            snapshot("AAA", 10.0, 600_000.0),    // $6M
+            snapshot("BBB", 2.0, 50_000_000.0),  // $100M, but under the $5 floor
+            snapshot("CCC", 50.0, 1_000_000.0),  // $50M
+            snapshot("DDD", 20.0, 700_000.0),    // $14M
+            snapshot("OTC", 100.0, 9_000_000.0), // Not a listed common stock
+        ];
+        let filters = ScreenerFilters::default();
+
+        // Alphabetical truncation would keep AAA and BBB; liquidity keeps BBB and CCC
+        let selection = select_universe(&listed, &snapshots, 2, &filters);
+        assert_eq!(
+            selection,
+            UniverseSelection {
+                survivors: vec!["CCC".to_string()],
+                prefiltered: 1,
+                missing_snapshot: 1,
+            }
+        );
+
+        let selection = select_universe(&listed, &snapshots, 10, &filters);
+        assert_eq!(selection.survivors, vec!["CCC", "DDD", "AAA"]);
+        assert_eq!(selection.prefiltered, 1);
+        assert_eq!(selection.missing_snapshot, 1);
+    }
+}
@@ -0,0 +1,67 @@
+use analysis_core::AnalysisError;
+use chrono::{DateTime, Utc};
+use polygon_client::{AllSnapshotsTicker, PolygonClient};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Snapshot and when it was fetched
+type Stamped = (Arc<Vec<AllSnapshotsTicker>>, DateTime<Utc>);
+
+/// Single shared copy of the all-tickers snapshot.
+///
+/// Reads of a fresh snapshot never block. When it is stale, one caller holds
+/// `refresh_lock` while fetching; concurrent callers wait on the lock and then
+/// reuse its result instead of issuing their own 5,000-ticker request.
+#[derive(Clone, Default)]
+pub(crate) struct SnapshotCache {
+    slot: Arc<RwLock<Option<Stamped>>>,
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl SnapshotCache {
+    /// The cached snapshot if younger than `ttl_secs`, otherwise a fresh fetch
+    pub async fn get(
+        &self,
+        client: &PolygonClient,
+        ttl_secs: i64,
+    ) -> Result<Arc<Vec<AllSnapshotsTicker>>, AnalysisError> {
+        if let Some(snapshots) = self.fresh(ttl_secs).await {
+            return Ok(snapshots);
+        }
+        let _guard = self.refresh_lock.lock().await;
+        // Another caller may have refreshed while we waited for the lock
+        if let Some(snapshots) = self.fresh(ttl_secs).await {
+            return Ok(snapshots);
+        }
+        self.fetch(client).await
+    }
+
+    /// Fetch unconditionally, unless a refresh is already running
+    pub async fn refresh(&self, client: &PolygonClient) -> Result<(), AnalysisError> {
+        let Ok(_guard) = self.refresh_lock.try_lock() else {
+            return Ok(());
+        };
+        self.fetch(client).await.map(|_| ())
+    }
+
+    async fn fresh(&self, ttl_secs: i64) -> Option<Arc<Vec<AllSnapshotsTicker>>> {
+        let slot = self.slot.read().await;
+        slot.as_ref()
+            .filter(|(_, fetched_at)| (Utc::now() - *fetched_at).num_seconds() < ttl_secs)
+            .map(|(snapshots, _)| Arc::clone(snapshots))
+    }
+
+    /// Callers must hold `refresh_lock`
+    async fn fetch(
+        &self,
+        client: &PolygonClient,
+    ) -> Result<Arc<Vec<AllSnapshotsTicker>>, AnalysisError> {
+        let snapshots = Arc::new(client.get_all_snapshots().await?);
+        tracing::debug!(
+            "Refreshed all-tickers snapshot ({} tickers)",
+            snapshots.len()
+        );
+        *self.slot.write().await = Some((Arc::clone(&snapshots), Utc::now()));
+        Ok(snapshots)
+    }
+}
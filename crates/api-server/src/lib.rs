@@ -735,7 +735,8 @@ pub async fn run_server() -> anyhow::Result<()> {
 
     // Create orchestrator
     tracing::info!("Initializing orchestrator...");
-    let orchestrator = Arc::new(AnalysisOrchestrator::new(polygon_api_key));
+    let orchestrator =
+        Arc::new(AnalysisOrchestrator::new(polygon_api_key).with_rs_background_refresh(true));
 
     // Create stock screener
     let screener = Arc::new(StockScreener::new(Arc::clone(&orchestrator)));
@@ -1955,6 +1956,9 @@ struct SuggestQuery {
     min_confidence: Option<f64>,
     min_signal: Option<i32>,
     limit: Option<usize>,
+    min_quality_score: Option<f64>,
+    min_piotroski: Option<f64>,
+    min_altman_z: Option<f64>,
 }
 
 #[utoipa::path(
@@ -1966,6 +1970,9 @@ struct SuggestQuery {
         ("min_confidence" = Option<f64>, Query, description = "Minimum confidence threshold (0.0-1.0, default: 0.5)"),
         ("min_signal" = Option<i32>, Query, description = "Minimum signal strength (-3 to 3, default: 0)"),
         ("limit" = Option<usize>, Query, description = "Max results (default: 10)"),
+        ("min_quality_score" = Option<f64>, Query, description = "Minimum fundamental quality score (0-5)"),
+        ("min_piotroski" = Option<f64>, Query, description = "Minimum Piotroski F-Score (0-9)"),
+        ("min_altman_z" = Option<f64>, Query, description = "Minimum Altman Z-Score"),
     ),
     responses(
         (status = 200, description = "Screened stock suggestions"),
@@ -2003,6 +2010,10 @@ async fn suggest_stocks(
         min_confidence: query.min_confidence.unwrap_or(0.5),
         min_signal_strength: query.min_signal.unwrap_or(0), // 0 = Neutral or better
         limit: query.limit.unwrap_or(10),
+        min_quality_score: query.min_quality_score,
+        min_piotroski_f_score: query.min_piotroski,
+        min_altman_z_score: query.min_altman_z,
+        ..Default::default()
     };
 
     tracing::info!(
@@ -2690,6 +2701,7 @@ mod tests {
             confidence: 0.9,
             reason: "test".into(),
             metrics: serde_json::json!({}),
+            confidence_components: serde_json::json!({}),
         });
         let quant = Some(AnalysisResult {
             symbol: "TEST".into(),
@@ -2698,6 +2710,7 @@ mod tests {
             confidence: 0.7,
             reason: "test".into(),
             metrics: serde_json::json!({}),
+            confidence_components: serde_json::json!({}),
         });
 
         let (signal, confidence) = combine_pit_signals(&tech, &quant);
@@ -2713,6 +2726,7 @@ mod tests {
             confidence: 0.5,
             reason: "test".into(),
             metrics: serde_json::json!({}),
+            confidence_components: serde_json::json!({}),
         });
         let quant_n = Some(AnalysisResult {
             symbol: "TEST".into(),
@@ -2721,6 +2735,7 @@ mod tests {
             confidence: 0.5,
             reason: "test".into(),
             metrics: serde_json::json!({}),
+            confidence_components: serde_json::json!({}),
         });
         let (signal2, _) = combine_pit_signals(&tech_n, &quant_n);
         assert_eq!(signal2, SignalStrength::Neutral);
@@ -1,12 +1,77 @@
 use analysis_core::{
-    adaptive, AnalysisError, AnalysisResult, AnalystConsensusData, Financials, FundamentalAnalyzer,
-    SignalStrength,
+    adaptive, AnalysisError, AnalysisResult, AnalystConsensusData, Clock, Financials,
+    FundamentalAnalyzer, SignalStrength, SystemClock,
 };
 use async_trait::async_trait;
-use chrono::Utc;
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-fn classify_sector(sic_desc: Option<&str>) -> &'static str {
+mod sector_benchmarks;
+pub use sector_benchmarks::{MetricBenchmark, SectorBenchmarks};
+
+/// US federal corporate tax rate, used for after-tax ROIC when no better rate is known
+pub const DEFAULT_TAX_RATE: f64 = 0.21;
+
+/// Analyst target range (high − low) as a fraction of the consensus target above
+/// which analysts disagree too much for the consensus to be actionable
+const HIGH_TARGET_DISPERSION: f64 = 0.5;
+
+/// Age in days of the newest analyst rating beyond which consensus counts as stale
+const STALE_RATING_DAYS: i64 = 90;
+
+/// Factor applied to the consensus blend weight for each of wide dispersion and staleness
+const CONSENSUS_DAMPENING: f64 = 0.5;
+
+/// Days between quarterly filings; data this old is due to be superseded
+const FILING_CYCLE_DAYS: f64 = 91.0;
+
+/// Confidence lost by financials that have aged a full filing cycle
+const MAX_FILING_AGE_DECAY: f64 = 0.15;
+
+/// Default share of the analyst consensus in the blended fundamental score
+pub const DEFAULT_CONSENSUS_WEIGHT: f64 = 0.30;
+
+/// Per-signal weight overrides for fundamental scoring, keyed by signal name
+/// (e.g. "Strong ROIC"). Signals without an override keep their built-in
+/// weight: the fixed default for threshold signals, or the z-score-derived
+/// weight for adaptive "(vs History)" signals.
+#[derive(Debug, Clone, Default)]
+pub struct SignalWeights {
+    overrides: HashMap<String, i32>,
+}
+
+impl SignalWeights {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override one signal's weight; 0 disables the signal, negatives are treated as 0
+    pub fn with_weight(mut self, signal: impl Into<String>, weight: i32) -> Self {
+        self.set(signal, weight);
+        self
+    }
+
+    pub fn set(&mut self, signal: impl Into<String>, weight: i32) {
+        self.overrides.insert(signal.into(), weight.max(0));
+    }
+
+    /// Weight to score `signal` with, falling back to its built-in `default`
+    pub fn weight(&self, signal: &str, default: i32) -> i32 {
+        self.overrides.get(signal).copied().unwrap_or(default)
+    }
+}
+
+impl From<HashMap<String, i32>> for SignalWeights {
+    fn from(overrides: HashMap<String, i32>) -> Self {
+        overrides
+            .into_iter()
+            .fold(Self::new(), |w, (name, weight)| w.with_weight(name, weight))
+    }
+}
+
+/// Coarse sector ("technology", "utilities", ...) from a Polygon SIC description
+pub fn classify_sector(sic_desc: Option<&str>) -> &'static str {
     let desc = match sic_desc {
         Some(d) => d.to_lowercase(),
         None => return "unknown",
@@ -75,11 +140,122 @@ fn classify_sector(sic_desc: Option<&str>) -> &'static str {
     }
 }
 
-pub struct FundamentalAnalysisEngine;
+/// Restate financials in another currency, multiplying every monetary field
+/// (EPS included) by `rate` units of `currency` per unit of the reporting currency.
+/// Used for ADRs whose statements are in the home currency but trade in USD.
+pub fn convert_financials_currency(
+    financials: &[Financials],
+    rate: f64,
+    currency: &str,
+) -> Vec<Financials> {
+    let convert = |v: Option<f64>| v.map(|v| v * rate);
+    financials
+        .iter()
+        .map(|f| Financials {
+            symbol: f.symbol.clone(),
+            fiscal_period: f.fiscal_period.clone(),
+            fiscal_year: f.fiscal_year,
+            filing_date: f.filing_date,
+            revenue: convert(f.revenue),
+            gross_profit: convert(f.gross_profit),
+            operating_income: convert(f.operating_income),
+            net_income: convert(f.net_income),
+            eps: convert(f.eps),
+            total_assets: convert(f.total_assets),
+            total_liabilities: convert(f.total_liabilities),
+            shareholders_equity: convert(f.shareholders_equity),
+            cash_flow_operating: convert(f.cash_flow_operating),
+            cash_flow_investing: convert(f.cash_flow_investing),
+            cash_flow_financing: convert(f.cash_flow_financing),
+            capital_expenditure: convert(f.capital_expenditure),
+            cost_of_revenue: convert(f.cost_of_revenue),
+            accounts_receivable: convert(f.accounts_receivable),
+            inventory: convert(f.inventory),
+            accounts_payable: convert(f.accounts_payable),
+            income_tax_expense: convert(f.income_tax_expense),
+            pretax_income: convert(f.pretax_income),
+            weighted_average_shares: f.weighted_average_shares,
+            currency: Some(currency.to_uppercase()),
+        })
+        .collect()
+}
+
+/// Cash conversion cycle in days (DSO + DIO − DPO) for one quarter. Needs
+/// receivables, payables and cost of revenue (or revenue and gross profit);
+/// missing inventory counts as none, as for service businesses.
+fn cash_conversion_cycle(f: &Financials) -> Option<f64> {
+    const DAYS_PER_QUARTER: f64 = 365.0 / 4.0;
+    let revenue = f.revenue.filter(|r| *r > 0.0)?;
+    let cogs = f
+        .cost_of_revenue
+        .or_else(|| f.gross_profit.map(|gp| revenue - gp))
+        .filter(|c| *c > 0.0)?;
+    let dso = f.accounts_receivable? / revenue * DAYS_PER_QUARTER;
+    let dio = f.inventory.unwrap_or(0.0) / cogs * DAYS_PER_QUARTER;
+    let dpo = f.accounts_payable? / cogs * DAYS_PER_QUARTER;
+    Some(dso + dio - dpo)
+}
+
+#[derive(Clone)]
+pub struct FundamentalAnalysisEngine {
+    signal_weights: SignalWeights,
+    /// Share (0–1) of the analyst consensus in `analyze_with_consensus`
+    consensus_weight: f64,
+    /// Most the consensus blend may move the fundamental score (−100..100 scale)
+    max_consensus_shift: Option<f64>,
+    /// Effective tax rate (0–1) for `analyze_with_consensus`; `None` derives it
+    /// from the statements
+    tax_rate: Option<f64>,
+    /// Time source for result timestamps and filing/rating ages
+    clock: Arc<dyn Clock>,
+}
 
 impl FundamentalAnalysisEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            signal_weights: SignalWeights::default(),
+            consensus_weight: DEFAULT_CONSENSUS_WEIGHT,
+            max_consensus_shift: None,
+            tax_rate: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Replace the signal weight overrides used when scoring
+    pub fn with_signal_weights(mut self, signal_weights: SignalWeights) -> Self {
+        self.signal_weights = signal_weights;
+        self
+    }
+
+    /// Inject the time source, e.g. a `FixedClock` for reproducible output
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Share of the analyst consensus in the blended score, clamped to [0, 1].
+    /// 0 ignores analysts entirely; 1 replaces the fundamental read with theirs.
+    pub fn with_consensus_weight(mut self, weight: f64) -> Self {
+        if weight.is_finite() {
+            self.consensus_weight = weight.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// Cap how many score points (on the −100..100 scale) the consensus blend can
+    /// move the fundamental score, so one contrarian or stale target can't flip a
+    /// well-supported verdict. `None` (the default) leaves the blend uncapped.
+    pub fn with_max_consensus_shift(mut self, max_shift: Option<f64>) -> Self {
+        self.max_consensus_shift = max_shift.filter(|s| s.is_finite()).map(f64::abs);
+        self
+    }
+
+    /// Effective tax rate (0–1) used for after-tax ROIC, EPV and the EV multiples
+    /// in `analyze_with_consensus`. `None` (the default) derives it from TTM tax
+    /// expense / pre-tax income, falling back to the 21% US federal rate.
+    pub fn with_tax_rate(mut self, tax_rate: Option<f64>) -> Self {
+        self.tax_rate = tax_rate.filter(|r| r.is_finite());
+        self
     }
 
     #[allow(dead_code)]
@@ -153,7 +329,15 @@ impl FundamentalAnalysisEngine {
         }
     }
 
-    /// Enhanced analysis that uses real current price and multi-quarter data
+    /// Enhanced analysis that uses real current price and multi-quarter data.
+    ///
+    /// `effective_tax_rate` (0–1) sets the rate used for after-tax ROIC, EPV and
+    /// the fair EV/EBITDA multiple; when `None` it is derived from TTM tax
+    /// expense / pre-tax income, falling back to the 21% US federal rate.
+    ///
+    /// When the latest statement carries a filing date, confidence decays mildly
+    /// (up to `MAX_FILING_AGE_DECAY`) as it ages toward the next quarterly report.
+    #[allow(clippy::too_many_arguments)]
     pub fn analyze_enhanced(
         &self,
         symbol: &str,
@@ -162,6 +346,7 @@ impl FundamentalAnalysisEngine {
         shares_outstanding: Option<f64>,
         risk_free_rate: Option<f64>,
         sic_description: Option<&str>,
+        effective_tax_rate: Option<f64>,
     ) -> Result<AnalysisResult, AnalysisError> {
         if financials.is_empty() {
             return Err(AnalysisError::InsufficientData(
@@ -196,6 +381,9 @@ impl FundamentalAnalysisEngine {
         let ttm_ocf = sum_ttm(ttm_slice, |f| f.cash_flow_operating);
         let ttm_cfi = sum_ttm(ttm_slice, |f| f.cash_flow_investing);
         let ttm_cff = sum_ttm(ttm_slice, |f| f.cash_flow_financing);
+        let ttm_capex = sum_ttm(ttm_slice, |f| f.capital_expenditure);
+        let ttm_income_tax = sum_ttm(ttm_slice, |f| f.income_tax_expense);
+        let ttm_pretax_income = sum_ttm(ttm_slice, |f| f.pretax_income);
 
         // Balance sheet: use latest quarter
         let latest = &financials[0];
@@ -551,8 +739,66 @@ impl FundamentalAnalysisEngine {
                     signals.push(("Negative Free Cash Flow", 2, false));
                 }
             }
+
+            // True FCF (TTM OCF - TTM capex) yield vs the growth-implied yield.
+            // A perpetuity growing at g and discounted at r yields r - g.
+            if let (Some(capex), Some(shares), true) = (ttm_capex, shares_outstanding, price > 0.0)
+            {
+                let market_cap = price * shares;
+                if market_cap > 0.0 {
+                    let capex_fcf = ocf - capex;
+                    let fcf_yield = capex_fcf / market_cap;
+                    let growth_rate = revenue_growth
+                        .map(|g| (g / 100.0).clamp(-0.05, 0.08))
+                        .unwrap_or(0.03);
+                    let rf = risk_free_rate.unwrap_or(0.045);
+                    let equity_risk_premium = 0.055;
+                    let discount_rate = (rf + equity_risk_premium).max(0.08);
+                    let implied_yield = (discount_rate - growth_rate).clamp(0.01, 0.15);
+                    let yield_z = (fcf_yield - implied_yield) / implied_yield;
+
+                    metrics_map.insert("capex".to_string(), json!(capex));
+                    metrics_map.insert("capex_free_cash_flow".to_string(), json!(capex_fcf));
+                    metrics_map.insert("fcf_yield".to_string(), json!(fcf_yield * 100.0));
+                    metrics_map.insert(
+                        "implied_fcf_yield".to_string(),
+                        json!(implied_yield * 100.0),
+                    );
+                    metrics_map.insert("fcf_yield_z_score".to_string(), json!(yield_z));
+
+                    if yield_z > 0.5 {
+                        signals.push((
+                            "High FCF Yield",
+                            adaptive::z_score_to_weight(yield_z),
+                            true,
+                        ));
+                    } else if yield_z < -0.5 {
+                        signals.push((
+                            "Low FCF Yield",
+                            adaptive::z_score_to_weight(yield_z.abs()),
+                            false,
+                        ));
+                    }
+                }
+            }
         }
 
+        // Tax rate for ROIC, EPV and the EV multiples: caller's rate, else the
+        // effective rate from the statements (ignored when pre-tax income is negative
+        // or the ratio is implausible, e.g. one-off deferred tax swings), else the
+        // 21% US federal rate.
+        let derived_rate = match (ttm_income_tax, ttm_pretax_income) {
+            (Some(tax), Some(pretax)) if pretax > 0.0 => {
+                Some(tax / pretax).filter(|r| (0.0..=0.5).contains(r))
+            }
+            _ => None,
+        };
+        let (tax_rate, tax_rate_source) = match (effective_tax_rate, derived_rate) {
+            (Some(rate), _) => (rate.clamp(0.0, 1.0), "provided"),
+            (None, Some(rate)) => (rate, "income_statement"),
+            (None, None) => (DEFAULT_TAX_RATE, "default"),
+        };
+
         // ROIC: TTM after-tax operating income / invested capital (balance sheet)
         if let (Some(op_income), Some(equity), Some(liabilities)) = (
             ttm_operating_income,
@@ -561,10 +807,10 @@ impl FundamentalAnalysisEngine {
         ) {
             let invested_capital = equity + liabilities;
             if invested_capital > 0.0 {
-                // US statutory corporate tax rate (21% federal). State taxes vary;
-                // 0.79 after-tax factor is a reasonable default for US-listed stocks.
-                let roic = (op_income * 0.79 / invested_capital) * 100.0;
+                let roic = (op_income * (1.0 - tax_rate) / invested_capital) * 100.0;
                 metrics_map.insert("roic".to_string(), json!(roic));
+                metrics_map.insert("roic_tax_rate".to_string(), json!(tax_rate));
+                metrics_map.insert("roic_tax_rate_source".to_string(), json!(tax_rate_source));
                 if roic > 15.0 {
                     signals.push(("Strong ROIC", 2, true));
                 } else if roic < 5.0 {
@@ -916,44 +1162,130 @@ impl FundamentalAnalysisEngine {
             );
         }
 
-        // --- EV/EBITDA (growth-implied fair value) ---
-        // EBITDA ≈ operating income (D&A not separately available from Polygon)
-        // EV ≈ Market Cap + Total Liabilities (cash not separately available)
-        if let (Some(oi), Some(tl)) = (ttm_operating_income, bs_total_liabilities) {
-            if let (Some(p), Some(s)) = (current_price, shares_outstanding) {
-                if p > 0.0 && s > 0.0 && oi > 0.0 {
-                    let market_cap = p * s;
-                    let ev = market_cap + tl;
-                    let ev_ebitda = ev / oi;
-                    metrics_map.insert("ev_ebitda".to_string(), json!(ev_ebitda));
-                    data_fields_present += 1;
-
-                    // Use growth-implied fair EV/EBITDA derived from implied P/E
-                    // adjusted for capital structure: EV/EBITDA ≈ P/E × (1 - tax) / (1 + D/E × 0.3)
-                    let growth_rate = revenue_growth.unwrap_or(0.0) / 100.0;
-                    let rf = risk_free_rate.unwrap_or(0.045);
-                    let equity_risk_premium = 0.055;
-                    let implied_pe =
-                        (1.0 / (rf + equity_risk_premium - growth_rate.min(0.08))).clamp(5.0, 80.0);
-                    let de = metrics_map.get("debt_to_equity").and_then(|v| v.as_f64()).unwrap_or(0.5);
-                    let pe_to_ev_factor = 0.79 / (1.0 + de * 0.3); // after-tax, debt-adjusted
-                    let implied_ev_ebitda = implied_pe * pe_to_ev_factor;
-                    let ev_ebitda_z = (ev_ebitda - implied_ev_ebitda) / implied_ev_ebitda.max(1.0);
-
-                    metrics_map.insert("implied_ev_ebitda".to_string(), json!(implied_ev_ebitda));
-                    metrics_map.insert("ev_ebitda_z_score".to_string(), json!(ev_ebitda_z));
-
-                    if ev_ebitda_z < -0.3 {
-                        let weight = adaptive::z_score_to_weight(ev_ebitda_z.abs());
-                        signals.push(("Low EV/EBITDA (vs Growth-Implied)", weight, true));
-                    } else if ev_ebitda_z > 0.5 {
-                        let weight = adaptive::z_score_to_weight(ev_ebitda_z);
-                        signals.push(("High EV/EBITDA (vs Growth-Implied)", weight, false));
+        // --- Enterprise-value multiples (growth-implied fair value) ---
+        // EV ≈ Market Cap + Total Liabilities (cash not separately available).
+        // Fair multiples derive from the growth-implied P/E, adjusted for capital
+        // structure where the denominator is pre-tax and pre-financing.
+        let growth_rate = revenue_growth.unwrap_or(0.0) / 100.0;
+        let rf = risk_free_rate.unwrap_or(0.045);
+        let equity_risk_premium = 0.055;
+        let implied_pe =
+            (1.0 / (rf + equity_risk_premium - growth_rate.min(0.08))).clamp(5.0, 80.0);
+        let de = metrics_map.get("debt_to_equity").and_then(|v| v.as_f64()).unwrap_or(0.5);
+        // EV/EBITDA ≈ P/E × (1 - tax) / (1 + D/E × 0.3)
+        let pe_to_ev_factor = (1.0 - tax_rate) / (1.0 + de * 0.3); // after-tax, debt-adjusted
+        let implied_ev_ebitda = implied_pe * pe_to_ev_factor;
+        let enterprise_value = match (current_price, shares_outstanding, bs_total_liabilities) {
+            (Some(p), Some(s), Some(tl)) if p > 0.0 && s > 0.0 => Some(p * s + tl),
+            _ => None,
+        };
+
+        // EV/EBITDA — EBITDA ≈ operating income (D&A not separately available from Polygon)
+        if let (Some(ev), Some(oi)) = (enterprise_value, ttm_operating_income) {
+            if oi > 0.0 {
+                let ev_ebitda = ev / oi;
+                metrics_map.insert("ev_ebitda".to_string(), json!(ev_ebitda));
+                data_fields_present += 1;
+
+                let ev_ebitda_z = (ev_ebitda - implied_ev_ebitda) / implied_ev_ebitda.max(1.0);
+
+                metrics_map.insert("implied_ev_ebitda".to_string(), json!(implied_ev_ebitda));
+                metrics_map.insert("ev_ebitda_z_score".to_string(), json!(ev_ebitda_z));
+
+                if ev_ebitda_z < -0.3 {
+                    let weight = adaptive::z_score_to_weight(ev_ebitda_z.abs());
+                    signals.push(("Low EV/EBITDA (vs Growth-Implied)", weight, true));
+                } else if ev_ebitda_z > 0.5 {
+                    let weight = adaptive::z_score_to_weight(ev_ebitda_z);
+                    signals.push(("High EV/EBITDA (vs Growth-Implied)", weight, false));
+                }
+            }
+        }
+
+        // EV/FCF — FCF is already after tax and capex, so the fair multiple is the
+        // growth-implied P/E itself. Prefers true capex over total investing CF.
+        let ttm_fcf = match (ttm_ocf, ttm_capex, ttm_cfi) {
+            (Some(ocf), Some(capex), _) => Some(ocf - capex),
+            (Some(ocf), None, Some(cfi)) => Some(ocf + cfi),
+            _ => None,
+        };
+        if let (Some(ev), Some(fcf)) = (enterprise_value, ttm_fcf) {
+            if fcf > 0.0 {
+                let ev_fcf = ev / fcf;
+                let ev_fcf_z = (ev_fcf - implied_pe) / implied_pe.max(1.0);
+
+                metrics_map.insert("ev_fcf".to_string(), json!(ev_fcf));
+                metrics_map.insert("implied_ev_fcf".to_string(), json!(implied_pe));
+                metrics_map.insert("ev_fcf_z_score".to_string(), json!(ev_fcf_z));
+
+                if ev_fcf_z < -0.3 {
+                    let weight = adaptive::z_score_to_weight(ev_fcf_z.abs());
+                    signals.push(("Low EV/FCF (vs Growth-Implied)", weight, true));
+                } else if ev_fcf_z > 0.5 {
+                    let weight = adaptive::z_score_to_weight(ev_fcf_z);
+                    signals.push(("High EV/FCF (vs Growth-Implied)", weight, false));
+                }
+            }
+        }
+
+        // EV/Sales — fair multiple is the fair EV/EBITDA times a sustainable
+        // operating margin. Unprofitable names use 30% of gross margin as the
+        // margin they could reach at maturity.
+        if let (Some(ev), Some(revenue)) = (enterprise_value, ttm_revenue) {
+            if revenue > 0.0 {
+                let ev_sales = ev / revenue;
+                metrics_map.insert("ev_sales".to_string(), json!(ev_sales));
+
+                let operating_margin = ttm_operating_income.map(|oi| oi / revenue);
+                let sustainable_margin = match operating_margin {
+                    Some(m) if m > 0.0 => Some(m),
+                    _ => ttm_gross_profit
+                        .map(|gp| gp / revenue * 0.3)
+                        .filter(|m| *m > 0.0),
+                };
+                if let Some(margin) = sustainable_margin {
+                    let implied_ev_sales = implied_ev_ebitda * margin;
+                    let ev_sales_z = (ev_sales - implied_ev_sales) / implied_ev_sales;
+
+                    metrics_map.insert("implied_ev_sales".to_string(), json!(implied_ev_sales));
+                    metrics_map.insert("ev_sales_z_score".to_string(), json!(ev_sales_z));
+
+                    if ev_sales_z < -0.3 {
+                        let weight = adaptive::z_score_to_weight(ev_sales_z.abs());
+                        signals.push(("Low EV/Sales (vs Growth-Implied)", weight, true));
+                    } else if ev_sales_z > 0.5 {
+                        let weight = adaptive::z_score_to_weight(ev_sales_z);
+                        signals.push(("High EV/Sales (vs Growth-Implied)", weight, false));
                     }
                 }
             }
         }
 
+        // --- Share Count Change (dilution vs buybacks) ---
+        // Latest quarter's diluted share count vs the same fiscal quarter a year
+        // earlier (or four quarters back when fiscal labels don't line up)
+        if let Some(current_shares) = latest.weighted_average_shares.filter(|s| *s > 0.0) {
+            let year_ago = financials
+                .iter()
+                .find(|f| {
+                    f.fiscal_year == latest.fiscal_year - 1
+                        && f.fiscal_period == latest.fiscal_period
+                })
+                .or_else(|| financials.get(4));
+            if let Some(prior_shares) = year_ago
+                .and_then(|f| f.weighted_average_shares)
+                .filter(|s| *s > 0.0)
+            {
+                let change_yoy = (current_shares / prior_shares - 1.0) * 100.0;
+                metrics_map.insert("share_count_change_yoy".to_string(), json!(change_yoy));
+                if change_yoy > 5.0 {
+                    signals.push(("Heavy Dilution", 2, false));
+                } else if change_yoy < -2.0 {
+                    signals.push(("Share Count Shrinking (buybacks)", 2, true));
+                }
+            }
+        }
+
         // --- Accrual Ratio (Earnings Quality Measure) ---
         // Accrual Ratio = (Net Income - OCF) / Total Assets
         // Higher accruals = lower earnings quality (potential manipulation)
@@ -1004,11 +1336,33 @@ impl FundamentalAnalysisEngine {
             }
         }
 
-        // --- Working Capital Efficiency (Working Capital Turnover) ---
-        // Measures how efficiently company uses working capital to generate revenue
-        if let (Some(rev), Some(ta), Some(tl)) =
+        // --- Working Capital Efficiency ---
+        // Cash conversion cycle when receivables/inventory/payables are reported;
+        // a shorter cycle than the company's own history means cash comes back faster
+        if let Some(ccc) = cash_conversion_cycle(latest) {
+            metrics_map.insert("cash_conversion_cycle".to_string(), json!(ccc));
+            data_fields_present += 1;
+
+            let ccc_history: Vec<f64> = financials
+                .iter()
+                .filter_map(cash_conversion_cycle)
+                .collect();
+            if ccc_history.len() >= 3 {
+                let ccc_z = adaptive::z_score_of(ccc, &ccc_history);
+                metrics_map.insert("cash_conversion_cycle_z_score".to_string(), json!(ccc_z));
+
+                if ccc_z < -1.0 {
+                    let weight = adaptive::z_score_to_weight(ccc_z.abs());
+                    signals.push(("Improving Cash Conversion", weight, true));
+                } else if ccc_z > 1.0 {
+                    let weight = adaptive::z_score_to_weight(ccc_z);
+                    signals.push(("Deteriorating Cash Conversion", weight, false));
+                }
+            }
+        } else if let (Some(rev), Some(ta), Some(tl)) =
             (ttm_revenue, bs_total_assets, bs_total_liabilities)
         {
+            // No balance-sheet detail: fall back to the working capital turnover proxy.
             // Estimate working capital from total assets/liabilities since Polygon
             // doesn't provide current assets/current liabilities separately.
             // Typical current assets ~40% of total, current liabilities ~30% of total.
@@ -1247,6 +1601,58 @@ impl FundamentalAnalysisEngine {
             }
         }
 
+        // --- Graham Number: sqrt(22.5 × EPS × book value per share) ---
+        if let (Some(eps), Some(equity), Some(shares)) =
+            (ttm_eps, bs_shareholders_equity, shares_outstanding)
+        {
+            if eps > 0.0 && equity > 0.0 && shares > 0.0 {
+                let bvps = equity / shares;
+                let graham_number = (22.5 * eps * bvps).sqrt();
+                metrics_map.insert("book_value_per_share".to_string(), json!(bvps));
+                metrics_map.insert("graham_number".to_string(), json!(graham_number));
+                if price > 0.0 {
+                    metrics_map.insert(
+                        "price_to_graham_number".to_string(),
+                        json!(price / graham_number),
+                    );
+                    if price < graham_number {
+                        signals.push(("Below Graham Number", 2, true));
+                    }
+                }
+            }
+        }
+
+        // --- Earnings Power Value: normalized after-tax operating earnings / WACC ---
+        if let (Some(shares), Some(equity)) = (shares_outstanding, bs_shareholders_equity) {
+            let op_quarters: Vec<f64> = ttm_slice
+                .iter()
+                .filter_map(|f| f.operating_income)
+                .collect();
+            if !op_quarters.is_empty() && shares > 0.0 && equity > 0.0 {
+                // Annualize the average quarter so a missing quarter doesn't understate earnings
+                let normalized_op_income =
+                    op_quarters.iter().sum::<f64>() / op_quarters.len() as f64 * 4.0;
+                let rf = risk_free_rate.unwrap_or(0.045);
+                let cost_of_equity = (rf + 0.055).max(0.08);
+                let cost_of_debt = (rf + 0.02) * (1.0 - tax_rate);
+                let debt = bs_total_liabilities.unwrap_or(0.0).max(0.0);
+                let wacc = (equity * cost_of_equity + debt * cost_of_debt) / (equity + debt);
+                if normalized_op_income > 0.0 && wacc > 0.0 {
+                    let epv = normalized_op_income * (1.0 - tax_rate) / wacc;
+                    let epv_per_share = epv / shares;
+                    metrics_map.insert("epv".to_string(), json!(epv));
+                    metrics_map.insert("epv_per_share".to_string(), json!(epv_per_share));
+                    metrics_map.insert("wacc".to_string(), json!(wacc * 100.0));
+                    metrics_map.insert("epv_tax_rate".to_string(), json!(tax_rate));
+                    metrics_map.insert("epv_tax_rate_source".to_string(), json!(tax_rate_source));
+                    if price > 0.0 {
+                        metrics_map
+                            .insert("price_to_epv".to_string(), json!(price / epv_per_share));
+                    }
+                }
+            }
+        }
+
         // --- Fundamental Value Score: quality + valuation ---
         // Composite quality score from existing metrics using z-scores where available
         let has_strong_roe =
@@ -1312,6 +1718,52 @@ impl FundamentalAnalysisEngine {
         .count() as u32;
         metrics_map.insert("quality_score".to_string(), json!(quality_score));
 
+        // Continuous quality composite: ROE, net margin, ROIC, and FCF conversion
+        // z-scored against sector peers and summed. With a component missing the
+        // sum is rescaled to four, so scores stay comparable across companies.
+        let benchmarks = SectorBenchmarks::for_sector(sector);
+        let fcf_conversion = match (ttm_ocf, ttm_net_income) {
+            (Some(ocf), Some(net_income)) if net_income > 0.0 => ttm_capex
+                .map(|capex| ocf - capex)
+                .or(ttm_cfi.map(|cfi| ocf + cfi))
+                .map(|fcf| fcf / net_income),
+            _ => None,
+        };
+        let metric = |key: &str| metrics_map.get(key).and_then(|v| v.as_f64());
+        let quality_z: Vec<(&str, f64)> = [
+            ("roe", metric("roe").and_then(|v| benchmarks.roe.z_score(v))),
+            (
+                "profit_margin",
+                metric("profit_margin").and_then(|v| benchmarks.profit_margin.z_score(v)),
+            ),
+            (
+                "roic",
+                metric("roic").and_then(|v| benchmarks.roic.z_score(v)),
+            ),
+            (
+                "fcf_conversion",
+                fcf_conversion.and_then(|v| benchmarks.fcf_conversion.z_score(v)),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, z)| z.map(|z| (name, z)))
+        .collect();
+        if quality_z.len() >= 3 {
+            let sum: f64 = quality_z.iter().map(|(_, z)| z).sum();
+            let quality_score_z = sum * 4.0 / quality_z.len() as f64;
+            metrics_map.insert("quality_score_z".to_string(), json!(quality_score_z));
+            metrics_map.insert(
+                "quality_score_z_components".to_string(),
+                json!(quality_z
+                    .iter()
+                    .map(|(name, z)| (name.to_string(), json!(z)))
+                    .collect::<serde_json::Map<_, _>>()),
+            );
+        }
+        if let Some(conversion) = fcf_conversion {
+            metrics_map.insert("fcf_conversion".to_string(), json!(conversion));
+        }
+
         if quality_score >= 4 {
             // High-quality company — check if undervalued relative to growth
             let high_pe_threshold = match revenue_growth {
@@ -1382,13 +1834,36 @@ impl FundamentalAnalysisEngine {
             metrics_map.insert("revenue".to_string(), json!(revenue));
         }
 
+        // --- Rule of 40 (technology only): revenue growth % + FCF margin %,
+        // or operating margin % when FCF isn't available ---
+        if sector == "technology" {
+            let margin = ttm_revenue.filter(|r| *r > 0.0).and_then(|revenue| {
+                ttm_fcf
+                    .map(|fcf| (fcf / revenue * 100.0, "fcf_margin"))
+                    .or_else(|| {
+                        ttm_operating_income.map(|oi| (oi / revenue * 100.0, "operating_margin"))
+                    })
+            });
+            if let (Some(growth), Some((margin, margin_basis))) = (revenue_growth, margin) {
+                let rule_of_40 = growth + margin;
+                metrics_map.insert("rule_of_40".to_string(), json!(rule_of_40));
+                metrics_map.insert("rule_of_40_margin_basis".to_string(), json!(margin_basis));
+                if rule_of_40 >= 40.0 {
+                    signals.push(("Passes Rule of 40", 2, true));
+                } else {
+                    signals.push(("Fails Rule of 40", 1, false));
+                }
+            }
+        }
+
         // Calculate overall signal
         let mut total_score = 0;
         let mut total_weight = 0;
 
-        for (_, weight, bullish) in &signals {
+        for (name, default_weight, bullish) in &signals {
+            let weight = self.signal_weights.weight(name, *default_weight);
             total_weight += weight;
-            total_score += if *bullish { *weight } else { -weight };
+            total_score += if *bullish { weight } else { -weight };
         }
 
         let normalized_score = if total_weight > 0 {
@@ -1408,7 +1883,20 @@ impl FundamentalAnalysisEngine {
             0.4
         };
         let data_completeness = data_fields_present as f64 / total_fields as f64;
-        let confidence = (signal_confidence * 0.6 + data_completeness * 0.4).min(0.95);
+
+        // Freshly filed numbers are the most reliable; by the next report they're stale
+        let financials_age_days = latest
+            .filing_date
+            .map(|filed| (self.clock.now().date_naive() - filed).num_days().max(0));
+        let filing_age_factor = financials_age_days
+            .map(|age| 1.0 - MAX_FILING_AGE_DECAY * (age as f64 / FILING_CYCLE_DAYS).min(1.0))
+            .unwrap_or(1.0);
+        if let Some(age) = financials_age_days {
+            metrics_map.insert("financials_age_days".to_string(), json!(age));
+        }
+
+        let confidence =
+            ((signal_confidence * 0.6 + data_completeness * 0.4) * filing_age_factor).min(0.95);
 
         let reason = signals
             .iter()
@@ -1417,10 +1905,18 @@ impl FundamentalAnalysisEngine {
             .join(", ");
 
         let metrics = json!(metrics_map);
+        let confidence_components = json!({
+            "signal_confidence": signal_confidence,
+            "data_completeness": data_completeness,
+            "fields_present": data_fields_present,
+            "total_fields": total_fields,
+            "signal_count": signals.len(),
+            "filing_age_factor": filing_age_factor,
+        });
 
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason: if reason.is_empty() {
@@ -1429,11 +1925,14 @@ impl FundamentalAnalysisEngine {
                 reason
             },
             metrics,
+            confidence_components,
         })
     }
 
     /// Enhanced analysis that incorporates analyst consensus data.
-    /// Blends the original fundamental score (70%) with analyst consensus signals (30%).
+    /// Blends the original fundamental score with analyst consensus signals at the
+    /// engine's consensus weight (30% by default, see `with_consensus_weight`),
+    /// optionally capped by `with_max_consensus_shift`.
     /// If no consensus data is available, falls through to analyze_enhanced unchanged.
     #[allow(clippy::too_many_arguments)]
     pub fn analyze_with_consensus(
@@ -1453,6 +1952,7 @@ impl FundamentalAnalysisEngine {
             shares_outstanding,
             risk_free_rate,
             sic_description,
+            self.tax_rate,
         )?;
 
         // If no consensus data at all, return unchanged
@@ -1550,6 +2050,53 @@ impl FundamentalAnalysisEngine {
             }
         }
 
+        // --- Consensus quality: target dispersion and rating staleness ---
+        let mut consensus_weight = self.consensus_weight;
+        let mut quality_flags: Vec<&str> = Vec::new();
+        let dispersion = consensus_data.consensus.as_ref().and_then(|c| {
+            match (
+                c.high_price_target,
+                c.low_price_target,
+                c.consensus_price_target,
+            ) {
+                (Some(high), Some(low), Some(target)) if target > 0.0 && high >= low => {
+                    Some((high - low) / target)
+                }
+                _ => None,
+            }
+        });
+        if let Some(dispersion) = dispersion {
+            metrics_map.insert(
+                "analyst_target_dispersion".to_string(),
+                serde_json::json!(dispersion),
+            );
+            if dispersion > HIGH_TARGET_DISPERSION {
+                consensus_weight *= CONSENSUS_DAMPENING;
+                quality_flags.push("High Analyst Disagreement");
+            }
+        }
+        let newest_rating_age_days = consensus_data
+            .recent_ratings
+            .iter()
+            .filter_map(|r| r.date.as_deref())
+            .filter_map(|d| chrono::NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok())
+            .max()
+            .map(|newest| (self.clock.now().date_naive() - newest).num_days());
+        if let Some(age) = newest_rating_age_days {
+            metrics_map.insert(
+                "analyst_newest_rating_age_days".to_string(),
+                serde_json::json!(age),
+            );
+            if age > STALE_RATING_DAYS {
+                consensus_weight *= CONSENSUS_DAMPENING;
+                quality_flags.push("Stale Analyst Ratings");
+            }
+        }
+        metrics_map.insert(
+            "consensus_blend_weight".to_string(),
+            serde_json::json!(consensus_weight),
+        );
+
         // --- Recent rating momentum (upgrades vs downgrades) ---
         if !consensus_data.recent_ratings.is_empty() {
             let mut upgrades = 0i32;
@@ -1603,9 +2150,10 @@ impl FundamentalAnalysisEngine {
         // Calculate consensus score
         let mut consensus_total_score = 0i32;
         let mut consensus_total_weight = 0i32;
-        for (_, weight, bullish) in &consensus_signals {
+        for (name, default_weight, bullish) in &consensus_signals {
+            let weight = self.signal_weights.weight(name, *default_weight);
             consensus_total_weight += weight;
-            consensus_total_score += if *bullish { *weight } else { -weight };
+            consensus_total_score += if *bullish { weight } else { -weight };
         }
 
         let consensus_normalized = if consensus_total_weight > 0 {
@@ -1614,15 +2162,21 @@ impl FundamentalAnalysisEngine {
             0.0
         };
 
-        // Blend: 70% original + 30% consensus
+        // Blend at the consensus weight, less when analysts disagree or are stale
         let original_score = result.signal.to_score() as f64;
-        let blended_score = original_score * 0.70 + consensus_normalized * 0.30;
+        let mut blended_score =
+            original_score * (1.0 - consensus_weight) + consensus_normalized * consensus_weight;
+        if let Some(max_shift) = self.max_consensus_shift {
+            blended_score =
+                original_score + (blended_score - original_score).clamp(-max_shift, max_shift);
+        }
         let new_signal = SignalStrength::from_score(blended_score as i32);
 
         // Append consensus reasons to existing reason
         let consensus_reason = consensus_signals
             .iter()
             .map(|(name, _, bullish)| format!("{} {}", if *bullish { "+" } else { "-" }, name))
+            .chain(quality_flags.iter().map(|flag| format!("! {}", flag)))
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -1738,9 +2292,10 @@ impl FundamentalAnalysisEngine {
         let mut total_score = 0;
         let mut total_weight = 0;
 
-        for (_, weight, bullish) in &signals {
+        for (name, default_weight, bullish) in &signals {
+            let weight = self.signal_weights.weight(name, *default_weight);
             total_weight += weight;
-            total_score += if *bullish { *weight } else { -weight };
+            total_score += if *bullish { weight } else { -weight };
         }
 
         let normalized_score = if total_weight > 0 {
@@ -1765,10 +2320,14 @@ impl FundamentalAnalysisEngine {
             .join(", ");
 
         let metrics = json!(metrics_map);
+        let confidence_components = json!({
+            "signal_confidence": confidence,
+            "signal_count": signals.len(),
+        });
 
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason: if reason.is_empty() {
@@ -1777,6 +2336,7 @@ impl FundamentalAnalysisEngine {
                 reason
             },
             metrics,
+            confidence_components,
         })
     }
 }
@@ -0,0 +1,62 @@
+/// Cross-sectional distribution of one metric among sector peers
+#[derive(Debug, Clone, Copy)]
+pub struct MetricBenchmark {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl MetricBenchmark {
+    const fn new(mean: f64, std_dev: f64) -> Self {
+        Self { mean, std_dev }
+    }
+
+    /// Standard deviations `value` sits above the peer mean, capped at ±3 so one
+    /// outlier metric can't dominate a composite
+    pub fn z_score(&self, value: f64) -> Option<f64> {
+        (self.std_dev > 0.0 && value.is_finite())
+            .then(|| ((value - self.mean) / self.std_dev).clamp(-3.0, 3.0))
+    }
+}
+
+/// Typical peer distributions of the quality metrics for one sector.
+///
+/// ROE, net margin, and ROIC are percentages; FCF conversion is free cash flow
+/// over net income. Figures are long-run approximations for US large and mid caps.
+#[derive(Debug, Clone, Copy)]
+pub struct SectorBenchmarks {
+    pub roe: MetricBenchmark,
+    pub profit_margin: MetricBenchmark,
+    pub roic: MetricBenchmark,
+    pub fcf_conversion: MetricBenchmark,
+}
+
+impl SectorBenchmarks {
+    const fn new(roe: (f64, f64), margin: (f64, f64), roic: (f64, f64), fcf: (f64, f64)) -> Self {
+        Self {
+            roe: MetricBenchmark::new(roe.0, roe.1),
+            profit_margin: MetricBenchmark::new(margin.0, margin.1),
+            roic: MetricBenchmark::new(roic.0, roic.1),
+            fcf_conversion: MetricBenchmark::new(fcf.0, fcf.1),
+        }
+    }
+
+    /// Benchmarks for a sector as returned by `classify_sector`, falling back to
+    /// broad-market figures for "unknown" or unlisted sectors
+    pub fn for_sector(sector: &str) -> Self {
+        match sector {
+            "technology" => Self::new((18.0, 15.0), (15.0, 12.0), (14.0, 12.0), (1.1, 0.5)),
+            "healthcare" => Self::new((14.0, 15.0), (10.0, 14.0), (10.0, 10.0), (1.0, 0.6)),
+            // Bank margins are on revenue net of interest expense, ROIC is leverage-diluted
+            "financial" => Self::new((11.0, 5.0), (20.0, 10.0), (6.0, 4.0), (1.0, 0.8)),
+            // Heavy regulated capex keeps utility FCF near zero
+            "utilities" => Self::new((9.0, 3.0), (11.0, 5.0), (5.0, 2.0), (0.2, 0.8)),
+            "energy" => Self::new((14.0, 10.0), (9.0, 8.0), (10.0, 7.0), (0.9, 0.6)),
+            "consumer_staples" => Self::new((20.0, 12.0), (8.0, 5.0), (12.0, 6.0), (0.95, 0.3)),
+            "industrial" => Self::new((15.0, 9.0), (9.0, 6.0), (11.0, 6.0), (0.9, 0.4)),
+            // Depreciation depresses REIT net income, inflating FCF conversion
+            "real_estate" => Self::new((7.0, 5.0), (20.0, 15.0), (4.0, 3.0), (1.2, 0.8)),
+            "telecom" => Self::new((10.0, 8.0), (10.0, 8.0), (6.0, 4.0), (0.8, 0.6)),
+            _ => Self::new((12.0, 10.0), (9.0, 10.0), (9.0, 8.0), (0.9, 0.5)),
+        }
+    }
+}
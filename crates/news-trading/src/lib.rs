@@ -530,6 +530,7 @@ mod tests {
             description: Some("Company reports record profits and growth".to_string()),
             keywords: vec![],
             tickers: vec!["AAPL".to_string()],
+            source: None,
         };
 
         let score = scanner.keyword_based_sentiment(&positive_article);
@@ -544,6 +545,7 @@ mod tests {
             description: Some("Company warns of declining revenues".to_string()),
             keywords: vec![],
             tickers: vec!["TSLA".to_string()],
+            source: None,
         };
 
         let score = scanner.keyword_based_sentiment(&negative_article);
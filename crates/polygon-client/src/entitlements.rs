@@ -0,0 +1,200 @@
+use crate::{PolygonClient, BASE_URL};
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+
+/// Liquid ticker every plan that covers an endpoint has data for
+const PROBE_SYMBOL: &str = "AAPL";
+
+/// Groups of Polygon endpoints that are licensed together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EndpointFamily {
+    Aggregates,
+    Snapshots,
+    TickerDetails,
+    Financials,
+    News,
+    Dividends,
+    Indicators,
+    Options,
+    Insiders,
+    Benzinga,
+}
+
+impl EndpointFamily {
+    pub const ALL: [EndpointFamily; 10] = [
+        EndpointFamily::Aggregates,
+        EndpointFamily::Snapshots,
+        EndpointFamily::TickerDetails,
+        EndpointFamily::Financials,
+        EndpointFamily::News,
+        EndpointFamily::Dividends,
+        EndpointFamily::Indicators,
+        EndpointFamily::Options,
+        EndpointFamily::Insiders,
+        EndpointFamily::Benzinga,
+    ];
+
+    /// Cheapest request in the family: URL and extra query parameters
+    fn probe(self, symbol: &str) -> (String, Vec<(&'static str, String)>) {
+        let ticker = || vec![("ticker", symbol.to_string()), ("limit", "1".to_string())];
+        match self {
+            EndpointFamily::Aggregates => (
+                format!("{}/v2/aggs/ticker/{}/prev", BASE_URL, symbol),
+                Vec::new(),
+            ),
+            EndpointFamily::Snapshots => (
+                format!(
+                    "{}/v2/snapshot/locale/us/markets/stocks/tickers/{}",
+                    BASE_URL, symbol
+                ),
+                Vec::new(),
+            ),
+            EndpointFamily::TickerDetails => (
+                format!("{}/v3/reference/tickers/{}", BASE_URL, symbol),
+                Vec::new(),
+            ),
+            EndpointFamily::Financials => {
+                (format!("{}/vX/reference/financials", BASE_URL), ticker())
+            }
+            EndpointFamily::News => (format!("{}/v2/reference/news", BASE_URL), ticker()),
+            EndpointFamily::Dividends => (format!("{}/v3/reference/dividends", BASE_URL), ticker()),
+            EndpointFamily::Indicators => (
+                format!("{}/v1/indicators/sma/{}", BASE_URL, symbol),
+                vec![
+                    ("timespan", "day".to_string()),
+                    ("window", "50".to_string()),
+                    ("limit", "1".to_string()),
+                ],
+            ),
+            EndpointFamily::Options => (
+                format!("{}/v3/snapshot/options/{}", BASE_URL, symbol),
+                vec![("limit", "1".to_string())],
+            ),
+            EndpointFamily::Insiders => (format!("{}/vX/reference/insiders", BASE_URL), ticker()),
+            EndpointFamily::Benzinga => (format!("{}/benzinga/v1/ratings", BASE_URL), ticker()),
+        }
+    }
+}
+
+/// Outcome of probing one endpoint family
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EntitlementStatus {
+    Available,
+    /// 401/403: the API key's plan does not include the endpoint
+    NotEntitled,
+    /// Any other failure (network, rate limit, server error); retry later
+    Error(String),
+}
+
+impl EntitlementStatus {
+    fn from_http_status(status: u16) -> Self {
+        match status {
+            200..=299 => EntitlementStatus::Available,
+            401 | 403 => EntitlementStatus::NotEntitled,
+            _ => EntitlementStatus::Error(format!("HTTP {}", status)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointCheck {
+    pub endpoint: EndpointFamily,
+    pub status: EntitlementStatus,
+}
+
+/// Which endpoint families the API key can use, from `check_entitlements`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitlementReport {
+    pub probe_symbol: String,
+    pub checked_at: DateTime<Utc>,
+    pub endpoints: Vec<EndpointCheck>,
+}
+
+impl EntitlementReport {
+    pub fn status(&self, endpoint: EndpointFamily) -> Option<&EntitlementStatus> {
+        self.endpoints
+            .iter()
+            .find(|c| c.endpoint == endpoint)
+            .map(|c| &c.status)
+    }
+
+    pub fn is_available(&self, endpoint: EndpointFamily) -> bool {
+        self.status(endpoint) == Some(&EntitlementStatus::Available)
+    }
+
+    /// Families the plan does not cover; callers can skip them up front
+    pub fn not_entitled(&self) -> Vec<EndpointFamily> {
+        self.endpoints
+            .iter()
+            .filter(|c| c.status == EntitlementStatus::NotEntitled)
+            .map(|c| c.endpoint)
+            .collect()
+    }
+}
+
+impl PolygonClient {
+    /// Make one minimal request per endpoint family against a liquid ticker and
+    /// report which ones the API key is entitled to. Goes through the shared rate
+    /// limiter, so it costs `EndpointFamily::ALL.len()` requests of budget.
+    pub async fn check_entitlements(&self) -> EntitlementReport {
+        let checks = join_all(EndpointFamily::ALL.into_iter().map(|endpoint| async move {
+            let (url, params) = endpoint.probe(PROBE_SYMBOL);
+            let builder = self
+                .client
+                .get(&url)
+                .query(&params)
+                .query(&[("apiKey", self.api_key.as_str())]);
+            let status = match self.send_request(builder).await {
+                Ok(response) => EntitlementStatus::from_http_status(response.status().as_u16()),
+                Err(e) => EntitlementStatus::Error(e.to_string()),
+            };
+            EndpointCheck { endpoint, status }
+        }))
+        .await;
+
+        for check in &checks {
+            if check.status != EntitlementStatus::Available {
+                tracing::info!("Polygon {:?}: {:?}", check.endpoint, check.status);
+            }
+        }
+
+        EntitlementReport {
+            probe_symbol: PROBE_SYMBOL.to_string(),
+            checked_at: Utc::now(),
+            endpoints: checks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entitlement_report_lookup() {
+        let report = EntitlementReport {
+            probe_symbol: PROBE_SYMBOL.to_string(),
+            checked_at: Utc::now(),
+            endpoints: vec![
+                EndpointCheck {
+                    endpoint: EndpointFamily::Aggregates,
+                    status: EntitlementStatus::from_http_status(200),
+                },
+                EndpointCheck {
+                    endpoint: EndpointFamily::Benzinga,
+                    status: EntitlementStatus::from_http_status(403),
+                },
+                EndpointCheck {
+                    endpoint: EndpointFamily::Options,
+                    status: EntitlementStatus::from_http_status(502),
+                },
+            ],
+        };
+
+        assert!(report.is_available(EndpointFamily::Aggregates));
+        assert!(!report.is_available(EndpointFamily::Options));
+        assert!(!report.is_available(EndpointFamily::News));
+        assert_eq!(report.not_entitled(), vec![EndpointFamily::Benzinga]);
+    }
+}
@@ -1,7 +1,15 @@
+mod entitlements;
+mod news_sources;
+mod symbol_resolver;
 pub mod websocket;
 
-use analysis_core::{AnalysisError, AnalystRating, Bar, ConsensusRating, Financials, NewsArticle};
-use chrono::{DateTime, Utc};
+pub use entitlements::{EndpointCheck, EndpointFamily, EntitlementReport, EntitlementStatus};
+pub use news_sources::{FinnhubNewsSource, PolygonNewsSource};
+
+use analysis_core::{
+    AnalysisError, AnalystRating, AssetClass, Bar, ConsensusRating, Financials, NewsArticle,
+};
+use chrono::{DateTime, NaiveTime, Timelike, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
@@ -12,6 +20,16 @@ use tokio::time::Instant;
 
 const BASE_URL: &str = "https://api.polygon.io";
 
+/// Most `next_url` pages followed for one aggregates request
+const MAX_AGGREGATE_PAGES: usize = 50;
+
+/// Bars at which aggregate pagination stops (~5 years of regular-session minute bars)
+const MAX_AGGREGATE_BARS: usize = 500_000;
+
+/// A burst of 429s hits every in-flight request at once; the concurrency cap
+/// is halved at most once per this window
+const CONCURRENCY_DECREASE_COOLDOWN: Duration = Duration::from_secs(2);
+
 /// Sliding-window rate limiter: at most `max_requests` per `window` duration.
 #[derive(Clone)]
 struct RateLimiter {
@@ -61,13 +79,129 @@ impl RateLimiter {
     }
 }
 
+/// In-flight request cap that adapts to rate limiting (AIMD): halved when
+/// Polygon answers 429, raised by one after a cap's worth of successes, never
+/// above the configured maximum.
+struct AdaptiveConcurrency {
+    semaphore: Semaphore,
+    max: usize,
+    state: std::sync::Mutex<ConcurrencyState>,
+}
+
+struct ConcurrencyState {
+    /// Effective cap
+    limit: usize,
+    /// Permits to retire as they are released; they were in use when the cap shrank
+    debt: usize,
+    /// Successful requests since the cap last grew
+    successes: usize,
+    last_decrease: Option<Instant>,
+}
+
+/// Permit from `AdaptiveConcurrency`; retired instead of released while the cap is shrinking
+struct ConcurrencyPermit<'a> {
+    permit: Option<tokio::sync::SemaphorePermit<'a>>,
+    owner: &'a AdaptiveConcurrency,
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        if let Some(permit) = self.permit.take() {
+            let mut state = self.owner.state();
+            if state.debt > 0 {
+                state.debt -= 1;
+                permit.forget();
+            }
+        }
+    }
+}
+
+impl AdaptiveConcurrency {
+    fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            semaphore: Semaphore::new(max),
+            max,
+            state: std::sync::Mutex::new(ConcurrencyState {
+                limit: max,
+                debt: 0,
+                successes: 0,
+                last_decrease: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ConcurrencyState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn acquire(&self) -> Result<ConcurrencyPermit<'_>, AnalysisError> {
+        let permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| AnalysisError::ApiError("Concurrency semaphore closed".to_string()))?;
+        Ok(ConcurrencyPermit {
+            permit: Some(permit),
+            owner: self,
+        })
+    }
+
+    /// Multiplicative decrease: halve the cap (at least 1)
+    fn on_rate_limited(&self) {
+        let mut state = self.state();
+        let now = Instant::now();
+        if state
+            .last_decrease
+            .is_some_and(|t| now.duration_since(t) < CONCURRENCY_DECREASE_COOLDOWN)
+        {
+            return;
+        }
+        let new_limit = (state.limit / 2).max(1);
+        let shrink = state.limit - new_limit;
+        // Idle permits go now, busy ones when their requests finish
+        let retired = self.semaphore.forget_permits(shrink);
+        state.debt += shrink - retired;
+        state.limit = new_limit;
+        state.successes = 0;
+        state.last_decrease = Some(now);
+        if shrink > 0 {
+            tracing::warn!(
+                "Polygon rate limited: concurrency reduced to {}/{}",
+                new_limit,
+                self.max
+            );
+        }
+    }
+
+    /// Additive increase: one more permit per `limit` successful requests
+    fn on_success(&self) {
+        let mut state = self.state();
+        if state.limit >= self.max {
+            return;
+        }
+        state.successes += 1;
+        if state.successes < state.limit {
+            return;
+        }
+        state.successes = 0;
+        state.limit += 1;
+        if state.debt > 0 {
+            state.debt -= 1;
+        } else {
+            self.semaphore.add_permits(1);
+        }
+        tracing::debug!("Polygon concurrency raised to {}/{}", state.limit, self.max);
+    }
+}
+
 #[derive(Clone)]
 pub struct PolygonClient {
     api_key: String,
     client: Client,
     rate_limiter: RateLimiter,
-    /// Limits the number of in-flight HTTP requests to Polygon.
-    concurrency_limit: Arc<Semaphore>,
+    /// Limits the number of in-flight HTTP requests to Polygon, backing off on 429s.
+    concurrency: Arc<AdaptiveConcurrency>,
 }
 
 // Finnhub article response structure
@@ -105,6 +239,7 @@ impl PolygonClient {
 
         // Cap concurrent in-flight requests to avoid overwhelming the connection pool.
         // Default 50 balances throughput vs connection overhead for most plans.
+        // This is the ceiling: the effective cap drops while Polygon returns 429s.
         let max_concurrent: usize = std::env::var("POLYGON_MAX_CONCURRENT")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -120,7 +255,7 @@ impl PolygonClient {
             api_key,
             client,
             rate_limiter: RateLimiter::new(rate_limit, Duration::from_secs(60)),
-            concurrency_limit: Arc::new(Semaphore::new(max_concurrent)),
+            concurrency: Arc::new(AdaptiveConcurrency::new(max_concurrent)),
         }
     }
 
@@ -134,11 +269,7 @@ impl PolygonClient {
             .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
 
         // Acquire concurrency permit (limits in-flight requests)
-        let _permit = self
-            .concurrency_limit
-            .acquire()
-            .await
-            .map_err(|_| AnalysisError::ApiError("Concurrency semaphore closed".to_string()))?;
+        let _permit = self.concurrency.acquire().await?;
 
         for attempt in 0..3u32 {
             self.rate_limiter.acquire().await;
@@ -149,11 +280,13 @@ impl PolygonClient {
                 .client
                 .execute(req_clone)
                 .await
-                .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+                .map_err(|e| AnalysisError::Network(e.to_string()))?;
 
             if response.status().as_u16() != 429 {
+                self.concurrency.on_success();
                 return Ok(response);
             }
+            self.concurrency.on_rate_limited();
 
             let wait_secs = 2u64;
             tracing::warn!(
@@ -164,12 +297,24 @@ impl PolygonClient {
             tokio::time::sleep(Duration::from_secs(wait_secs)).await;
         }
 
-        Err(AnalysisError::ApiError(
+        Err(AnalysisError::RateLimited(
             "Rate limited by Polygon after 3 retries".to_string(),
         ))
     }
 
-    /// Get aggregates (bars) for a symbol
+    /// Typed error for a non-success response; `context` prefixes the status and body
+    async fn status_error(context: &str, response: reqwest::Response) -> AnalysisError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        AnalysisError::from_http_status(
+            status.as_u16(),
+            format!("{} {}: {}", context, status, body),
+        )
+    }
+
+    /// Get aggregates (bars) for a symbol. Intraday timespans include the
+    /// pre- and post-market bars Polygon returns; see `get_aggregates_with_session`.
+    /// Crypto (`X:`) and forex (`C:`) tickers share the stock aggregates path.
     pub async fn get_aggregates(
         &self,
         symbol: &str,
@@ -177,6 +322,43 @@ impl PolygonClient {
         timespan: &str,
         from: DateTime<Utc>,
         to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AnalysisError> {
+        self.get_aggregates_with_session(symbol, multiplier, timespan, from, to, true)
+            .await
+    }
+
+    /// Get aggregates, keeping extended-hours (4:00–9:30 and 16:00–20:00 ET) bars
+    /// only when `extended_hours` is set. Daily and longer bars always cover the
+    /// regular session, so the flag only filters intraday timespans.
+    pub async fn get_aggregates_with_session(
+        &self,
+        symbol: &str,
+        multiplier: u32,
+        timespan: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        extended_hours: bool,
+    ) -> Result<Vec<Bar>, AnalysisError> {
+        let mut bars = self
+            .fetch_aggregates(symbol, multiplier, timespan, from, to)
+            .await?;
+        // Crypto and forex have no exchange session to filter to
+        if !extended_hours
+            && matches!(timespan, "second" | "minute" | "hour")
+            && AssetClass::from_symbol(symbol).is_equity()
+        {
+            bars.retain(|b| market_session(b.timestamp) == MarketSession::Regular);
+        }
+        Ok(bars)
+    }
+
+    async fn fetch_aggregates(
+        &self,
+        symbol: &str,
+        multiplier: u32,
+        timespan: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
     ) -> Result<Vec<Bar>, AnalysisError> {
         let url = format!(
             "{}/v2/aggs/ticker/{}/range/{}/{}/{}/{}",
@@ -188,39 +370,94 @@ impl PolygonClient {
             to.format("%Y-%m-%d")
         );
 
+        let mut builder = self.client.get(&url).query(&[
+            ("apiKey", &self.api_key),
+            ("adjusted", &"true".to_string()),
+            ("limit", &"50000".to_string()),
+            // Newest first, so hitting the page or bar cap drops the oldest bars
+            ("sort", &"desc".to_string()),
+        ]);
+
+        // Long minute-bar ranges span several pages linked by `next_url`
+        let mut bars = Vec::new();
+        for page in 0..MAX_AGGREGATE_PAGES {
+            let response = self.send_request(builder).await?;
+            if !response.status().is_success() {
+                return Err(Self::status_error("HTTP", response).await);
+            }
+
+            let agg_response: AggregateResponse = response
+                .json()
+                .await
+                .map_err(|e| AnalysisError::Parse(e.to_string()))?;
+
+            match push_aggregate_page(&mut bars, agg_response, MAX_AGGREGATE_BARS) {
+                // next_url carries the cursor but not the API key
+                Some(next) => {
+                    builder = self
+                        .client
+                        .get(next)
+                        .query(&[("apiKey", self.api_key.as_str())]);
+                }
+                None => {
+                    if bars.len() >= MAX_AGGREGATE_BARS {
+                        tracing::warn!(
+                            "{} aggregates truncated at {} bars, older bars dropped",
+                            symbol,
+                            MAX_AGGREGATE_BARS
+                        );
+                    }
+                    break;
+                }
+            }
+            if page + 1 == MAX_AGGREGATE_PAGES {
+                tracing::warn!(
+                    "{} aggregates truncated at {} pages ({} bars), older bars dropped",
+                    symbol,
+                    MAX_AGGREGATE_PAGES,
+                    bars.len()
+                );
+            }
+        }
+
+        bars.reverse();
+        Ok(bars)
+    }
+
+    /// Get the daily close of every US stock for one trading day (grouped daily aggregates).
+    /// Returns ticker -> close; empty on weekends and market holidays.
+    pub async fn get_grouped_daily_closes(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> Result<HashMap<String, f64>, AnalysisError> {
+        let url = format!(
+            "{}/v2/aggs/grouped/locale/us/market/stocks/{}",
+            BASE_URL,
+            date.format("%Y-%m-%d")
+        );
+
         let response = self
-            .send_request(self.client.get(&url).query(&[
-                ("apiKey", &self.api_key),
-                ("adjusted", &"true".to_string()),
-                ("limit", &"50000".to_string()),
-            ]))
+            .send_request(
+                self.client
+                    .get(&url)
+                    .query(&[("apiKey", self.api_key.as_str()), ("adjusted", "true")]),
+            )
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
-        let agg_response: AggregateResponse = response
+        let grouped: GroupedDailyResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
-        Ok(agg_response
+        Ok(grouped
             .results
             .into_iter()
-            .map(|r| Bar {
-                timestamp: DateTime::from_timestamp_millis(r.t).unwrap_or_else(Utc::now),
-                open: r.o,
-                high: r.h,
-                low: r.l,
-                close: r.c,
-                volume: r.v,
-                vwap: r.vw,
-            })
+            .filter(|r| r.c > 0.0)
+            .map(|r| (r.ticker, r.c))
             .collect())
     }
 
@@ -241,17 +478,13 @@ impl PolygonClient {
             if response.status().as_u16() == 403 || response.status().as_u16() == 401 {
                 return Ok(Vec::new());
             }
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let fin_response: FinancialsResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(fin_response
             .results
@@ -265,6 +498,10 @@ impl PolygonClient {
                     symbol: symbol.to_string(),
                     fiscal_period: r.fiscal_period,
                     fiscal_year: r.fiscal_year.parse().unwrap_or(0),
+                    filing_date: r
+                        .filing_date
+                        .as_deref()
+                        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
                     revenue: income
                         .get("revenues")
                         .and_then(|v| v.get("value"))
@@ -309,6 +546,53 @@ impl PolygonClient {
                         .get("net_cash_flow_from_financing_activities")
                         .and_then(|v| v.get("value"))
                         .and_then(|v| v.as_f64()),
+                    // Filers label capex differently; report it as a positive outflow
+                    capital_expenditure: [
+                        "capital_expenditure",
+                        "payments_to_acquire_property_plant_and_equipment",
+                        "purchase_of_property_plant_and_equipment",
+                    ]
+                    .iter()
+                    .find_map(|key| cash_flow.get(*key))
+                    .and_then(|v| v.get("value"))
+                    .and_then(|v| v.as_f64())
+                    .map(f64::abs),
+                    cost_of_revenue: income
+                        .get("cost_of_revenue")
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    accounts_receivable: ["accounts_receivable", "accounts_receivable_net"]
+                        .iter()
+                        .find_map(|key| balance.get(*key))
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    inventory: balance
+                        .get("inventory")
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    accounts_payable: balance
+                        .get("accounts_payable")
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    income_tax_expense: income
+                        .get("income_tax_expense_benefit")
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    pretax_income: income
+                        .get("income_loss_from_continuing_operations_before_tax")
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    weighted_average_shares: ["diluted_average_shares", "basic_average_shares"]
+                        .iter()
+                        .find_map(|key| income.get(*key))
+                        .and_then(|v| v.get("value"))
+                        .and_then(|v| v.as_f64()),
+                    currency: ["revenues", "net_income_loss"]
+                        .iter()
+                        .find_map(|key| income.get(*key))
+                        .and_then(|v| v.get("unit"))
+                        .and_then(|v| v.as_str())
+                        .map(|unit| unit.to_uppercase()),
                 }
             })
             .collect())
@@ -319,6 +603,17 @@ impl PolygonClient {
         &self,
         symbol: Option<&str>,
         limit: u32,
+    ) -> Result<Vec<NewsArticle>, AnalysisError> {
+        self.get_news_before(symbol, limit, None).await
+    }
+
+    /// Get news articles published at or before `published_before` (latest first).
+    /// Passing `None` returns the most recent articles, same as `get_news`.
+    pub async fn get_news_before(
+        &self,
+        symbol: Option<&str>,
+        limit: u32,
+        published_before: Option<DateTime<Utc>>,
     ) -> Result<Vec<NewsArticle>, AnalysisError> {
         let url = format!("{}/v2/reference/news", BASE_URL);
 
@@ -329,23 +624,25 @@ impl PolygonClient {
         if let Some(sym) = symbol {
             query.push(("ticker", sym.to_string()));
         }
+        if let Some(before) = published_before {
+            query.push((
+                "published_utc.lte",
+                before.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            ));
+        }
 
         let response = self
             .send_request(self.client.get(&url).query(&query))
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let news_response: NewsResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(news_response
             .results
@@ -361,6 +658,7 @@ impl PolygonClient {
                 description: r.description,
                 keywords: r.keywords.unwrap_or_default(),
                 tickers: r.tickers,
+                source: Some("polygon".to_string()),
             })
             .collect())
     }
@@ -374,17 +672,13 @@ impl PolygonClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let details_response: TickerDetailsResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(details_response.results)
     }
@@ -410,21 +704,46 @@ impl PolygonClient {
             if response.status().as_u16() == 403 || response.status().as_u16() == 401 {
                 return Ok(Vec::new());
             }
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let div_response: DividendResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(div_response.results)
     }
 
+    /// Get stock split history for a symbol, most recent first
+    pub async fn get_splits(
+        &self,
+        symbol: &str,
+        limit: u32,
+    ) -> Result<Vec<SplitInfo>, AnalysisError> {
+        let url = format!("{}/v3/reference/splits", BASE_URL);
+
+        let response = self
+            .send_request(self.client.get(&url).query(&[
+                ("ticker", symbol),
+                ("apiKey", &self.api_key as &str),
+                ("limit", &limit.to_string()),
+                ("order", "desc"),
+            ]))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::status_error("Splits HTTP", response).await);
+        }
+
+        let split_response: SplitResponse = response
+            .json()
+            .await
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
+
+        Ok(split_response.results)
+    }
+
     /// Get options chain snapshot for an underlying symbol
     pub async fn get_options_snapshot(
         &self,
@@ -444,17 +763,13 @@ impl PolygonClient {
             if response.status().as_u16() == 403 || response.status().as_u16() == 401 {
                 return Ok(Vec::new());
             }
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let snap_response: OptionsSnapshotResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(snap_response.results.unwrap_or_default())
     }
@@ -479,44 +794,33 @@ impl PolygonClient {
             if response.status().as_u16() == 403 || response.status().as_u16() == 401 {
                 return Ok(Vec::new());
             }
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let insider_response: InsiderResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(insider_response.results.unwrap_or_default())
     }
 
     /// Get snapshot for a ticker (near-real-time last trade, today's OHLCV, prev day)
     pub async fn get_snapshot(&self, symbol: &str) -> Result<SnapshotTicker, AnalysisError> {
-        let url = format!(
-            "{}/v2/snapshot/locale/us/markets/stocks/tickers/{}",
-            BASE_URL, symbol
-        );
+        let url = snapshot_url(symbol);
 
         let response = self
             .send_request(self.client.get(&url).query(&[("apiKey", &self.api_key)]))
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "Snapshot HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("Snapshot HTTP", response).await);
         }
 
         let snap_response: SnapshotResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(snap_response.ticker)
     }
@@ -547,10 +851,7 @@ impl PolygonClient {
                 tokio::time::sleep(backoff).await;
             }
 
-            let _permit =
-                self.concurrency_limit.acquire().await.map_err(|_| {
-                    AnalysisError::ApiError("Concurrency semaphore closed".to_string())
-                })?;
+            let _permit = self.concurrency.acquire().await?;
             self.rate_limiter.acquire().await;
 
             let response = match heavy_client
@@ -561,17 +862,23 @@ impl PolygonClient {
             {
                 Ok(r) => r,
                 Err(e) => {
-                    last_err = Some(format!("All snapshots request failed: {}", e));
+                    last_err = Some(AnalysisError::Network(format!(
+                        "All snapshots request failed: {}",
+                        e
+                    )));
                     continue;
                 }
             };
 
+            // Feed the adaptive limit like `send_request` does
+            if response.status().as_u16() == 429 {
+                self.concurrency.on_rate_limited();
+            } else {
+                self.concurrency.on_success();
+            }
+
             if !response.status().is_success() {
-                last_err = Some(format!(
-                    "All snapshots HTTP {}: {}",
-                    response.status(),
-                    response.text().await.unwrap_or_default()
-                ));
+                last_err = Some(Self::status_error("All snapshots HTTP", response).await);
                 continue;
             }
 
@@ -579,7 +886,10 @@ impl PolygonClient {
             let body = match response.text().await {
                 Ok(b) => b,
                 Err(e) => {
-                    last_err = Some(format!("All snapshots body read failed: {}", e));
+                    last_err = Some(AnalysisError::Network(format!(
+                        "All snapshots body read failed: {}",
+                        e
+                    )));
                     continue;
                 }
             };
@@ -592,7 +902,10 @@ impl PolygonClient {
                         e,
                         &body[..body.len().min(500)]
                     );
-                    last_err = Some(format!("All snapshots parse error: {}", e));
+                    last_err = Some(AnalysisError::Parse(format!(
+                        "All snapshots parse error: {}",
+                        e
+                    )));
                     continue;
                 }
             };
@@ -600,9 +913,9 @@ impl PolygonClient {
             return Ok(snap_response.tickers.unwrap_or_default());
         }
 
-        Err(AnalysisError::ApiError(last_err.unwrap_or_else(|| {
-            "All snapshots failed after retries".to_string()
-        })))
+        Err(last_err.unwrap_or_else(|| {
+            AnalysisError::ApiError("All snapshots failed after retries".to_string())
+        }))
     }
 
     /// Get SMA (Simple Moving Average) from Polygon technical indicators API
@@ -626,17 +939,13 @@ impl PolygonClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "SMA HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("SMA HTTP", response).await);
         }
 
         let ind_response: IndicatorResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(ind_response.results.values.unwrap_or_default())
     }
@@ -662,17 +971,13 @@ impl PolygonClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "RSI HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("RSI HTTP", response).await);
         }
 
         let ind_response: IndicatorResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(ind_response.results.values.unwrap_or_default())
     }
@@ -696,17 +1001,13 @@ impl PolygonClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "MACD HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("MACD HTTP", response).await);
         }
 
         let macd_response: MacdResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(macd_response.results.values.unwrap_or_default())
     }
@@ -740,7 +1041,7 @@ impl PolygonClient {
         let body: BenzingaConsensusResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(body.results.into_iter().next().map(|r| ConsensusRating {
             consensus_rating: r.consensus_rating,
@@ -789,7 +1090,7 @@ impl PolygonClient {
         let body: BenzingaRatingsResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(body
             .results
@@ -805,6 +1106,50 @@ impl PolygonClient {
             .collect())
     }
 
+    /// Get the next scheduled earnings report date (Benzinga earnings calendar).
+    /// Returns Ok(None) on 403/401 (subscription not available) or when nothing is scheduled.
+    pub async fn get_next_earnings_date(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<DateTime<Utc>>, AnalysisError> {
+        let url = format!("{}/benzinga/v1/earnings", BASE_URL);
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+
+        let response = self
+            .send_request(self.client.get(&url).query(&[
+                ("apiKey", self.api_key.as_str()),
+                ("ticker", symbol),
+                ("date.gte", today.as_str()),
+                ("sort", "date.asc"),
+                ("limit", "1"),
+            ]))
+            .await?;
+
+        let status = response.status().as_u16();
+        if status == 403 || status == 401 {
+            tracing::info!(
+                "Benzinga earnings calendar not available (HTTP {}), skipping",
+                status
+            );
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            tracing::warn!("Benzinga earnings HTTP {}: ignoring", status);
+            return Ok(None);
+        }
+
+        let body: BenzingaEarningsResponse = response
+            .json()
+            .await
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
+
+        Ok(body
+            .results
+            .into_iter()
+            .find_map(|r| benzinga_report_time(r.date.as_deref()?, r.time.as_deref())))
+    }
+
     /// Fetch news from Finnhub as a supplemental source.
     /// Requires FINNHUB_API_KEY env var. Returns empty vec if not configured.
     pub async fn get_finnhub_news(
@@ -836,7 +1181,7 @@ impl PolygonClient {
             .timeout(std::time::Duration::from_secs(10))
             .send()
             .await
-            .map_err(|e| AnalysisError::ApiError(format!("Finnhub request failed: {}", e)))?;
+            .map_err(|e| AnalysisError::Network(format!("Finnhub request failed: {}", e)))?;
 
         if !response.status().is_success() {
             tracing::warn!(
@@ -863,6 +1208,7 @@ impl PolygonClient {
                 tickers: vec![symbol.to_string()],
                 description: Some(a.summary),
                 keywords: vec![a.category],
+                source: Some("finnhub".to_string()),
             })
             .collect())
     }
@@ -883,7 +1229,7 @@ impl PolygonClient {
             .timeout(std::time::Duration::from_secs(10))
             .send()
             .await
-            .map_err(|e| AnalysisError::ApiError(format!("Finnhub general news failed: {}", e)))?;
+            .map_err(|e| AnalysisError::Network(format!("Finnhub general news failed: {}", e)))?;
 
         if !response.status().is_success() {
             tracing::warn!(
@@ -913,6 +1259,7 @@ impl PolygonClient {
                 },
                 description: Some(a.summary),
                 keywords: vec![a.category],
+                source: Some("finnhub".to_string()),
             })
             .collect())
     }
@@ -950,7 +1297,7 @@ impl PolygonClient {
             let body: TickerListResponse = response
                 .json()
                 .await
-                .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+                .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
             for t in &body.results {
                 // Filter: only US exchanges, skip OTC / weird tickers
@@ -1005,17 +1352,13 @@ impl PolygonClient {
             .await?;
 
         if !response.status().is_success() {
-            return Err(AnalysisError::ApiError(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+            return Err(Self::status_error("HTTP", response).await);
         }
 
         let body: TickerSearchResponse = response
             .json()
             .await
-            .map_err(|e| AnalysisError::ApiError(e.to_string()))?;
+            .map_err(|e| AnalysisError::Parse(e.to_string()))?;
 
         Ok(body.results)
     }
@@ -1106,11 +1449,61 @@ struct BenzingaRatingResult {
     date: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct BenzingaEarningsResponse {
+    #[serde(default)]
+    results: Vec<BenzingaEarningsResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BenzingaEarningsResult {
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupedDailyResponse {
+    #[serde(default)]
+    results: Vec<GroupedDailyResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupedDailyResult {
+    #[serde(rename = "T")]
+    ticker: String,
+    c: f64,
+}
+
 // Response structures
 #[derive(Debug, Deserialize)]
 struct AggregateResponse {
     #[serde(default)]
     results: Vec<AggregateResult>,
+    #[serde(default)]
+    next_url: Option<String>,
+}
+
+/// Append one newest-first (`sort=desc`) aggregates page to `bars`, up to
+/// `max_bars` in total, so a capped series keeps the most recent bars.
+/// Returns the next page's URL while there is one and the cap isn't reached.
+fn push_aggregate_page(
+    bars: &mut Vec<Bar>,
+    page: AggregateResponse,
+    max_bars: usize,
+) -> Option<String> {
+    let room = max_bars.saturating_sub(bars.len());
+    bars.extend(page.results.into_iter().take(room).map(|r| Bar {
+        timestamp: DateTime::from_timestamp_millis(r.t).unwrap_or_else(Utc::now),
+        open: r.o,
+        high: r.h,
+        low: r.l,
+        close: r.c,
+        volume: r.v,
+        vwap: r.vw,
+    }));
+    page.next_url.filter(|_| bars.len() < max_bars)
 }
 
 #[derive(Debug, Deserialize)]
@@ -1135,6 +1528,8 @@ struct FinancialsResponse {
 struct FinancialResult {
     fiscal_period: String,
     fiscal_year: String,
+    #[serde(default)]
+    filing_date: Option<String>,
     financials: FinancialStatements,
 }
 
@@ -1206,6 +1601,107 @@ pub struct DividendInfo {
     pub dividend_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct SplitResponse {
+    #[serde(default)]
+    results: Vec<SplitInfo>,
+}
+
+/// A stock split: `split_to` new shares for every `split_from` old ones
+/// (a 4-for-1 split is from 1, to 4)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitInfo {
+    /// First trading day at the post-split price, "YYYY-MM-DD"
+    pub execution_date: String,
+    pub split_from: f64,
+    pub split_to: f64,
+}
+
+/// Back-adjust raw bars for splits: bars before each split's execution date
+/// have prices divided and volume multiplied by `split_to / split_from`, so the
+/// series is continuous in post-split terms. Splits with unparseable dates or
+/// non-positive ratios are skipped.
+pub fn apply_splits(bars: &mut [Bar], splits: &[SplitInfo]) {
+    for split in splits {
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&split.execution_date, "%Y-%m-%d") else {
+            continue;
+        };
+        if split.split_from <= 0.0 || split.split_to <= 0.0 {
+            continue;
+        }
+        let ratio = split.split_to / split.split_from;
+        for bar in bars.iter_mut().filter(|b| b.timestamp.date_naive() < date) {
+            bar.open /= ratio;
+            bar.high /= ratio;
+            bar.low /= ratio;
+            bar.close /= ratio;
+            bar.volume *= ratio;
+            bar.vwap = bar.vwap.map(|v| v / ratio);
+        }
+    }
+}
+
+/// Snapshot endpoint for `symbol`: crypto and forex snapshots live under the
+/// global locale rather than US stocks
+fn snapshot_url(symbol: &str) -> String {
+    let market = match AssetClass::from_symbol(symbol) {
+        AssetClass::Equity => "locale/us/markets/stocks",
+        AssetClass::Crypto => "locale/global/markets/crypto",
+        AssetClass::Forex => "locale/global/markets/forex",
+    };
+    format!("{}/v2/snapshot/{}/tickers/{}", BASE_URL, market, symbol)
+}
+
+/// US equity trading session a timestamp falls in (Eastern time, weekdays;
+/// exchange holidays are not modelled)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketSession {
+    /// 4:00–9:30 ET
+    PreMarket,
+    /// 9:30–16:00 ET
+    Regular,
+    /// 16:00–20:00 ET
+    AfterHours,
+    Closed,
+}
+
+pub fn market_session(timestamp: DateTime<Utc>) -> MarketSession {
+    use chrono::Datelike;
+
+    let et = timestamp.with_timezone(&chrono_tz::US::Eastern);
+    if et.weekday().number_from_monday() > 5 {
+        return MarketSession::Closed;
+    }
+    let minutes = et.hour() * 60 + et.minute();
+    match minutes {
+        240..=569 => MarketSession::PreMarket,
+        570..=959 => MarketSession::Regular,
+        960..=1199 => MarketSession::AfterHours,
+        _ => MarketSession::Closed,
+    }
+}
+
+/// Eastern-time trading date and time of day of a timestamp
+pub fn eastern_date_time(timestamp: DateTime<Utc>) -> (chrono::NaiveDate, NaiveTime) {
+    let et = timestamp.with_timezone(&chrono_tz::US::Eastern);
+    (et.date_naive(), et.time())
+}
+
+/// Benzinga earnings date and optional report time, both US/Eastern. Without a
+/// time the report is placed at the start of the Eastern day.
+fn benzinga_report_time(date: &str, time: Option<&str>) -> Option<DateTime<Utc>> {
+    use chrono::TimeZone;
+
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let time = time
+        .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M:%S").ok())
+        .unwrap_or_default();
+    chrono_tz::US::Eastern
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|et| et.with_timezone(&Utc))
+}
+
 // Options types
 #[derive(Debug, Deserialize)]
 struct OptionsSnapshotResponse {
@@ -1224,6 +1720,22 @@ pub struct OptionsContractSnapshot {
     pub day: Option<OptionsDay>,
 }
 
+impl OptionsContractSnapshot {
+    /// Session volume traded in this contract, when reported
+    pub fn volume(&self) -> Option<i64> {
+        self.day.as_ref().and_then(|d| d.volume)
+    }
+
+    /// Today's volume as a multiple of open interest. Volume far above OI means
+    /// new positions are being opened rather than existing ones traded.
+    pub fn volume_oi_ratio(&self) -> Option<f64> {
+        match (self.volume(), self.open_interest) {
+            (Some(volume), Some(oi)) if oi > 0 => Some(volume as f64 / oi as f64),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptionsDetails {
     pub contract_type: Option<String>,
@@ -1301,6 +1813,8 @@ pub struct SnapshotTicker {
     pub day: Option<SnapshotDay>,
     #[serde(rename = "lastTrade")]
     pub last_trade: Option<SnapshotLastTrade>,
+    #[serde(rename = "lastQuote", default)]
+    pub last_quote: Option<SnapshotLastQuote>,
     #[serde(rename = "prevDay")]
     pub prev_day: Option<SnapshotDay>,
     #[serde(rename = "todaysChange")]
@@ -1325,6 +1839,20 @@ pub struct SnapshotLastTrade {
     pub t: Option<i64>,
 }
 
+/// Latest NBBO quote; Polygon uses upper-case keys for the ask side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotLastQuote {
+    #[serde(rename = "p")]
+    pub bid: Option<f64>,
+    #[serde(rename = "s")]
+    pub bid_size: Option<i64>,
+    #[serde(rename = "P")]
+    pub ask: Option<f64>,
+    #[serde(rename = "S")]
+    pub ask_size: Option<i64>,
+    pub t: Option<i64>,
+}
+
 // Technical indicator types
 #[derive(Debug, Deserialize)]
 struct IndicatorResponse {
@@ -1359,3 +1887,217 @@ pub struct MacdValue {
     pub signal: Option<f64>,
     pub histogram: Option<f64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(json: &str) -> AggregateResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_aggregate_pages_follow_next_url() {
+        let first = page(
+            r#"{
+                "results": [
+                    {"t": 1704205920000, "o": 1.4, "h": 1.7, "l": 1.3, "c": 1.5, "v": 90.0, "vw": 1.45},
+                    {"t": 1704205860000, "o": 1.2, "h": 1.6, "l": 1.1, "c": 1.4, "v": 120.0}
+                ],
+                "next_url": "https://api.polygon.io/v2/aggs/ticker/AAPL/range/1/minute/cursor=abc"
+            }"#,
+        );
+        let last = page(
+            r#"{"results": [{"t": 1704205800000, "o": 1.0, "h": 1.5, "l": 0.9, "c": 1.2, "v": 100.0}]}"#,
+        );
+
+        let mut bars = Vec::new();
+        let next = push_aggregate_page(&mut bars, first, MAX_AGGREGATE_BARS);
+        assert_eq!(
+            next.as_deref(),
+            Some("https://api.polygon.io/v2/aggs/ticker/AAPL/range/1/minute/cursor=abc")
+        );
+        assert_eq!(
+            push_aggregate_page(&mut bars, last, MAX_AGGREGATE_BARS),
+            None
+        );
+        bars.reverse();
+
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[2].close, 1.5);
+        assert_eq!(bars[2].vwap, Some(1.45));
+        assert!(bars.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn test_apply_splits_back_adjusts_pre_split_bars() {
+        let bar = |day: u32, close: f64, volume: f64| Bar {
+            timestamp: chrono::NaiveDate::from_ymd_opt(2024, 6, day)
+                .unwrap()
+                .and_hms_opt(20, 0, 0)
+                .unwrap()
+                .and_utc(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            vwap: Some(close),
+        };
+        let mut bars = vec![
+            bar(6, 1200.0, 1_000.0),
+            bar(7, 1210.0, 1_000.0),
+            bar(10, 121.0, 10_000.0),
+        ];
+        let splits = [SplitInfo {
+            execution_date: "2024-06-10".to_string(),
+            split_from: 1.0,
+            split_to: 10.0,
+        }];
+
+        apply_splits(&mut bars, &splits);
+
+        assert!((bars[0].close - 120.0).abs() < 1e-9);
+        assert!((bars[1].vwap.unwrap() - 121.0).abs() < 1e-9);
+        assert!((bars[1].volume - 10_000.0).abs() < 1e-9);
+        // Bars on or after the execution date are already post-split
+        assert!((bars[2].close - 121.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_pages_stop_at_bar_cap() {
+        let first = page(
+            r#"{
+                "results": [
+                    {"t": 1704205980000, "o": 1.5, "h": 1.8, "l": 1.4, "c": 1.6, "v": 80.0},
+                    {"t": 1704205920000, "o": 1.4, "h": 1.7, "l": 1.3, "c": 1.5, "v": 90.0}
+                ],
+                "next_url": "https://api.polygon.io/next"
+            }"#,
+        );
+        let second = page(
+            r#"{
+                "results": [
+                    {"t": 1704205860000, "o": 1.2, "h": 1.6, "l": 1.1, "c": 1.4, "v": 120.0},
+                    {"t": 1704205800000, "o": 1.0, "h": 1.5, "l": 0.9, "c": 1.2, "v": 100.0}
+                ],
+                "next_url": "https://api.polygon.io/next2"
+            }"#,
+        );
+
+        let mut bars = Vec::new();
+        assert!(push_aggregate_page(&mut bars, first, 3).is_some());
+        assert_eq!(push_aggregate_page(&mut bars, second, 3), None);
+        bars.reverse();
+
+        // The oldest bar is dropped, the newest kept
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[0].close, 1.4);
+        assert_eq!(bars[2].close, 1.6);
+        assert_eq!(bars[2].timestamp.timestamp_millis(), 1704205980000);
+    }
+
+    #[test]
+    fn test_market_session_tracks_eastern_dst() {
+        use chrono::TimeZone;
+
+        let at = |y, m, d, h, min| Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap();
+        // Winter (EST, UTC-5): 13:00 UTC is 8:00 ET
+        assert_eq!(
+            market_session(at(2024, 1, 10, 13, 0)),
+            MarketSession::PreMarket
+        );
+        assert_eq!(
+            market_session(at(2024, 1, 10, 14, 30)),
+            MarketSession::Regular
+        );
+        // Summer (EDT, UTC-4): 13:30 UTC is already the open
+        assert_eq!(
+            market_session(at(2024, 7, 10, 13, 30)),
+            MarketSession::Regular
+        );
+        assert_eq!(
+            market_session(at(2024, 7, 10, 20, 0)),
+            MarketSession::AfterHours
+        );
+        assert_eq!(market_session(at(2024, 7, 11, 1, 0)), MarketSession::Closed);
+        // Saturday
+        assert_eq!(
+            market_session(at(2024, 7, 13, 15, 0)),
+            MarketSession::Closed
+        );
+    }
+
+    #[test]
+    fn test_benzinga_report_time_is_eastern() {
+        use chrono::TimeZone;
+
+        // Pre-market report in summer (EDT, UTC-4)
+        assert_eq!(
+            benzinga_report_time("2024-07-25", Some("08:30:00")),
+            Some(Utc.with_ymd_and_hms(2024, 7, 25, 12, 30, 0).unwrap())
+        );
+        // After-close report in winter (EST, UTC-5) lands on the next UTC day
+        assert_eq!(
+            benzinga_report_time("2024-01-25", Some("20:00:00")),
+            Some(Utc.with_ymd_and_hms(2024, 1, 26, 1, 0, 0).unwrap())
+        );
+        // No report time: Eastern midnight
+        assert_eq!(
+            benzinga_report_time("2024-01-25", None),
+            Some(Utc.with_ymd_and_hms(2024, 1, 25, 5, 0, 0).unwrap())
+        );
+        assert_eq!(benzinga_report_time("not-a-date", None), None);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_concurrency_backs_off_and_recovers() {
+        let concurrency = AdaptiveConcurrency::new(4);
+        let busy: Vec<_> = futures_util::future::join_all((0..3).map(|_| concurrency.acquire()))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        // Cap 4 -> 2: the idle permit goes now, one busy permit on release
+        concurrency.on_rate_limited();
+        assert_eq!(concurrency.state().limit, 2);
+        assert_eq!(concurrency.semaphore.available_permits(), 0);
+        drop(busy);
+        assert_eq!(concurrency.semaphore.available_permits(), 2);
+
+        // The rest of the same burst does not shrink it again
+        concurrency.on_rate_limited();
+        assert_eq!(concurrency.state().limit, 2);
+
+        concurrency.on_success();
+        concurrency.on_success();
+        assert_eq!(concurrency.state().limit, 3);
+        assert_eq!(concurrency.semaphore.available_permits(), 3);
+    }
+
+    #[test]
+    fn test_snapshot_url_routes_by_asset_class() {
+        assert_eq!(
+            snapshot_url("AAPL"),
+            format!(
+                "{}/v2/snapshot/locale/us/markets/stocks/tickers/AAPL",
+                BASE_URL
+            )
+        );
+        assert_eq!(
+            snapshot_url("X:BTCUSD"),
+            format!(
+                "{}/v2/snapshot/locale/global/markets/crypto/tickers/X:BTCUSD",
+                BASE_URL
+            )
+        );
+        assert_eq!(
+            snapshot_url("C:EURUSD"),
+            format!(
+                "{}/v2/snapshot/locale/global/markets/forex/tickers/C:EURUSD",
+                BASE_URL
+            )
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::PolygonClient;
+use analysis_core::{AnalysisError, NewsArticle, NewsSource};
+use async_trait::async_trait;
+
+/// Days of company news requested from Finnhub by default
+const DEFAULT_FINNHUB_DAYS: u32 = 7;
+
+/// Polygon's ticker news endpoint as a `NewsSource`
+pub struct PolygonNewsSource {
+    client: PolygonClient,
+}
+
+impl PolygonNewsSource {
+    pub fn new(client: PolygonClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl NewsSource for PolygonNewsSource {
+    fn name(&self) -> &str {
+        "polygon"
+    }
+
+    async fn fetch(&self, symbol: &str, limit: u32) -> Result<Vec<NewsArticle>, AnalysisError> {
+        self.client.get_news(Some(symbol), limit).await
+    }
+}
+
+/// Finnhub company news as a `NewsSource`; a no-op unless FINNHUB_API_KEY is set
+pub struct FinnhubNewsSource {
+    client: PolygonClient,
+    days_back: u32,
+}
+
+impl FinnhubNewsSource {
+    pub fn new(client: PolygonClient) -> Self {
+        Self {
+            client,
+            days_back: DEFAULT_FINNHUB_DAYS,
+        }
+    }
+
+    /// How many days of company news to request
+    pub fn with_days_back(mut self, days_back: u32) -> Self {
+        self.days_back = days_back;
+        self
+    }
+}
+
+#[async_trait]
+impl NewsSource for FinnhubNewsSource {
+    fn name(&self) -> &str {
+        "finnhub"
+    }
+
+    async fn fetch(&self, symbol: &str, limit: u32) -> Result<Vec<NewsArticle>, AnalysisError> {
+        let mut articles = self.client.get_finnhub_news(symbol, self.days_back).await?;
+        articles.truncate(limit as usize);
+        Ok(articles)
+    }
+}
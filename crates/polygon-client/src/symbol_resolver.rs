@@ -0,0 +1,278 @@
+use crate::{PolygonClient, TickerSearchResult};
+
+/// Search results pulled from Polygon when no alias matches
+const RESOLVER_SEARCH_LIMIT: usize = 20;
+
+/// Exchanges preferred when a name matches several listings (NYSE, Nasdaq,
+/// NYSE Arca, NYSE American, Cboe BZX)
+const PRIMARY_EXCHANGES: &[&str] = &["XNYS", "XNAS", "ARCX", "XASE", "BATS"];
+
+/// Words that don't distinguish one company name from another
+const NAME_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "ltd",
+    "limited",
+    "plc",
+    "the",
+    "com",
+];
+
+/// Common names of heavily-traded companies: (normalized alias, ticker, name, exchange)
+const ALIASES: &[(&str, &str, &str, &str)] = &[
+    ("apple", "AAPL", "Apple Inc.", "XNAS"),
+    ("microsoft", "MSFT", "Microsoft Corp", "XNAS"),
+    ("alphabet", "GOOGL", "Alphabet Inc. Class A", "XNAS"),
+    ("alphabet class a", "GOOGL", "Alphabet Inc. Class A", "XNAS"),
+    ("alphabet class c", "GOOG", "Alphabet Inc. Class C", "XNAS"),
+    ("google", "GOOGL", "Alphabet Inc. Class A", "XNAS"),
+    ("amazon", "AMZN", "Amazon.com Inc.", "XNAS"),
+    ("nvidia", "NVDA", "Nvidia Corp", "XNAS"),
+    ("meta", "META", "Meta Platforms, Inc. Class A", "XNAS"),
+    (
+        "meta platforms",
+        "META",
+        "Meta Platforms, Inc. Class A",
+        "XNAS",
+    ),
+    ("facebook", "META", "Meta Platforms, Inc. Class A", "XNAS"),
+    ("tesla", "TSLA", "Tesla, Inc.", "XNAS"),
+    (
+        "berkshire hathaway",
+        "BRK.B",
+        "Berkshire Hathaway Inc. Class B",
+        "XNYS",
+    ),
+    (
+        "berkshire hathaway class b",
+        "BRK.B",
+        "Berkshire Hathaway Inc. Class B",
+        "XNYS",
+    ),
+    (
+        "berkshire hathaway class a",
+        "BRK.A",
+        "Berkshire Hathaway Inc. Class A",
+        "XNYS",
+    ),
+    ("jpmorgan", "JPM", "JPMorgan Chase & Co.", "XNYS"),
+    ("jpmorgan chase", "JPM", "JPMorgan Chase & Co.", "XNYS"),
+    ("jp morgan", "JPM", "JPMorgan Chase & Co.", "XNYS"),
+    ("bank of america", "BAC", "Bank of America Corp", "XNYS"),
+    ("visa", "V", "Visa Inc.", "XNYS"),
+    ("mastercard", "MA", "Mastercard Incorporated", "XNYS"),
+    ("walmart", "WMT", "Walmart Inc.", "XNAS"),
+    ("exxon", "XOM", "Exxon Mobil Corp", "XNYS"),
+    ("exxon mobil", "XOM", "Exxon Mobil Corp", "XNYS"),
+    ("johnson johnson", "JNJ", "Johnson & Johnson", "XNYS"),
+    ("procter gamble", "PG", "Procter & Gamble Company", "XNYS"),
+    ("coca cola", "KO", "Coca-Cola Company", "XNYS"),
+    ("pepsico", "PEP", "PepsiCo, Inc.", "XNAS"),
+    ("pepsi", "PEP", "PepsiCo, Inc.", "XNAS"),
+    ("netflix", "NFLX", "Netflix, Inc.", "XNAS"),
+    ("intel", "INTC", "Intel Corp", "XNAS"),
+    ("amd", "AMD", "Advanced Micro Devices", "XNAS"),
+    (
+        "advanced micro devices",
+        "AMD",
+        "Advanced Micro Devices",
+        "XNAS",
+    ),
+    ("broadcom", "AVGO", "Broadcom Inc.", "XNAS"),
+    ("oracle", "ORCL", "Oracle Corp", "XNYS"),
+    ("salesforce", "CRM", "Salesforce, Inc.", "XNYS"),
+    ("adobe", "ADBE", "Adobe Inc.", "XNAS"),
+    ("cisco", "CSCO", "Cisco Systems, Inc.", "XNAS"),
+    ("disney", "DIS", "Walt Disney Company", "XNYS"),
+    ("walt disney", "DIS", "Walt Disney Company", "XNYS"),
+    ("home depot", "HD", "Home Depot, Inc.", "XNYS"),
+    ("costco", "COST", "Costco Wholesale Corp", "XNAS"),
+    ("mcdonalds", "MCD", "McDonald's Corp", "XNYS"),
+    ("nike", "NKE", "Nike, Inc. Class B", "XNYS"),
+    ("boeing", "BA", "Boeing Company", "XNYS"),
+];
+
+impl PolygonClient {
+    /// Best-matching ticker for free-text company name or symbol input, with a
+    /// 0–1 confidence.
+    ///
+    /// Popular names resolve from a local alias table without an API call;
+    /// anything else goes through `search_tickers`, ranked by exact and prefix
+    /// matches on ticker and name, then by primary-exchange listing.
+    pub async fn resolve_symbol(&self, query: &str) -> Option<(TickerSearchResult, f64)> {
+        let normalized = normalize_name(query);
+        if normalized.is_empty() {
+            return None;
+        }
+
+        if let Some(&(_, ticker, name, exchange)) = ALIASES.iter().find(|(alias, ticker, ..)| {
+            *alias == normalized || ticker.eq_ignore_ascii_case(query.trim())
+        }) {
+            return Some((
+                TickerSearchResult {
+                    ticker: ticker.to_string(),
+                    name: name.to_string(),
+                    market: "stocks".to_string(),
+                    locale: "us".to_string(),
+                    primary_exchange: exchange.to_string(),
+                    r#type: "CS".to_string(),
+                    currency_name: "usd".to_string(),
+                },
+                1.0,
+            ));
+        }
+
+        let results = match self
+            .search_tickers(query.trim(), RESOLVER_SEARCH_LIMIT)
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::debug!("Symbol search for {:?} failed: {}", query, e);
+                return None;
+            }
+        };
+
+        best_match(query.trim(), &normalized, results)
+    }
+}
+
+/// Highest-scoring search result, `None` when there are no results
+fn best_match(
+    query: &str,
+    normalized_query: &str,
+    results: Vec<TickerSearchResult>,
+) -> Option<(TickerSearchResult, f64)> {
+    results
+        .into_iter()
+        .map(|r| {
+            let score = match_score(query, normalized_query, &r);
+            (r, score)
+        })
+        .max_by(|(a, sa), (b, sb)| {
+            sa.partial_cmp(sb)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                // Prefer the shorter ticker (common stock over preferreds/warrants)
+                .then_with(|| b.ticker.len().cmp(&a.ticker.len()))
+        })
+}
+
+/// Lowercase, drop punctuation and corporate suffixes ("Apple, Inc." → "apple")
+fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'' && *c != '’')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .filter(|w| !NAME_SUFFIXES.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How well a search result matches the query, 0–1
+fn match_score(query: &str, normalized_query: &str, result: &TickerSearchResult) -> f64 {
+    let name = normalize_name(&result.name);
+    let base: f64 = if result.ticker.eq_ignore_ascii_case(query) {
+        0.95
+    } else if name == normalized_query {
+        0.9
+    } else if name.starts_with(normalized_query) {
+        0.75
+    } else if result
+        .ticker
+        .to_lowercase()
+        .starts_with(&query.to_lowercase())
+    {
+        0.6
+    } else if name.contains(normalized_query) {
+        0.5
+    } else {
+        0.3
+    };
+    let exchange_adj = if PRIMARY_EXCHANGES.contains(&result.primary_exchange.as_str()) {
+        0.05
+    } else {
+        -0.1
+    };
+    (base + exchange_adj).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(ticker: &str, name: &str, exchange: &str) -> TickerSearchResult {
+        TickerSearchResult {
+            ticker: ticker.to_string(),
+            name: name.to_string(),
+            market: "stocks".to_string(),
+            locale: "us".to_string(),
+            primary_exchange: exchange.to_string(),
+            r#type: "CS".to_string(),
+            currency_name: "usd".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_name_strips_suffixes() {
+        assert_eq!(normalize_name("Apple Inc."), "apple");
+        assert_eq!(normalize_name("The Coca-Cola Company"), "coca cola");
+        assert_eq!(normalize_name("McDonald's Corp"), "mcdonalds");
+        assert_eq!(normalize_name("  JPMorgan Chase & Co. "), "jpmorgan chase");
+        assert_eq!(normalize_name("Inc."), "");
+    }
+
+    #[test]
+    fn test_exact_ticker_beats_fuzzy_name() {
+        let results = vec![
+            listing("CATX", "Cat Co", "XNAS"),
+            listing("CAT", "Caterpillar Inc.", "XNYS"),
+        ];
+        let (best, score) = best_match("cat", &normalize_name("cat"), results).unwrap();
+        assert_eq!(best.ticker, "CAT");
+        assert_eq!(score, 1.0);
+
+        // Off a primary exchange even an exact ticker match loses confidence
+        let otc = listing("CAT", "Caterpillar Inc.", "OTC");
+        assert!(match_score("cat", "cat", &otc) < 1.0);
+    }
+
+    #[test]
+    fn test_share_class_tie_prefers_shorter_ticker() {
+        let class_a = listing("FOXA", "Fox Corporation Class A", "XNAS");
+        let class_b = listing("FOX", "Fox Corporation Class B", "XNAS");
+        let query = "Fox Corporation";
+        let normalized = normalize_name(query);
+        assert_eq!(
+            match_score(query, &normalized, &class_a),
+            match_score(query, &normalized, &class_b)
+        );
+
+        for results in [
+            vec![class_a.clone(), class_b.clone()],
+            vec![class_b.clone(), class_a.clone()],
+        ] {
+            let (best, _) = best_match(query, &normalized, results).unwrap();
+            assert_eq!(best.ticker, "FOX");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_symbol_without_match() {
+        assert!(best_match("zzqx", "zzqx", Vec::new()).is_none());
+
+        // Aliases and blank or suffix-only input never reach the search API
+        let client = PolygonClient::new("test".to_string());
+        let (apple, confidence) = client.resolve_symbol("Apple, Inc.").await.unwrap();
+        assert_eq!((apple.ticker.as_str(), confidence), ("AAPL", 1.0));
+        assert!(client.resolve_symbol("  ").await.is_none());
+        assert!(client.resolve_symbol("Inc.").await.is_none());
+    }
+}
@@ -0,0 +1,56 @@
+//! Rolling-window baselines over 5000 daily bars: every window on one thread
+//! versus spread across the rayon pool.
+//!
+//! Run with `cargo bench -p quant-analysis --bench rolling_series`.
+
+use analysis_core::Bar;
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use quant_analysis::QuantAnalysisEngine;
+
+/// Deterministic random walk (LCG noise with a slight upward drift)
+fn synthetic_bars(n: usize) -> Vec<Bar> {
+    let start = Utc.with_ymd_and_hms(2000, 1, 3, 0, 0, 0).unwrap();
+    let mut seed: u64 = 42;
+    let mut price = 100.0;
+    (0..n)
+        .map(|i| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let noise = ((seed >> 33) as f64 / (1u64 << 31) as f64) - 0.5;
+            price *= 1.0 + noise * 0.04 + 0.0003;
+            Bar {
+                timestamp: start + Duration::days(i as i64),
+                open: price,
+                high: price * 1.01,
+                low: price * 0.99,
+                close: price,
+                volume: 1_000_000.0,
+                vwap: None,
+            }
+        })
+        .collect()
+}
+
+fn bench_rolling_series(c: &mut Criterion) {
+    let engine = QuantAnalysisEngine::new();
+    let bars = synthetic_bars(5_000);
+    let single = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("compute_rolling_series_5000_bars");
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| single.install(|| engine.rolling_series_windows(black_box(&bars), 0.045)))
+    });
+    group.bench_function("rayon", |b| {
+        b.iter(|| engine.rolling_series_windows(black_box(&bars), 0.045))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_rolling_series);
+criterion_main!(benches);
@@ -1,25 +1,626 @@
-use analysis_core::{adaptive, AnalysisError, AnalysisResult, Bar, QuantAnalyzer, SignalStrength};
+use analysis_core::{
+    adaptive, adaptive::RollingStats, AnalysisError, AnalysisResult, AssetClass, Bar, Clock,
+    QuantAnalyzer, SignalStrength, SystemClock, Timeframe,
+};
 use async_trait::async_trait;
-use chrono::{Datelike, Utc};
+use chrono::Datelike;
 use rayon::prelude::*;
 use serde_json::json;
+use statrs::distribution::{ContinuousCDF, Normal};
 use statrs::statistics::Statistics;
+use std::sync::Arc;
+
+/// How Value at Risk is derived from a return series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VarMethod {
+    /// Empirical quantile of observed returns
+    #[default]
+    Historical,
+    /// Normal-distribution quantile from mean and standard deviation
+    Gaussian,
+    /// Normal quantile adjusted for skewness and excess kurtosis
+    CornishFisher,
+}
+
+impl VarMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VarMethod::Historical => "historical",
+            VarMethod::Gaussian => "gaussian",
+            VarMethod::CornishFisher => "cornish_fisher",
+        }
+    }
+}
+
+/// How abnormal single-bar returns (jumps) are identified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JumpDetection {
+    /// Return more than the threshold away from the full-sample mean, in
+    /// full-sample standard deviations
+    #[default]
+    ZScore,
+    /// Return scaled by a trailing bipower-variation volatility, which jumps
+    /// themselves barely inflate, so volatile regimes aren't mistaken for jumps
+    BipowerVariation,
+}
+
+impl JumpDetection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JumpDetection::ZScore => "z_score",
+            JumpDetection::BipowerVariation => "bipower_variation",
+        }
+    }
+}
+
+pub struct QuantAnalysisEngine {
+    var_method: VarMethod,
+    jump_detection: JumpDetection,
+    /// Standardized return beyond which a bar counts as a jump
+    jump_threshold_sigma: f64,
+    /// Clamp suspected split/data-artifact returns instead of only flagging them
+    winsorize_splits: bool,
+    /// Decay factor of the EWMA volatility estimator
+    ewma_lambda: f64,
+    /// Weight pulling the OLS beta toward the market beta of 1.0
+    beta_shrinkage: f64,
+    /// Time source for result timestamps (wall clock unless overridden)
+    clock: Arc<dyn Clock>,
+}
+
+/// Robust z-score above which a single-bar return is treated as a possible artifact
+const SPLIT_SIGMA_THRESHOLD: f64 = 6.0;
+
+/// Window length for the short (30-bar) rolling series
+const SHORT_WINDOW: usize = 30;
+/// Window length for the long (60-bar) rolling series
+const LONG_WINDOW: usize = 60;
+
+/// Window length for the rolling beta series
+const ROLLING_BETA_WINDOW: usize = 60;
+/// Most recent rolling beta values reported in the metrics
+const ROLLING_BETA_OUTPUT_LEN: usize = 60;
+
+/// RiskMetrics decay factor for daily EWMA volatility
+pub const DEFAULT_EWMA_LAMBDA: f64 = 0.94;
+/// EWMA/realized volatility ratio above which volatility is building
+const EWMA_VOL_BUILDING_RATIO: f64 = 1.25;
+
+/// Blume (1971) adjustment: adjusted beta = 0.67 * raw + 0.33 * 1.0
+pub const BLUME_BETA_SHRINKAGE: f64 = 0.33;
+
+/// Minimum benchmark up (or down) days for a conditional beta
+const MIN_CONDITIONAL_BETA_DAYS: usize = 20;
+/// Downside minus upside beta that flags asymmetric downside risk
+const ASYMMETRIC_BETA_GAP: f64 = 0.3;
+
+/// Rolling Sharpe decline over the series, in its own standard deviations,
+/// that counts as deteriorating
+const SHARPE_DETERIORATION_SIGMAS: f64 = 1.0;
+/// Sharpe ratio still considered acceptable when checking for deterioration
+const ACCEPTABLE_SHARPE: f64 = 0.5;
+
+/// Timestamp-aligned benchmark returns needed for a trustworthy beta
+const MIN_BETA_OVERLAP: usize = 60;
+/// Confidence multiplier when the benchmark overlap is shorter than that
+const SHORT_BETA_OVERLAP_CONFIDENCE_FACTOR: f64 = 0.9;
+
+/// Largest fraction of bars with a zero, negative, or non-finite close that is
+/// dropped before analysis rather than rejected
+const MAX_INVALID_BAR_FRACTION: f64 = 0.05;
+
+/// Default jump threshold, in standard deviations
+const DEFAULT_JUMP_THRESHOLD_SIGMA: f64 = 3.0;
+/// Trailing returns in the local bipower-variation volatility estimate
+const BIPOWER_WINDOW: usize = 20;
+
+/// Default VaR/CVaR tail probability (95% confidence)
+const DEFAULT_VAR_ALPHA: f64 = 0.05;
+
+/// Engle-Granger 5% critical value for two series with a constant (MacKinnon 2010)
+const ENGLE_GRANGER_CRITICAL_5PCT: f64 = -3.34;
+
+/// Lagged differences included in the residual ADF regression
+const COINTEGRATION_ADF_LAGS: usize = 1;
+
+/// Minimum aligned bars for a cointegration test
+const MIN_COINTEGRATION_BARS: usize = 60;
+
+/// Engle-Granger two-step cointegration test result for a pair of price series
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CointegrationResult {
+    /// Units of B per unit of A in the spread `A - hedge_ratio * B - intercept`
+    pub hedge_ratio: f64,
+    pub intercept: f64,
+    /// ADF t-statistic of the residual spread
+    pub adf_statistic: f64,
+    pub critical_value_5pct: f64,
+    pub is_cointegrated: bool,
+    /// Latest spread in standard deviations from its mean
+    pub spread_zscore: f64,
+    /// Aligned bars used in the regression
+    pub sample_size: usize,
+}
+
+/// Depth, timing, and recovery of the worst peak-to-trough decline in a price series
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DrawdownStats {
+    /// Worst decline from a running peak, in percent
+    pub max_drawdown_pct: f64,
+    pub peak_index: usize,
+    pub trough_index: usize,
+    /// First bar after the trough back at or above the peak, if any
+    pub recovery_index: Option<usize>,
+    /// Bars from the peak to recovery, or to the last bar when unrecovered
+    pub duration_bars: usize,
+    /// Decline of the last price from the running peak, in percent
+    pub current_drawdown_pct: f64,
+    /// Whether the series has regained the worst drawdown's peak (true when it never fell)
+    pub recovered: bool,
+}
+
+/// ADF critical values for a regression with a constant and no trend (MacKinnon 2010)
+const ADF_CRITICAL_1PCT: f64 = -3.43;
+const ADF_CRITICAL_5PCT: f64 = -2.86;
+const ADF_CRITICAL_10PCT: f64 = -2.57;
+
+/// Augmented Dickey-Fuller unit-root test outcome
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AdfResult {
+    /// t-statistic on the lagged level; `None` when the regression couldn't run
+    pub statistic: Option<f64>,
+    pub lags: usize,
+    pub critical_1pct: f64,
+    pub critical_5pct: f64,
+    pub critical_10pct: f64,
+    /// Unit root rejected at the 5% level
+    pub is_stationary: bool,
+    /// "stationary", "non_stationary", or "insufficient_data"
+    pub interpretation: &'static str,
+}
+
+/// Augmented Dickey-Fuller test (constant, no trend) with `lags` lagged differences
+pub fn augmented_dickey_fuller(series: &[f64], lags: usize) -> AdfResult {
+    let statistic = adf_t_statistic(series, lags);
+    let is_stationary = statistic.is_some_and(|t| t < ADF_CRITICAL_5PCT);
+    let interpretation = match statistic {
+        None => "insufficient_data",
+        Some(_) if is_stationary => "stationary",
+        Some(_) => "non_stationary",
+    };
+    AdfResult {
+        statistic,
+        lags,
+        critical_1pct: ADF_CRITICAL_1PCT,
+        critical_5pct: ADF_CRITICAL_5PCT,
+        critical_10pct: ADF_CRITICAL_10PCT,
+        is_stationary,
+        interpretation,
+    }
+}
+
+/// ADF t-statistic on the lagged level in
+/// `dy_t = c + gamma * y_{t-1} + sum(delta_i * dy_{t-i}) + e_t`.
+/// Returns `None` when there are too few observations or the design is singular.
+fn adf_t_statistic(series: &[f64], lags: usize) -> Option<f64> {
+    let diffs: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+    let rows = diffs.len().checked_sub(lags)?;
+    let cols = 2 + lags;
+    if rows <= cols + 2 {
+        return None;
+    }
+
+    let mut x_data = Vec::with_capacity(rows * cols);
+    let mut y = Vec::with_capacity(rows);
+    for t in lags..diffs.len() {
+        x_data.push(1.0);
+        x_data.push(series[t]); // level before diffs[t]
+        for i in 1..=lags {
+            x_data.push(diffs[t - i]);
+        }
+        y.push(diffs[t]);
+    }
+
+    let x = nalgebra::DMatrix::from_row_slice(rows, cols, &x_data);
+    let y = nalgebra::DVector::from_column_slice(&y);
+    let xtx_inv = (x.transpose() * &x).try_inverse()?;
+    let coeffs = &xtx_inv * (x.transpose() * &y);
+    let residuals = &y - &x * &coeffs;
+    let sigma2 = residuals.dot(&residuals) / (rows - cols) as f64;
+    let se_gamma = (sigma2 * xtx_inv[(1, 1)]).sqrt();
+    if !se_gamma.is_finite() || se_gamma <= 0.0 {
+        return None;
+    }
+    Some(coeffs[1] / se_gamma)
+}
+
+/// Historical VaR (as a positive percentage) from ascending-sorted returns
+fn var_from_sorted(sorted: &[f64], alpha: f64) -> Option<f64> {
+    let index = (sorted.len() as f64 * alpha) as usize;
+    sorted.get(index).map(|r| r.abs() * 100.0)
+}
+
+/// Parametric VaR (as a positive percentage) from the normal quantile, optionally
+/// with the Cornish-Fisher expansion for skewness and excess kurtosis
+fn parametric_var(returns: &[f64], alpha: f64, cornish_fisher: bool) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.mean();
+    let std_dev = returns.std_dev();
+    if std_dev == 0.0 || std_dev.is_nan() {
+        return (-mean).max(0.0) * 100.0;
+    }
+
+    let z = Normal::new(0.0, 1.0)
+        .map(|n| n.inverse_cdf(alpha))
+        .unwrap_or(-1.645);
+    let z = if cornish_fisher {
+        let n = returns.len() as f64;
+        let skew = returns
+            .iter()
+            .map(|r| ((r - mean) / std_dev).powi(3))
+            .sum::<f64>()
+            / n;
+        let excess_kurt = returns
+            .iter()
+            .map(|r| ((r - mean) / std_dev).powi(4))
+            .sum::<f64>()
+            / n
+            - 3.0;
+        z + (z.powi(2) - 1.0) * skew / 6.0 + (z.powi(3) - 3.0 * z) * excess_kurt / 24.0
+            - (2.0 * z.powi(3) - 5.0 * z) * skew.powi(2) / 36.0
+    } else {
+        z
+    };
+
+    (-(mean + z * std_dev)).max(0.0) * 100.0
+}
+
+/// Historical CVaR (mean of the worst `alpha` tail, as a positive percentage)
+/// from ascending-sorted returns
+fn cvar_from_sorted(sorted: &[f64], alpha: f64) -> Option<f64> {
+    let cutoff = (sorted.len() as f64 * alpha).ceil() as usize;
+    if cutoff == 0 || cutoff > sorted.len() {
+        return None;
+    }
+    let tail = &sorted[..cutoff];
+    Some((tail.iter().sum::<f64>() / tail.len() as f64).abs() * 100.0)
+}
+
+/// Pair each stock return (`bars[i]` → `bars[i + 1]`) with the benchmark return
+/// over the same two timestamps, dropping returns the benchmark has no bars for.
+/// `stock_returns[i]` must be the return ending at `bars[i + 1]`.
+fn align_benchmark_returns(
+    bars: &[Bar],
+    stock_returns: &[f64],
+    benchmark: &[Bar],
+) -> (Vec<f64>, Vec<f64>) {
+    let closes: std::collections::HashMap<_, f64> =
+        benchmark.iter().map(|b| (b.timestamp, b.close)).collect();
+    stock_returns
+        .iter()
+        .zip(bars.windows(2))
+        .filter_map(|(&r, pair)| {
+            let c0 = *closes.get(&pair[0].timestamp)?;
+            let c1 = *closes.get(&pair[1].timestamp)?;
+            (c0 > 0.0).then(|| (r, c1 / c0 - 1.0))
+        })
+        .unzip()
+}
+
+/// OLS slope of `values` against their index (change per step)
+fn trend_slope(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = values.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - x_mean;
+        cov += dx * (y - y_mean);
+        var += dx * dx;
+    }
+    Some(cov / var)
+}
+
+/// Reconcile the Hurst regime signal with the momentum tier signal.
+///
+/// When the two point opposite ways (e.g. a mean-reverting regime under strong
+/// positive momentum) both are replaced by one "Regime/Momentum Conflict" signal
+/// in the heavier side's direction at half its weight, rounded up. Momentum wins
+/// ties as the more recent read. The disagreement is then explicit and
+/// discounted instead of silently netting out. Returns whether they conflicted.
+fn reconcile_regime_momentum(
+    signals: &mut Vec<(&str, i32, bool)>,
+    momentum_idx: Option<usize>,
+    hurst_idx: Option<usize>,
+) -> bool {
+    let (Some(m), Some(h)) = (momentum_idx, hurst_idx) else {
+        return false;
+    };
+    let (momentum, regime) = (signals[m], signals[h]);
+    if momentum.2 == regime.2 {
+        return false;
+    }
+    let dominant = if regime.1 > momentum.1 {
+        regime
+    } else {
+        momentum
+    };
+    signals.remove(h.max(m));
+    signals[h.min(m)] = (
+        "Regime/Momentum Conflict",
+        ((dominant.1 + 1) / 2).max(1),
+        dominant.2,
+    );
+    true
+}
+
+/// Factor bucket a quant signal contributes to in `factor_attribution`
+fn factor_bucket(signal_name: &str) -> &'static str {
+    match signal_name {
+        "Positive Momentum"
+        | "Negative Momentum"
+        | "Strong Momentum — Extended"
+        | "Extreme Momentum — Reversion Risk"
+        | "Positive Momentum Factor"
+        | "Negative Momentum Factor"
+        | "Trending Market (Hurst)"
+        | "Regime/Momentum Conflict"
+        | "Weak Mean Reversion (Trending)"
+        | "Positive Serial Correlation"
+        | "Consistent Outperformance (IR)"
+        | "Consistent Underperformance (IR)" => "momentum",
+        "Extreme Sell-off — Bounce Risk"
+        | "Heavy Selling — Oversold"
+        | "Mean-Reverting Market (Hurst)"
+        | "Fast Mean Reversion"
+        | "Negative Serial Correlation" => "mean_reversion",
+        "Extreme VaR Risk"
+        | "Elevated VaR Risk"
+        | "Low VaR Risk"
+        | "Extreme Tail Risk (CVaR)"
+        | "Elevated Tail Risk (CVaR)"
+        | "Low Tail Risk (CVaR)"
+        | "Favorable Tail Risk Profile (Rachev)"
+        | "Unfavorable Tail Risk Profile (Rachev)"
+        | "Fat Tails (Extreme Moves Likely)"
+        | "Negative Skew (Crash Risk)"
+        | "Asymmetric Downside Risk"
+        | "Positive Skew (Lottery-Ticket)"
+        | "Frequent Jumps (High Event Risk)" => "tail_risk",
+        "Positive Seasonal Tendency" | "Negative Seasonal Tendency" => "seasonality",
+        "Good Risk-Adjusted Return"
+        | "Poor Risk-Adjusted Return"
+        | "Risk-Adjusted Performance Deteriorating"
+        | "Strong Downside Protection"
+        | "Poor Downside Profile"
+        | "High Volatility"
+        | "Low Volatility"
+        | "High Volatility Factor"
+        | "Low Volatility Factor"
+        | "Volatility Expected to Increase"
+        | "Volatility Expected to Decrease"
+        | "Volatility Building"
+        | "High Drawdown"
+        | "Low Drawdown"
+        | "High Pain Profile"
+        | "Low Pain Profile"
+        | "High Beta (Aggressive)"
+        | "Low Beta (Defensive)"
+        | "Correlation Regime Shift"
+        | "Superior Omega Ratio"
+        | "Poor Omega Ratio"
+        | "Strong Treynor Ratio"
+        | "Weak Treynor Ratio" => "volatility_risk",
+        _ => "other",
+    }
+}
+
+/// Break the normalized quant score into per-bucket net contributions.
+/// Contributions are on the same -100..100 scale and sum to the overall score.
+fn factor_attribution(signals: &[(&str, i32, bool)], total_weight: i32) -> serde_json::Value {
+    let mut buckets = serde_json::Map::new();
+    for bucket in [
+        "momentum",
+        "volatility_risk",
+        "mean_reversion",
+        "tail_risk",
+        "seasonality",
+        "other",
+    ] {
+        let members: Vec<&(&str, i32, bool)> = signals
+            .iter()
+            .filter(|(name, _, _)| factor_bucket(name) == bucket)
+            .collect();
+        let net: i32 = members
+            .iter()
+            .map(|(_, weight, bullish)| if *bullish { *weight } else { -weight })
+            .sum();
+        let contribution = if total_weight > 0 {
+            net as f64 / total_weight as f64 * 100.0
+        } else {
+            0.0
+        };
+        buckets.insert(
+            bucket.to_string(),
+            json!({
+                "contribution": contribution,
+                "signal_count": members.len(),
+                "signals": members.iter().map(|(name, _, _)| *name).collect::<Vec<&str>>(),
+            }),
+        );
+    }
+    serde_json::Value::Object(buckets)
+}
 
-pub struct QuantAnalysisEngine;
+/// Per-window statistics over the short rolling window
+struct ShortWindowStats {
+    vol: f64,
+    var: Option<f64>,
+    cvar: Option<f64>,
+    kelly: f64,
+    garch_ratio: Option<f64>,
+}
+
+/// Per-window statistics over the long rolling window
+struct LongWindowStats {
+    sharpe: Option<f64>,
+    omega: f64,
+    rachev: f64,
+}
+
+/// Rolling-window distributions used as adaptive baselines
+struct RollingSeries {
+    sharpes: RollingStats,
+    vols: RollingStats,
+    drawdowns: RollingStats,
+    ulcers: RollingStats,
+    vars: RollingStats,
+    cvars: RollingStats,
+    kellys: RollingStats,
+    garch_ratios: RollingStats,
+    omegas: RollingStats,
+    rachevs: RollingStats,
+}
 
 impl QuantAnalysisEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            var_method: VarMethod::default(),
+            jump_detection: JumpDetection::default(),
+            jump_threshold_sigma: DEFAULT_JUMP_THRESHOLD_SIGMA,
+            winsorize_splits: true,
+            ewma_lambda: DEFAULT_EWMA_LAMBDA,
+            beta_shrinkage: BLUME_BETA_SHRINKAGE,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Shrinkage weight toward 1.0 for `adjusted_beta` (defaults to Blume's 0.33;
+    /// 0.0 reports the raw OLS beta)
+    pub fn with_beta_shrinkage(mut self, weight: f64) -> Self {
+        self.beta_shrinkage = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Inject the time source, e.g. a `FixedClock` for reproducible output
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Decay factor for `ewma_volatility` (defaults to RiskMetrics' 0.94)
+    pub fn with_ewma_lambda(mut self, lambda: f64) -> Self {
+        self.ewma_lambda = lambda.clamp(0.0, 0.999);
+        self
+    }
+
+    /// Whether suspected split artifacts are winsorized (default) or only reported
+    pub fn with_split_winsorizing(mut self, enabled: bool) -> Self {
+        self.winsorize_splits = enabled;
+        self
+    }
+
+    /// Choose how jumps are detected (defaults to `JumpDetection::ZScore`)
+    pub fn with_jump_detection(mut self, method: JumpDetection) -> Self {
+        self.jump_detection = method;
+        self
+    }
+
+    /// Standardized return beyond which a bar is a jump (defaults to 3 sigma)
+    pub fn with_jump_threshold(mut self, sigma: f64) -> Self {
+        self.jump_threshold_sigma = sigma;
+        self
+    }
+
+    /// Choose how VaR is computed (defaults to `VarMethod::Historical`)
+    pub fn with_var_method(mut self, method: VarMethod) -> Self {
+        self.var_method = method;
+        self
     }
 
     /// Calculate returns from prices
+    /// A window touching a zero, negative, or non-finite price yields a 0.0 return
+    /// (keeping one return per bar pair) instead of inf/NaN.
     fn calculate_returns(&self, prices: &[f64]) -> Vec<f64> {
-        prices.windows(2).map(|w| (w[1] - w[0]) / w[0]).collect()
+        prices
+            .windows(2)
+            .map(|w| {
+                if w[0] > 0.0 && w[1] > 0.0 && w[0].is_finite() && w[1].is_finite() {
+                    (w[1] - w[0]) / w[0]
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    /// Flag single-bar returns beyond `SPLIT_SIGMA_THRESHOLD` robust sigmas that are
+    /// reversed on the next bar — the signature of an unadjusted split or bad print.
+    /// Flagged pairs are winsorized to the threshold band when enabled.
+    /// Returns (sanitized returns, suspected split days).
+    fn sanitize_returns(&self, returns: &[f64]) -> (Vec<f64>, usize) {
+        let mut sanitized = returns.to_vec();
+        if returns.len() < 20 {
+            return (sanitized, 0);
+        }
+
+        // Median/MAD so the spike itself doesn't inflate the scale estimate
+        let median = adaptive::percentile_value(returns, 50.0);
+        let deviations: Vec<f64> = returns.iter().map(|r| (r - median).abs()).collect();
+        let sigma = adaptive::percentile_value(&deviations, 50.0) * 1.4826;
+        if sigma <= 0.0 {
+            return (sanitized, 0);
+        }
+        let band = SPLIT_SIGMA_THRESHOLD * sigma;
+
+        let mut suspected = 0;
+        let mut i = 0;
+        while i + 1 < returns.len() {
+            let (r, next) = (returns[i], returns[i + 1]);
+            let reversed = r.signum() != next.signum()
+                && (1.0 + r) > 0.0
+                && (1.0 + next) > 0.0
+                && ((1.0 + r) * (1.0 + next)).ln().abs() < 0.5 * (1.0 + r).ln().abs();
+            if (r - median).abs() > band && reversed {
+                suspected += 1;
+                if self.winsorize_splits {
+                    sanitized[i] = r.clamp(median - band, median + band);
+                    sanitized[i + 1] = next.clamp(median - band, median + band);
+                }
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+
+        if suspected > 0 {
+            tracing::warn!(
+                "Detected {} suspected split/data-artifact return(s){}",
+                suspected,
+                if self.winsorize_splits {
+                    "; winsorized"
+                } else {
+                    ""
+                }
+            );
+        }
+
+        (sanitized, suspected)
     }
 
     /// Calculate Sharpe Ratio (annualized)
     #[allow(dead_code)]
-    fn calculate_sharpe_ratio(&self, returns: &[f64], risk_free_rate: f64) -> f64 {
+    fn calculate_sharpe_ratio(
+        &self,
+        returns: &[f64],
+        risk_free_rate: f64,
+        periods_per_year: f64,
+    ) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
@@ -31,9 +632,8 @@ impl QuantAnalysisEngine {
             return 0.0;
         }
 
-        // Annualize: assuming daily returns
-        let annualized_return = mean_return * 252.0;
-        let annualized_volatility = std_dev * (252.0_f64).sqrt();
+        let annualized_return = mean_return * periods_per_year;
+        let annualized_volatility = std_dev * periods_per_year.sqrt();
 
         (annualized_return - risk_free_rate) / annualized_volatility
     }
@@ -61,19 +661,73 @@ impl QuantAnalysisEngine {
         max_dd * 100.0 // Return as percentage
     }
 
+    /// Ulcer Index: root-mean-square percentage drawdown from the running peak,
+    /// so deep and long drawdowns both raise it
+    pub fn calculate_ulcer_index(&self, prices: &[f64]) -> f64 {
+        if prices.is_empty() {
+            return 0.0;
+        }
+        let mut peak = prices[0];
+        let sum_sq: f64 = prices
+            .iter()
+            .map(|&p| {
+                peak = peak.max(p);
+                ((peak - p) / peak * 100.0).powi(2)
+            })
+            .sum();
+        (sum_sq / prices.len() as f64).sqrt()
+    }
+
+    /// Worst drawdown with its peak, trough, duration, and recovery
+    pub fn calculate_drawdown_stats(&self, prices: &[f64]) -> DrawdownStats {
+        if prices.is_empty() {
+            return DrawdownStats::default();
+        }
+
+        let mut peak_index = 0;
+        let mut stats = DrawdownStats::default();
+        for (i, &price) in prices.iter().enumerate() {
+            if price > prices[peak_index] {
+                peak_index = i;
+            }
+            let drawdown = (prices[peak_index] - price) / prices[peak_index];
+            if drawdown > stats.max_drawdown_pct {
+                stats.max_drawdown_pct = drawdown;
+                stats.peak_index = peak_index;
+                stats.trough_index = i;
+            }
+        }
+
+        if stats.max_drawdown_pct > 0.0 {
+            let peak_price = prices[stats.peak_index];
+            stats.recovery_index =
+                (stats.trough_index + 1..prices.len()).find(|&i| prices[i] >= peak_price);
+            stats.recovered = stats.recovery_index.is_some();
+            stats.duration_bars =
+                stats.recovery_index.unwrap_or(prices.len() - 1) - stats.peak_index;
+        } else {
+            // Never below its running peak: there is no drawdown to recover from
+            stats.recovered = true;
+        }
+        let last = prices[prices.len() - 1];
+        stats.current_drawdown_pct = (prices[peak_index] - last) / prices[peak_index] * 100.0;
+        stats.max_drawdown_pct *= 100.0;
+        stats
+    }
+
     /// Calculate volatility (annualized)
-    fn calculate_volatility(&self, returns: &[f64]) -> f64 {
+    fn calculate_volatility(&self, returns: &[f64], periods_per_year: f64) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
 
         let std_dev = returns.std_dev();
-        std_dev * (252.0_f64).sqrt() * 100.0 // Annualized and as percentage
+        std_dev * periods_per_year.sqrt() * 100.0 // Annualized and as percentage
     }
 
     /// Calculate Beta (market sensitivity)
     /// For simplicity, this uses a mock market return
-    fn calculate_beta(&self, returns: &[f64]) -> f64 {
+    fn calculate_beta(&self, returns: &[f64], periods_per_year: f64) -> f64 {
         if returns.is_empty() {
             return 1.0;
         }
@@ -81,7 +735,7 @@ impl QuantAnalysisEngine {
         // In production, you'd compare against actual market index returns (e.g., SPY)
         // For now, we'll use a simplified calculation
         let volatility = returns.std_dev();
-        let market_volatility = 0.15 / (252.0_f64).sqrt(); // Assume 15% annual market volatility
+        let market_volatility = 0.15 / periods_per_year.sqrt(); // Assume 15% annual market volatility
 
         if market_volatility == 0.0 {
             return 1.0;
@@ -120,33 +774,44 @@ impl QuantAnalysisEngine {
     }
 
     /// Calculate Value at Risk (VaR) at 95% confidence
-    fn calculate_var(&self, returns: &[f64]) -> f64 {
+    fn calculate_var_95(&self, returns: &[f64]) -> f64 {
+        self.calculate_var(returns, DEFAULT_VAR_ALPHA)
+    }
+
+    /// Calculate Value at Risk (VaR) for the `alpha` tail (0.05 = 95%, 0.01 = 99%)
+    /// using the engine's `VarMethod`
+    fn calculate_var(&self, returns: &[f64], alpha: f64) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
 
-        let mut sorted_returns = returns.to_vec();
-        sorted_returns.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-        let index = (returns.len() as f64 * 0.05) as usize;
-        if index < sorted_returns.len() {
-            sorted_returns[index].abs() * 100.0 // As percentage
-        } else {
-            0.0
+        match self.var_method {
+            VarMethod::Historical => {
+                let mut sorted_returns = returns.to_vec();
+                sorted_returns.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                var_from_sorted(&sorted_returns, alpha).unwrap_or(0.0)
+            }
+            VarMethod::Gaussian => parametric_var(returns, alpha, false),
+            VarMethod::CornishFisher => parametric_var(returns, alpha, true),
         }
     }
 
     /// Calculate Sortino Ratio (uses downside deviation only)
-    fn calculate_sortino_ratio(&self, returns: &[f64], risk_free_rate: f64) -> f64 {
+    fn calculate_sortino_ratio(
+        &self,
+        returns: &[f64],
+        risk_free_rate: f64,
+        periods_per_year: f64,
+    ) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
 
         let mean_return = returns.mean();
-        let annualized_return = mean_return * 252.0;
+        let annualized_return = mean_return * periods_per_year;
 
-        // Downside deviation: std dev of returns below risk-free daily rate
-        let daily_rf = risk_free_rate / 252.0;
+        // Downside deviation: std dev of returns below the per-period risk-free rate
+        let daily_rf = risk_free_rate / periods_per_year;
         let downside_returns: Vec<f64> = returns
             .iter()
             .filter(|&&r| r < daily_rf)
@@ -158,7 +823,7 @@ impl QuantAnalysisEngine {
         }
 
         let downside_variance = downside_returns.iter().sum::<f64>() / returns.len() as f64;
-        let downside_dev = downside_variance.sqrt() * (252.0_f64).sqrt();
+        let downside_dev = downside_variance.sqrt() * periods_per_year.sqrt();
 
         if downside_dev == 0.0 {
             return 3.0;
@@ -197,6 +862,89 @@ impl QuantAnalysisEngine {
         covariance / bench_variance
     }
 
+    /// Downside and upside beta: OLS beta over only the days the benchmark fell
+    /// (resp. rose). Each is `None` with fewer than `MIN_CONDITIONAL_BETA_DAYS`
+    /// such days. Series are aligned on their most recent values.
+    pub fn calculate_conditional_betas(
+        &self,
+        stock_returns: &[f64],
+        benchmark_returns: &[f64],
+    ) -> (Option<f64>, Option<f64>) {
+        let n = stock_returns.len().min(benchmark_returns.len());
+        let stock = &stock_returns[stock_returns.len() - n..];
+        let bench = &benchmark_returns[benchmark_returns.len() - n..];
+
+        let beta_when = |keep: fn(f64) -> bool| {
+            let (s, b): (Vec<f64>, Vec<f64>) = stock
+                .iter()
+                .zip(bench)
+                .filter(|(_, &b)| keep(b))
+                .map(|(&s, &b)| (s, b))
+                .unzip();
+            (s.len() >= MIN_CONDITIONAL_BETA_DAYS).then(|| self.calculate_real_beta(&s, &b))
+        };
+        (beta_when(|b| b < 0.0), beta_when(|b| b > 0.0))
+    }
+
+    /// Beta over each trailing `window` of returns, oldest first. Series are
+    /// aligned on their most recent values, as in `calculate_real_beta`.
+    pub fn calculate_rolling_beta(
+        &self,
+        stock_returns: &[f64],
+        benchmark_returns: &[f64],
+        window: usize,
+    ) -> Vec<f64> {
+        let n = stock_returns.len().min(benchmark_returns.len());
+        if window < 2 || n < window {
+            return Vec::new();
+        }
+        let stock = &stock_returns[stock_returns.len() - n..];
+        let bench = &benchmark_returns[benchmark_returns.len() - n..];
+        (window..=n)
+            .map(|end| {
+                self.calculate_real_beta(&stock[end - window..end], &bench[end - window..end])
+            })
+            .collect()
+    }
+
+    /// Treynor Ratio: annualized excess return per unit of systematic (beta) risk
+    fn calculate_treynor_ratio(
+        &self,
+        returns: &[f64],
+        beta: f64,
+        risk_free_rate: f64,
+        periods_per_year: f64,
+    ) -> f64 {
+        if returns.is_empty() || beta.abs() < 1e-6 {
+            return 0.0;
+        }
+        let annualized_return = returns.mean() * periods_per_year;
+        (annualized_return - risk_free_rate) / beta
+    }
+
+    /// Information Ratio: annualized active return over tracking error vs the benchmark
+    fn calculate_information_ratio(
+        &self,
+        stock_returns: &[f64],
+        benchmark_returns: &[f64],
+        periods_per_year: f64,
+    ) -> f64 {
+        let n = stock_returns.len().min(benchmark_returns.len());
+        if n < 2 {
+            return 0.0;
+        }
+
+        let stock = &stock_returns[stock_returns.len() - n..];
+        let bench = &benchmark_returns[benchmark_returns.len() - n..];
+        let active: Vec<f64> = stock.iter().zip(bench.iter()).map(|(s, b)| s - b).collect();
+
+        let tracking_error = active.as_slice().std_dev() * periods_per_year.sqrt();
+        if tracking_error == 0.0 || tracking_error.is_nan() {
+            return 0.0;
+        }
+        active.as_slice().mean() * periods_per_year / tracking_error
+    }
+
     /// Calculate win rate for mean-reversion strategy (10-SMA crossover)
     fn calculate_mean_reversion_win_rate(&self, bars: &[Bar]) -> f64 {
         if bars.len() < 12 {
@@ -244,18 +992,18 @@ impl QuantAnalysisEngine {
     }
 
     /// CVaR (Conditional VaR) / Expected Shortfall at 95% confidence
-    fn calculate_cvar(&self, returns: &[f64]) -> f64 {
+    fn calculate_cvar_95(&self, returns: &[f64]) -> f64 {
+        self.calculate_cvar(returns, DEFAULT_VAR_ALPHA)
+    }
+
+    /// CVaR (Conditional VaR) / Expected Shortfall for the `alpha` tail
+    fn calculate_cvar(&self, returns: &[f64], alpha: f64) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
         let mut sorted = returns.to_vec();
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let cutoff = (returns.len() as f64 * 0.05).ceil() as usize;
-        if cutoff == 0 {
-            return 0.0;
-        }
-        let tail = &sorted[..cutoff];
-        (tail.iter().sum::<f64>() / tail.len() as f64).abs() * 100.0
+        cvar_from_sorted(&sorted, alpha).unwrap_or(0.0)
     }
 
     /// Hurst Exponent via Rescaled Range (R/S) analysis.
@@ -354,8 +1102,28 @@ impl QuantAnalysisEngine {
         cov / var
     }
 
+    /// RiskMetrics EWMA volatility (annualized %):
+    /// `sigma2_t = lambda * sigma2_{t-1} + (1 - lambda) * r_t^2`,
+    /// seeded with the mean squared return of the first 20 bars.
+    pub fn calculate_ewma_volatility(
+        &self,
+        returns: &[f64],
+        lambda: f64,
+        periods_per_year: f64,
+    ) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+        let seed = &returns[..returns.len().min(20)];
+        let mut sigma2 = seed.iter().map(|r| r * r).sum::<f64>() / seed.len() as f64;
+        for r in returns {
+            sigma2 = lambda * sigma2 + (1.0 - lambda) * r * r;
+        }
+        sigma2.sqrt() * periods_per_year.sqrt() * 100.0
+    }
+
     /// GARCH(1,1) one-step volatility forecast (annualized %)
-    fn forecast_volatility_garch(&self, returns: &[f64]) -> f64 {
+    fn forecast_volatility_garch(&self, returns: &[f64], periods_per_year: f64) -> f64 {
         if returns.is_empty() {
             return 0.0;
         }
@@ -370,7 +1138,7 @@ impl QuantAnalysisEngine {
         }
         let last_r = returns.last().unwrap_or(&0.0);
         let forecast = omega + alpha * last_r * last_r + beta * sigma2;
-        forecast.sqrt() * (252.0_f64).sqrt() * 100.0
+        forecast.sqrt() * periods_per_year.sqrt() * 100.0
     }
 
     /// Kelly Criterion optimal fraction
@@ -406,11 +1174,11 @@ impl QuantAnalysisEngine {
 
     /// Omega Ratio: probability-weighted ratio of gains to losses relative to threshold
     /// More comprehensive than Sharpe as it considers entire return distribution
-    fn calculate_omega_ratio(&self, returns: &[f64], threshold: f64) -> f64 {
+    fn calculate_omega_ratio(&self, returns: &[f64], threshold: f64, periods_per_year: f64) -> f64 {
         if returns.is_empty() {
             return 1.0;
         }
-        let daily_threshold = threshold / 252.0; // Convert annual to daily
+        let daily_threshold = threshold / periods_per_year; // Convert annual to per-period
 
         let gains: f64 = returns
             .iter()
@@ -460,6 +1228,12 @@ impl QuantAnalysisEngine {
             return None;
         }
 
+        // A half-life only means something if deviations actually revert;
+        // on trending names the AR(1) fit produces nonsense
+        if !augmented_dickey_fuller(&deviations, 1).is_stationary {
+            return None;
+        }
+
         // Estimate AR(1) coefficient: dev[t] = rho * dev[t-1] + epsilon
         // Half-life = -ln(2) / ln(rho)
         let mut sum_xy = 0.0;
@@ -483,43 +1257,68 @@ impl QuantAnalysisEngine {
     }
 
     /// Jump Diffusion Detection: identifies days with abnormal returns (potential jumps)
-    /// Returns (jump_days, jump_intensity)
-    fn detect_jumps(&self, returns: &[f64], threshold_sigma: f64) -> (usize, f64) {
+    /// Returns (indices of jump returns, jump_intensity)
+    fn detect_jumps(&self, returns: &[f64], threshold_sigma: f64) -> (Vec<usize>, f64) {
         if returns.len() < 20 {
-            return (0, 0.0);
+            return (Vec::new(), 0.0);
         }
 
-        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
-        let std_dev = {
-            let variance =
-                returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
-            variance.sqrt()
-        };
-
-        if std_dev == 0.0 {
-            return (0, 0.0);
-        }
+        let jumps: Vec<usize> = match self.jump_detection {
+            JumpDetection::ZScore => {
+                let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+                let std_dev = {
+                    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+                        / returns.len() as f64;
+                    variance.sqrt()
+                };
 
-        let jumps: Vec<f64> = returns
-            .iter()
-            .filter_map(|&r| {
-                let z = (r - mean) / std_dev;
-                if z.abs() > threshold_sigma {
-                    Some(r)
-                } else {
-                    None
+                if std_dev == 0.0 {
+                    return (Vec::new(), 0.0);
                 }
-            })
-            .collect();
 
-        let jump_days = jumps.len();
-        let jump_intensity = if jump_days > 0 {
-            jumps.iter().map(|j| j.abs()).sum::<f64>() / returns.len() as f64
+                (0..returns.len())
+                    .filter(|&i| ((returns[i] - mean) / std_dev).abs() > threshold_sigma)
+                    .collect()
+            }
+            JumpDetection::BipowerVariation => (BIPOWER_WINDOW + 1..returns.len())
+                .filter(|&t| {
+                    // Local variance from adjacent absolute-return products over the
+                    // trailing window, excluding bar t itself
+                    let window = &returns[t - BIPOWER_WINDOW - 1..t];
+                    let bipower = std::f64::consts::FRAC_PI_2
+                        * window
+                            .windows(2)
+                            .map(|w| w[0].abs() * w[1].abs())
+                            .sum::<f64>()
+                        / BIPOWER_WINDOW as f64;
+                    bipower > 0.0 && returns[t].abs() / bipower.sqrt() > threshold_sigma
+                })
+                .collect(),
+        };
+
+        let jump_intensity = if !jumps.is_empty() {
+            jumps.iter().map(|&i| returns[i].abs()).sum::<f64>() / returns.len() as f64
         } else {
             0.0
         };
 
-        (jump_days, jump_intensity)
+        (jumps, jump_intensity)
+    }
+
+    /// Share of realized variance not explained by bipower variation,
+    /// `max(0, (RV - BV) / RV)` — the jump component of total variance
+    fn jump_variance_ratio(&self, returns: &[f64]) -> Option<f64> {
+        if returns.len() < 20 {
+            return None;
+        }
+        let realized: f64 = returns.iter().map(|r| r * r).sum();
+        let n = returns.len() as f64;
+        let bipower = std::f64::consts::FRAC_PI_2 * n / (n - 1.0)
+            * returns
+                .windows(2)
+                .map(|w| w[0].abs() * w[1].abs())
+                .sum::<f64>();
+        (realized > 0.0).then(|| ((realized - bipower) / realized).max(0.0))
     }
 
     /// Rachev Ratio: ratio of expected tail gains to tail losses (like CVaR-based Sharpe)
@@ -550,6 +1349,121 @@ impl QuantAnalysisEngine {
         }
     }
 
+    /// Build the rolling-window baselines for daily `bars` and return how many
+    /// short windows they cover. Public only so `benches/rolling_series.rs` can
+    /// time `compute_rolling_series`.
+    #[doc(hidden)]
+    pub fn rolling_series_windows(&self, bars: &[Bar], risk_free_rate: f64) -> usize {
+        let prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let returns = self.calculate_returns(&prices);
+        self.compute_rolling_series(&prices, &returns, risk_free_rate, DEFAULT_VAR_ALPHA, 252.0)
+            .vols
+            .len()
+    }
+
+    /// Compute every rolling-window series used for adaptive thresholds.
+    ///
+    /// Windows are independent, so each window index is evaluated in parallel with
+    /// rayon. Series sharing a window length come out of a single pass, and VaR/CVaR
+    /// share one sort per window.
+    fn compute_rolling_series(
+        &self,
+        prices: &[f64],
+        returns: &[f64],
+        risk_free_rate: f64,
+        var_alpha: f64,
+        periods_per_year: f64,
+    ) -> RollingSeries {
+        let short: Vec<ShortWindowStats> = if returns.len() >= SHORT_WINDOW {
+            (SHORT_WINDOW..=returns.len())
+                .into_par_iter()
+                .map(|i| {
+                    let window = &returns[i - SHORT_WINDOW..i];
+                    let realized_vol = window.std_dev() * periods_per_year.sqrt() * 100.0;
+
+                    let mut sorted_window = window.to_vec();
+                    sorted_window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    let var = match self.var_method {
+                        VarMethod::Historical => var_from_sorted(&sorted_window, var_alpha),
+                        _ => Some(self.calculate_var(window, var_alpha)),
+                    };
+                    let cvar = cvar_from_sorted(&sorted_window, var_alpha);
+
+                    let garch_ratio = if realized_vol > 0.0 {
+                        Some(
+                            self.forecast_volatility_garch(window, periods_per_year) / realized_vol,
+                        )
+                    } else {
+                        None
+                    };
+
+                    ShortWindowStats {
+                        vol: realized_vol,
+                        var,
+                        cvar,
+                        kelly: self.calculate_kelly(window),
+                        garch_ratio,
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let long: Vec<LongWindowStats> = if returns.len() >= LONG_WINDOW {
+            (LONG_WINDOW..=returns.len())
+                .into_par_iter()
+                .map(|i| {
+                    let window = &returns[i - LONG_WINDOW..i];
+                    let mean_r = window.mean();
+                    let std_r = window.std_dev();
+                    let sharpe = if std_r > 0.0 {
+                        let ann_ret = mean_r * periods_per_year;
+                        let ann_vol = std_r * periods_per_year.sqrt();
+                        Some((ann_ret - risk_free_rate) / ann_vol)
+                    } else {
+                        None
+                    };
+                    LongWindowStats {
+                        sharpe,
+                        omega: self.calculate_omega_ratio(window, risk_free_rate, periods_per_year),
+                        rachev: self.calculate_rachev_ratio(window),
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let (drawdowns, ulcers): (Vec<f64>, Vec<f64>) = if prices.len() >= LONG_WINDOW {
+            (LONG_WINDOW..=prices.len())
+                .into_par_iter()
+                .map(|i| {
+                    let window = &prices[i - LONG_WINDOW..i];
+                    (
+                        self.calculate_max_drawdown(window),
+                        self.calculate_ulcer_index(window),
+                    )
+                })
+                .unzip()
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        RollingSeries {
+            sharpes: RollingStats::new(long.iter().filter_map(|w| w.sharpe).collect()),
+            vols: RollingStats::new(short.iter().map(|w| w.vol).collect()),
+            drawdowns: RollingStats::new(drawdowns),
+            ulcers: RollingStats::new(ulcers),
+            vars: RollingStats::new(short.iter().filter_map(|w| w.var).collect()),
+            cvars: RollingStats::new(short.iter().filter_map(|w| w.cvar).collect()),
+            kellys: RollingStats::new(short.iter().map(|w| w.kelly).collect()),
+            garch_ratios: RollingStats::new(short.iter().filter_map(|w| w.garch_ratio).collect()),
+            omegas: RollingStats::new(long.iter().map(|w| w.omega).collect()),
+            rachevs: RollingStats::new(long.iter().map(|w| w.rachev).collect()),
+        }
+    }
+
     /// Enhanced analysis with optional SPY benchmark for real beta
     pub fn analyze_with_benchmark(
         &self,
@@ -560,7 +1474,8 @@ impl QuantAnalysisEngine {
         self.analyze_with_benchmark_and_rate(symbol, bars, spy_bars, None)
     }
 
-    /// Full analysis with optional SPY benchmark and optional dynamic risk-free rate
+    /// Full analysis with optional SPY benchmark and optional dynamic risk-free rate,
+    /// annualizing as daily bars
     pub fn analyze_with_benchmark_and_rate(
         &self,
         symbol: &str,
@@ -568,6 +1483,44 @@ impl QuantAnalysisEngine {
         spy_bars: Option<&[Bar]>,
         dynamic_risk_free_rate: Option<f64>,
     ) -> Result<AnalysisResult, AnalysisError> {
+        self.analyze_with_periods(
+            symbol,
+            bars,
+            spy_bars,
+            dynamic_risk_free_rate,
+            Timeframe::Day1.periods_per_year(),
+        )
+    }
+
+    /// Full analysis annualizing with `periods_per_year` bars per year
+    /// (see `Timeframe::periods_per_year`)
+    pub fn analyze_with_periods(
+        &self,
+        symbol: &str,
+        bars: &[Bar],
+        spy_bars: Option<&[Bar]>,
+        dynamic_risk_free_rate: Option<f64>,
+        periods_per_year: f64,
+    ) -> Result<AnalysisResult, AnalysisError> {
+        // Bad prints and halted names can carry zero/NaN closes; drop a few,
+        // refuse a series where they are common
+        let valid = |b: &Bar| b.close.is_finite() && b.close > 0.0;
+        let invalid = bars.iter().filter(|b| !valid(b)).count();
+        if invalid as f64 > bars.len() as f64 * MAX_INVALID_BAR_FRACTION {
+            return Err(AnalysisError::InsufficientData(format!(
+                "{} of {} bars have invalid prices",
+                invalid,
+                bars.len()
+            )));
+        }
+        let clean_bars: Vec<Bar>;
+        let bars = if invalid > 0 {
+            clean_bars = bars.iter().filter(|b| valid(b)).cloned().collect();
+            clean_bars.as_slice()
+        } else {
+            bars
+        };
+
         if bars.len() < 30 {
             return Err(AnalysisError::InsufficientData(
                 "Need at least 30 bars for quantitative analysis".to_string(),
@@ -575,8 +1528,16 @@ impl QuantAnalysisEngine {
         }
 
         let prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
-        let returns = self.calculate_returns(&prices);
+        let (returns, suspected_split_days) =
+            self.sanitize_returns(&self.calculate_returns(&prices));
         let risk_free_rate = dynamic_risk_free_rate.unwrap_or(0.045);
+        let rolling = self.compute_rolling_series(
+            &prices,
+            &returns,
+            risk_free_rate,
+            DEFAULT_VAR_ALPHA,
+            periods_per_year,
+        );
 
         let mut signals = Vec::new();
 
@@ -588,27 +1549,17 @@ impl QuantAnalysisEngine {
             if std_dev == 0.0 {
                 0.0
             } else {
-                let annualized_return = mean_return * 252.0;
-                let annualized_volatility = std_dev * (252.0_f64).sqrt();
+                let annualized_return = mean_return * periods_per_year;
+                let annualized_volatility = std_dev * periods_per_year.sqrt();
                 (annualized_return - risk_free_rate) / annualized_volatility
             }
         };
         // Adaptive Sharpe threshold: rolling 60-day windows
         if returns.len() >= 60 {
-            let mut rolling_sharpes = Vec::new();
-            for i in 60..=returns.len() {
-                let window = &returns[i - 60..i];
-                let mean_r = window.mean();
-                let std_r = window.std_dev();
-                if std_r > 0.0 {
-                    let ann_ret = mean_r * 252.0;
-                    let ann_vol = std_r * (252.0_f64).sqrt();
-                    rolling_sharpes.push((ann_ret - risk_free_rate) / ann_vol);
-                }
-            }
+            let rolling_sharpes = &rolling.sharpes;
             if !rolling_sharpes.is_empty() {
-                let sharpe_pct = adaptive::percentile_rank(sharpe, &rolling_sharpes);
-                let sharpe_z = adaptive::z_score_of(sharpe, &rolling_sharpes);
+                let sharpe_pct = rolling_sharpes.percentile_rank(sharpe);
+                let sharpe_z = rolling_sharpes.z_score(sharpe);
                 let sharpe_weight = adaptive::z_score_to_weight(sharpe_z);
                 if sharpe_pct > 0.80 {
                     signals.push(("Good Risk-Adjusted Return", sharpe_weight, true));
@@ -622,8 +1573,23 @@ impl QuantAnalysisEngine {
             signals.push(("Poor Risk-Adjusted Return", 2, false));
         }
 
+        // Rolling Sharpe trend: catches names rolling over before the level does.
+        // Overlapping windows make a regression t-stat meaningless, so the fitted
+        // decline is compared to the spread of the series instead.
+        let sharpe_trend_slope = trend_slope(rolling.sharpes.values());
+        if let Some(slope) = sharpe_trend_slope {
+            let spread = rolling.sharpes.std_dev();
+            let decline = -slope * (rolling.sharpes.len() - 1) as f64;
+            if sharpe >= ACCEPTABLE_SHARPE
+                && spread > 0.0
+                && decline > SHARPE_DETERIORATION_SIGMAS * spread
+            {
+                signals.push(("Risk-Adjusted Performance Deteriorating", 1, false));
+            }
+        }
+
         // Sortino Ratio
-        let sortino = self.calculate_sortino_ratio(&returns, risk_free_rate);
+        let sortino = self.calculate_sortino_ratio(&returns, risk_free_rate, periods_per_year);
         // Adaptive Sortino: use z-score vs benchmark distribution
         let sortino_benchmarks = vec![0.0, 0.5, 1.0, 1.5, 2.0];
         let sortino_z = adaptive::z_score_of(sortino, &sortino_benchmarks);
@@ -635,18 +1601,13 @@ impl QuantAnalysisEngine {
         }
 
         // Volatility
-        let volatility = self.calculate_volatility(&returns);
+        let volatility = self.calculate_volatility(&returns, periods_per_year);
         // Adaptive volatility: rolling 30-day windows
         if returns.len() >= 30 {
-            let mut rolling_vols = Vec::new();
-            for i in 30..=returns.len() {
-                let window = &returns[i - 30..i];
-                let std_dev = window.std_dev();
-                rolling_vols.push(std_dev * (252.0_f64).sqrt() * 100.0);
-            }
+            let rolling_vols = &rolling.vols;
             if !rolling_vols.is_empty() {
-                let vol_pct = adaptive::percentile_rank(volatility, &rolling_vols);
-                let vol_z = adaptive::z_score_of(volatility, &rolling_vols);
+                let vol_pct = rolling_vols.percentile_rank(volatility);
+                let vol_z = rolling_vols.z_score(volatility);
                 let vol_weight = adaptive::z_score_to_weight(vol_z);
                 if vol_pct > 0.85 {
                     signals.push(("High Volatility", vol_weight, false));
@@ -662,27 +1623,36 @@ impl QuantAnalysisEngine {
 
         // Max Drawdown
         let max_dd = self.calculate_max_drawdown(&prices);
+        let drawdown = self.calculate_drawdown_stats(&prices);
+
+        // Ulcer Index and Martin (pain) ratio: excess return per unit of drawdown pain
+        let ulcer_index = self.calculate_ulcer_index(&prices);
+        let martin_ratio = (ulcer_index > 0.0).then(|| {
+            (returns.as_slice().mean() * periods_per_year - risk_free_rate) * 100.0 / ulcer_index
+        });
+        if prices.len() >= LONG_WINDOW && !rolling.ulcers.is_empty() {
+            let ulcer_pct = rolling.ulcers.percentile_rank(ulcer_index);
+            let ulcer_z = rolling.ulcers.z_score(ulcer_index);
+            let ulcer_weight = adaptive::z_score_to_weight(ulcer_z.abs());
+            if ulcer_pct > 0.85 {
+                signals.push(("High Pain Profile", ulcer_weight, false));
+            } else if ulcer_pct < 0.15 {
+                signals.push(("Low Pain Profile", ulcer_weight, true));
+            }
+        } else if ulcer_index < 5.0 {
+            signals.push(("Low Pain Profile", 1, true));
+        } else if ulcer_index > 15.0 {
+            signals.push(("High Pain Profile", 1, false));
+        }
+        let drawdown_end = drawdown.recovery_index.unwrap_or(bars.len() - 1);
+        let max_drawdown_duration_days =
+            (bars[drawdown_end].timestamp - bars[drawdown.peak_index].timestamp).num_days();
         // Adaptive max drawdown: rolling 60-day windows
         if prices.len() >= 60 {
-            let mut rolling_dds = Vec::new();
-            for i in 60..=prices.len() {
-                let window = &prices[i - 60..i];
-                let mut max_price = window[0];
-                let mut max_dd_window = 0.0;
-                for &price in window.iter() {
-                    if price > max_price {
-                        max_price = price;
-                    }
-                    let drawdown = (max_price - price) / max_price * 100.0;
-                    if drawdown > max_dd_window {
-                        max_dd_window = drawdown;
-                    }
-                }
-                rolling_dds.push(max_dd_window);
-            }
+            let rolling_dds = &rolling.drawdowns;
             if !rolling_dds.is_empty() {
-                let dd_pct = adaptive::percentile_rank(max_dd, &rolling_dds);
-                let dd_z = adaptive::z_score_of(max_dd, &rolling_dds);
+                let dd_pct = rolling_dds.percentile_rank(max_dd);
+                let dd_z = rolling_dds.z_score(max_dd);
                 let dd_weight = adaptive::z_score_to_weight(dd_z);
                 if dd_pct > 0.85 {
                     signals.push(("High Drawdown", dd_weight, false));
@@ -696,20 +1666,89 @@ impl QuantAnalysisEngine {
             signals.push(("High Drawdown", 2, false));
         }
 
-        // Beta — real calculation if SPY bars available
-        let beta = if let Some(spy) = spy_bars {
-            let spy_prices: Vec<f64> = spy.iter().map(|b| b.close).collect();
-            let spy_returns = self.calculate_returns(&spy_prices);
-            self.calculate_real_beta(&returns, &spy_returns)
-        } else {
-            self.calculate_beta(&returns)
+        // Beta — real calculation if SPY bars available. Returns are paired by
+        // timestamp so a recent IPO or missing sessions can't shift the calendars.
+        let benchmark_returns: Option<(Vec<f64>, Vec<f64>)> =
+            spy_bars.map(|spy| align_benchmark_returns(bars, &returns, spy));
+        let beta_sample_size = benchmark_returns.as_ref().map(|(stock, _)| stock.len());
+        let short_beta_overlap = beta_sample_size.is_some_and(|n| n < MIN_BETA_OVERLAP);
+        let beta = match &benchmark_returns {
+            Some((stock, bench)) => self.calculate_real_beta(stock, bench),
+            None => self.calculate_beta(&returns, periods_per_year),
         };
-        if beta > 1.2 {
-            signals.push(("High Beta (Aggressive)", 1, false));
-        } else if beta < 0.8 {
-            signals.push(("Low Beta (Defensive)", 1, true));
+        // Raw OLS beta is noisy on short histories; the High/Low Beta signals use
+        // the estimate shrunk toward the market, Treynor keeps the raw beta.
+        // Too short an overlap and the beta signals are dropped altogether.
+        let adjusted_beta = (1.0 - self.beta_shrinkage) * beta + self.beta_shrinkage;
+        if !short_beta_overlap {
+            if adjusted_beta > 1.2 {
+                signals.push(("High Beta (Aggressive)", 1, false));
+            } else if adjusted_beta < 0.8 {
+                signals.push(("Low Beta (Defensive)", 1, true));
+            }
+        }
+
+        // Stocks that fall with the market but lag its rallies
+        let (downside_beta, upside_beta) = match &benchmark_returns {
+            Some((stock, bench)) => self.calculate_conditional_betas(stock, bench),
+            None => (None, None),
+        };
+        if let (Some(down), Some(up)) = (downside_beta, upside_beta) {
+            if !short_beta_overlap && down - up > ASYMMETRIC_BETA_GAP && down > 1.0 {
+                signals.push(("Asymmetric Downside Risk", 1, false));
+            }
         }
 
+        // --- Treynor & Information Ratio (benchmark-relative) ---
+        let (treynor_ratio, information_ratio) = if let Some((stock, bench)) = &benchmark_returns {
+            let treynor =
+                self.calculate_treynor_ratio(stock, beta, risk_free_rate, periods_per_year);
+            let ir = self.calculate_information_ratio(stock, bench, periods_per_year);
+            let min_len = stock.len();
+            // Adaptive thresholds: rolling 60-day windows on the aligned returns
+            if min_len >= 60 {
+                let mut rolling_treynors = Vec::new();
+                let mut rolling_irs = Vec::new();
+                for i in 60..=min_len {
+                    let stock_window = &stock[i - 60..i];
+                    let bench_window = &bench[i - 60..i];
+                    let window_beta = self.calculate_real_beta(stock_window, bench_window);
+                    rolling_treynors.push(self.calculate_treynor_ratio(
+                        stock_window,
+                        window_beta,
+                        risk_free_rate,
+                        periods_per_year,
+                    ));
+                    rolling_irs.push(self.calculate_information_ratio(
+                        stock_window,
+                        bench_window,
+                        periods_per_year,
+                    ));
+                }
+                let treynor_z = adaptive::z_score_of(treynor, &rolling_treynors);
+                let treynor_weight = adaptive::z_score_to_weight(treynor_z.abs());
+                if treynor_z > 1.0 && treynor > 0.0 {
+                    signals.push(("Strong Treynor Ratio", treynor_weight, true));
+                } else if treynor_z < -1.0 && treynor < 0.0 {
+                    signals.push(("Weak Treynor Ratio", treynor_weight, false));
+                }
+                let ir_z = adaptive::z_score_of(ir, &rolling_irs);
+                let ir_weight = adaptive::z_score_to_weight(ir_z.abs());
+                if ir_z > 1.0 && ir > 0.0 {
+                    signals.push(("Consistent Outperformance (IR)", ir_weight, true));
+                } else if ir_z < -1.0 && ir < 0.0 {
+                    signals.push(("Consistent Underperformance (IR)", ir_weight, false));
+                }
+            } else if ir > 0.5 {
+                signals.push(("Consistent Outperformance (IR)", 2, true));
+            } else if ir < -0.5 {
+                signals.push(("Consistent Underperformance (IR)", 2, false));
+            }
+            (Some(treynor), Some(ir))
+        } else {
+            (None, None)
+        };
+
         // Win Rate — test both strategies, report the better one
         let momentum_wr = self.calculate_win_rate(bars);
         let mean_rev_wr = self.calculate_mean_reversion_win_rate(bars);
@@ -725,22 +1764,14 @@ impl QuantAnalysisEngine {
         }
 
         // VaR — generate signals for extreme risk
-        let var = self.calculate_var(&returns);
+        let var = self.calculate_var_95(&returns);
+        let var_99 = self.calculate_var(&returns, 0.01);
         // Adaptive VaR: rolling 30-day windows
         if returns.len() >= 30 {
-            let mut rolling_vars = Vec::new();
-            for i in 30..=returns.len() {
-                let window = &returns[i - 30..i];
-                let mut sorted_window = window.to_vec();
-                sorted_window.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                let index = (window.len() as f64 * 0.05) as usize;
-                if index < sorted_window.len() {
-                    rolling_vars.push(sorted_window[index].abs() * 100.0);
-                }
-            }
+            let rolling_vars = &rolling.vars;
             if !rolling_vars.is_empty() {
-                let var_pct = adaptive::percentile_rank(var, &rolling_vars);
-                let var_z = adaptive::z_score_of(var, &rolling_vars);
+                let var_pct = rolling_vars.percentile_rank(var);
+                let var_z = rolling_vars.z_score(var);
                 let var_weight = adaptive::z_score_to_weight(var_z);
                 if var_pct > 0.85 {
                     signals.push(("Extreme VaR Risk", var_weight, false));
@@ -764,16 +1795,14 @@ impl QuantAnalysisEngine {
         } else {
             0.0
         };
+        let momentum_idx = signals.len();
         // Adaptive momentum: compute all 20-day rolling returns
         if prices.len() >= 40 {
-            let mut rolling_rets = Vec::new();
-            for i in 20..prices.len() {
-                let ret = (prices[i] - prices[i - 20]) / prices[i - 20];
-                rolling_rets.push(ret);
-            }
+            let rolling_rets =
+                RollingStats::from_windows(&prices, 21, |w| Some((w[20] - w[0]) / w[0]));
             if !rolling_rets.is_empty() {
-                let mom_pct = adaptive::percentile_rank(recent_return, &rolling_rets);
-                let mom_z = adaptive::z_score_of(recent_return, &rolling_rets);
+                let mom_pct = rolling_rets.percentile_rank(recent_return);
+                let mom_z = rolling_rets.z_score(recent_return);
                 let mom_weight = adaptive::z_score_to_weight(mom_z.abs());
                 if mom_pct > 0.95 {
                     signals.push(("Extreme Momentum — Reversion Risk", mom_weight, false));
@@ -802,26 +1831,17 @@ impl QuantAnalysisEngine {
         } else if recent_return < -0.05 {
             signals.push(("Negative Momentum", 2, false));
         }
+        let momentum_idx = (signals.len() > momentum_idx).then_some(momentum_idx);
 
         // --- CVaR / Expected Shortfall ---
-        let cvar = self.calculate_cvar(&returns);
+        let cvar = self.calculate_cvar_95(&returns);
+        let cvar_99 = self.calculate_cvar(&returns, 0.01);
         // Adaptive CVaR: rolling 30-day windows
         if returns.len() >= 30 {
-            let mut rolling_cvars = Vec::new();
-            for i in 30..=returns.len() {
-                let window = &returns[i - 30..i];
-                let mut sorted_window = window.to_vec();
-                sorted_window.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                let cutoff = (window.len() as f64 * 0.05).ceil() as usize;
-                if cutoff > 0 && cutoff <= sorted_window.len() {
-                    let tail = &sorted_window[..cutoff];
-                    let cvar_val = (tail.iter().sum::<f64>() / tail.len() as f64).abs() * 100.0;
-                    rolling_cvars.push(cvar_val);
-                }
-            }
+            let rolling_cvars = &rolling.cvars;
             if !rolling_cvars.is_empty() {
-                let cvar_pct = adaptive::percentile_rank(cvar, &rolling_cvars);
-                let cvar_z = adaptive::z_score_of(cvar, &rolling_cvars);
+                let cvar_pct = rolling_cvars.percentile_rank(cvar);
+                let cvar_z = rolling_cvars.z_score(cvar);
                 let cvar_weight = adaptive::z_score_to_weight(cvar_z);
                 if cvar_pct > 0.85 {
                     signals.push(("Extreme Tail Risk (CVaR)", cvar_weight, false));
@@ -848,6 +1868,7 @@ impl QuantAnalysisEngine {
         } else {
             "random"
         };
+        let hurst_idx = signals.len();
         if hurst > 0.65 {
             // Trending — signal aligns with current direction
             signals.push(("Trending Market (Hurst)", 2, recent_return > 0.0));
@@ -856,6 +1877,10 @@ impl QuantAnalysisEngine {
             signals.push(("Mean-Reverting Market (Hurst)", 2, recent_return < 0.0));
         }
 
+        let hurst_idx = (signals.len() > hurst_idx).then_some(hurst_idx);
+        let regime_momentum_conflict =
+            reconcile_regime_momentum(&mut signals, momentum_idx, hurst_idx);
+
         // --- Autocorrelation ---
         let ac1 = self.calculate_autocorrelation(&returns, 1);
         // Statistical significance: |ac1| > 2.0 / sqrt(n) at 95% confidence
@@ -879,22 +1904,14 @@ impl QuantAnalysisEngine {
         }
 
         // --- GARCH Volatility Forecast ---
-        let garch_vol = self.forecast_volatility_garch(&returns);
+        let garch_vol = self.forecast_volatility_garch(&returns, periods_per_year);
         // Adaptive GARCH: percentile of garch/realized ratio
         if volatility > 0.0 && returns.len() >= 30 {
-            let mut rolling_ratios = Vec::new();
-            for i in 30..=returns.len() {
-                let window = &returns[i - 30..i];
-                let realized_vol = window.std_dev() * (252.0_f64).sqrt() * 100.0;
-                let garch_forecast = self.forecast_volatility_garch(window);
-                if realized_vol > 0.0 {
-                    rolling_ratios.push(garch_forecast / realized_vol);
-                }
-            }
+            let rolling_ratios = &rolling.garch_ratios;
             if !rolling_ratios.is_empty() {
                 let current_ratio = garch_vol / volatility;
-                let ratio_pct = adaptive::percentile_rank(current_ratio, &rolling_ratios);
-                let ratio_z = adaptive::z_score_of(current_ratio, &rolling_ratios);
+                let ratio_pct = rolling_ratios.percentile_rank(current_ratio);
+                let ratio_z = rolling_ratios.z_score(current_ratio);
                 let ratio_weight = adaptive::z_score_to_weight(ratio_z.abs());
                 if ratio_pct > 0.85 {
                     signals.push(("Volatility Expected to Increase", ratio_weight, false));
@@ -908,18 +1925,23 @@ impl QuantAnalysisEngine {
             signals.push(("Volatility Expected to Decrease", 1, true));
         }
 
+        // --- EWMA Volatility ---
+        // Reacts to recent shocks faster than full-sample realized vol, so a
+        // widening gap is an early warning that volatility is building
+        let ewma_vol = self.calculate_ewma_volatility(&returns, self.ewma_lambda, periods_per_year);
+        let ewma_vol_ratio = (volatility > 0.0).then(|| ewma_vol / volatility);
+        if ewma_vol_ratio.is_some_and(|r| r > EWMA_VOL_BUILDING_RATIO) {
+            signals.push(("Volatility Building", 1, false));
+        }
+
         // --- Kelly Criterion ---
         let kelly = self.calculate_kelly(&returns);
         // Adaptive Kelly: percentile of 30-day rolling Kelly estimates
         if returns.len() >= 30 {
-            let mut rolling_kellys = Vec::new();
-            for i in 30..=returns.len() {
-                let window = &returns[i - 30..i];
-                rolling_kellys.push(self.calculate_kelly(window));
-            }
+            let rolling_kellys = &rolling.kellys;
             if !rolling_kellys.is_empty() {
-                let kelly_pct = adaptive::percentile_rank(kelly, &rolling_kellys);
-                let kelly_z = adaptive::z_score_of(kelly, &rolling_kellys);
+                let kelly_pct = rolling_kellys.percentile_rank(kelly);
+                let kelly_z = rolling_kellys.z_score(kelly);
                 let kelly_weight = adaptive::z_score_to_weight(kelly_z.abs());
                 if kelly > 0.0 && kelly_pct > 0.85 {
                     signals.push(("Favorable Risk/Reward (Kelly)", kelly_weight, true));
@@ -967,7 +1989,7 @@ impl QuantAnalysisEngine {
         // --- Skewness & Kurtosis ---
         let skewness = if returns.len() >= 30 {
             let mean = returns.iter().sum::<f64>() / returns.len() as f64;
-            let std_dev = volatility / (252.0_f64).sqrt(); // daily vol
+            let std_dev = volatility / periods_per_year.sqrt(); // per-bar vol
             if std_dev > 0.0 {
                 let n = returns.len() as f64;
                 let m3 = returns
@@ -985,7 +2007,7 @@ impl QuantAnalysisEngine {
 
         let kurtosis = if returns.len() >= 30 {
             let mean = returns.iter().sum::<f64>() / returns.len() as f64;
-            let std_dev = volatility / (252.0_f64).sqrt();
+            let std_dev = volatility / periods_per_year.sqrt();
             if std_dev > 0.0 {
                 let n = returns.len() as f64;
                 let m4 = returns
@@ -1015,91 +2037,21 @@ impl QuantAnalysisEngine {
         }
 
         // --- Correlation Regime Shift (rolling beta stability) ---
-        let beta_stability = if let Some(spy) = spy_bars {
-            let spy_prices: Vec<f64> = spy.iter().map(|b| b.close).collect();
-            let spy_returns = self.calculate_returns(&spy_prices);
-            let min_len = returns.len().min(spy_returns.len());
+        let mut rolling_beta: Vec<f64> = Vec::new();
+        let beta_stability = if let Some((stock, bench)) = &benchmark_returns {
+            let min_len = stock.len();
             if min_len >= 60 {
-                // Compare beta from first half vs second half
-                let half = min_len / 2;
-                let beta_first = {
-                    let sr = &spy_returns[..half];
-                    let ar = &returns[..half];
-                    let sm = sr.iter().sum::<f64>() / sr.len() as f64;
-                    let cov = sr
-                        .iter()
-                        .zip(ar.iter())
-                        .map(|(s, a)| (s - sm) * a)
-                        .sum::<f64>()
-                        / sr.len() as f64;
-                    let var = sr.iter().map(|s| (s - sm).powi(2)).sum::<f64>() / sr.len() as f64;
-                    if var > 0.0 {
-                        cov / var
-                    } else {
-                        0.0
-                    }
-                };
-                let beta_second = {
-                    let sr = &spy_returns[half..min_len];
-                    let ar = &returns[half..min_len];
-                    let sm = sr.iter().sum::<f64>() / sr.len() as f64;
-                    let cov = sr
-                        .iter()
-                        .zip(ar.iter())
-                        .map(|(s, a)| (s - sm) * a)
-                        .sum::<f64>()
-                        / sr.len() as f64;
-                    let var = sr.iter().map(|s| (s - sm).powi(2)).sum::<f64>() / sr.len() as f64;
-                    if var > 0.0 {
-                        cov / var
-                    } else {
-                        0.0
-                    }
+                // Window fits twice so the first and last windows don't overlap
+                let window = (min_len / 2).min(ROLLING_BETA_WINDOW);
+                rolling_beta = self.calculate_rolling_beta(stock, bench, window);
+                let shift = match (rolling_beta.first(), rolling_beta.last()) {
+                    (Some(first), Some(last)) => (last - first).abs(),
+                    _ => 0.0,
                 };
-                let shift = (beta_second - beta_first).abs();
-                // Adaptive beta shift: z-score of shift magnitude
-                let mut rolling_shifts = Vec::new();
-                let min_window = 60;
-                for i in min_window * 2..min_len {
-                    let half = i / 2;
-                    let b1 = {
-                        let sr = &spy_returns[..half];
-                        let ar = &returns[..half];
-                        let sm = sr.iter().sum::<f64>() / sr.len() as f64;
-                        let cov = sr
-                            .iter()
-                            .zip(ar.iter())
-                            .map(|(s, a)| (s - sm) * a)
-                            .sum::<f64>()
-                            / sr.len() as f64;
-                        let var =
-                            sr.iter().map(|s| (s - sm).powi(2)).sum::<f64>() / sr.len() as f64;
-                        if var > 0.0 {
-                            cov / var
-                        } else {
-                            0.0
-                        }
-                    };
-                    let b2 = {
-                        let sr = &spy_returns[half..i];
-                        let ar = &returns[half..i];
-                        let sm = sr.iter().sum::<f64>() / sr.len() as f64;
-                        let cov = sr
-                            .iter()
-                            .zip(ar.iter())
-                            .map(|(s, a)| (s - sm) * a)
-                            .sum::<f64>()
-                            / sr.len() as f64;
-                        let var =
-                            sr.iter().map(|s| (s - sm).powi(2)).sum::<f64>() / sr.len() as f64;
-                        if var > 0.0 {
-                            cov / var
-                        } else {
-                            0.0
-                        }
-                    };
-                    rolling_shifts.push((b2 - b1).abs());
-                }
+                // Adaptive beta shift: z-score vs beta changes over one window length
+                let rolling_shifts: Vec<f64> = (window..rolling_beta.len())
+                    .map(|i| (rolling_beta[i] - rolling_beta[i - window]).abs())
+                    .collect();
                 if !rolling_shifts.is_empty() {
                     let shift_z = adaptive::z_score_of(shift, &rolling_shifts);
                     let shift_weight = adaptive::z_score_to_weight(shift_z.abs());
@@ -1116,18 +2068,16 @@ impl QuantAnalysisEngine {
         } else {
             None
         };
+        let rolling_beta_tail =
+            &rolling_beta[rolling_beta.len().saturating_sub(ROLLING_BETA_OUTPUT_LEN)..];
 
         // --- Omega Ratio ---
-        let omega_ratio = self.calculate_omega_ratio(&returns, risk_free_rate);
+        let omega_ratio = self.calculate_omega_ratio(&returns, risk_free_rate, periods_per_year);
         // Adaptive Omega: z-score vs rolling 60-day windows
         if returns.len() >= 60 {
-            let mut rolling_omegas = Vec::new();
-            for i in 60..=returns.len() {
-                let window = &returns[i - 60..i];
-                rolling_omegas.push(self.calculate_omega_ratio(window, risk_free_rate));
-            }
+            let rolling_omegas = &rolling.omegas;
             if !rolling_omegas.is_empty() {
-                let omega_z = adaptive::z_score_of(omega_ratio, &rolling_omegas);
+                let omega_z = rolling_omegas.z_score(omega_ratio);
                 let omega_weight = adaptive::z_score_to_weight(omega_z);
                 if omega_z > 1.0 {
                     signals.push(("Superior Omega Ratio", omega_weight, true));
@@ -1154,7 +2104,14 @@ impl QuantAnalysisEngine {
         }
 
         // --- Jump Diffusion Detection ---
-        let (jump_days, jump_intensity) = self.detect_jumps(&returns, 3.0); // 3-sigma threshold
+        let (jumps, jump_intensity) = self.detect_jumps(&returns, self.jump_threshold_sigma);
+        let jump_days = jumps.len();
+        // returns[i] is the move into bars[i + 1]
+        let jump_dates: Vec<String> = jumps
+            .iter()
+            .map(|&i| bars[i + 1].timestamp.format("%Y-%m-%d").to_string())
+            .collect();
+        let jump_variance_ratio = self.jump_variance_ratio(&returns);
         if jump_days as f64 / returns.len() as f64 > 0.05 {
             signals.push(("Frequent Jumps (High Event Risk)", 2, false));
         }
@@ -1163,13 +2120,9 @@ impl QuantAnalysisEngine {
         let rachev_ratio = self.calculate_rachev_ratio(&returns);
         // Adaptive Rachev: z-score vs rolling 60-day windows
         if returns.len() >= 60 {
-            let mut rolling_rachevs = Vec::new();
-            for i in 60..=returns.len() {
-                let window = &returns[i - 60..i];
-                rolling_rachevs.push(self.calculate_rachev_ratio(window));
-            }
+            let rolling_rachevs = &rolling.rachevs;
             if !rolling_rachevs.is_empty() {
-                let rachev_z = adaptive::z_score_of(rachev_ratio, &rolling_rachevs);
+                let rachev_z = rolling_rachevs.z_score(rachev_ratio);
                 let rachev_weight = adaptive::z_score_to_weight(rachev_z.abs());
                 if rachev_z > 1.0 {
                     signals.push(("Favorable Tail Risk Profile (Rachev)", rachev_weight, true));
@@ -1235,7 +2188,7 @@ impl QuantAnalysisEngine {
         let low_vol_factor = if let Some(spy) = spy_bars {
             let spy_prices: Vec<f64> = spy.iter().map(|b| b.close).collect();
             let spy_returns = self.calculate_returns(&spy_prices);
-            let spy_vol = self.calculate_volatility(&spy_returns);
+            let spy_vol = self.calculate_volatility(&spy_returns, periods_per_year);
             if spy_vol > 0.0 {
                 let ratio = volatility / spy_vol;
                 // Adaptive vol factor: z-score of volatility ratio
@@ -1245,8 +2198,8 @@ impl QuantAnalysisEngine {
                     for i in 30..=min_len {
                         let stock_window = &returns[i - 30..i];
                         let spy_window = &spy_returns[i - 30..i];
-                        let stock_vol = stock_window.std_dev() * (252.0_f64).sqrt() * 100.0;
-                        let spy_vol_window = spy_window.std_dev() * (252.0_f64).sqrt() * 100.0;
+                        let stock_vol = stock_window.std_dev() * periods_per_year.sqrt() * 100.0;
+                        let spy_vol_window = spy_window.std_dev() * periods_per_year.sqrt() * 100.0;
                         if spy_vol_window > 0.0 {
                             rolling_ratios.push(stock_vol / spy_vol_window);
                         }
@@ -1288,6 +2241,7 @@ impl QuantAnalysisEngine {
         };
 
         let signal = SignalStrength::from_score(normalized_score as i32);
+        let factor_attribution = factor_attribution(&signals, total_weight);
 
         // Dynamic confidence: data quantity (60%) + signal agreement (40%)
         let data_confidence = if bars.len() >= 90 {
@@ -1307,7 +2261,12 @@ impl QuantAnalysisEngine {
         } else {
             0.5
         };
-        let confidence = (data_confidence * 0.6 + agreement * 0.4).min(0.95);
+        let beta_overlap_factor = if short_beta_overlap {
+            SHORT_BETA_OVERLAP_CONFIDENCE_FACTOR
+        } else {
+            1.0
+        };
+        let confidence = (data_confidence * 0.6 + agreement * 0.4).min(0.95) * beta_overlap_factor;
 
         let reason = signals
             .iter()
@@ -1315,45 +2274,123 @@ impl QuantAnalysisEngine {
             .collect::<Vec<_>>()
             .join(", ");
 
-        let metrics = json!({
-            "sharpe_ratio": sharpe,
-            "sortino_ratio": sortino,
-            "volatility": volatility,
-            "max_drawdown": max_dd,
-            "beta": beta,
-            "win_rate": best_wr,
-            "best_strategy": best_strategy,
-            "momentum_win_rate": momentum_wr,
-            "mean_reversion_win_rate": mean_rev_wr,
-            "var_95": var,
-            "cvar_95": cvar,
-            "recent_return": recent_return * 100.0,
-            "risk_free_rate": risk_free_rate,
-            "hurst_exponent": hurst,
-            "hurst_regime": hurst_regime,
-            "autocorrelation_lag1": ac1,
-            "garch_forecast_vol": garch_vol,
-            "kelly_fraction": kelly,
-            "momentum_factor": momentum_factor,
-            "low_vol_factor_ratio": low_vol_factor,
-            "skewness": skewness,
-            "excess_kurtosis": kurtosis,
-            "beta_stability_shift": beta_stability,
-            "seasonality_avg_return": seasonality_signal,
-            "omega_ratio": omega_ratio,
-            "mean_reversion_half_life": mean_rev_half_life,
-            "jump_days": jump_days,
-            "jump_intensity": jump_intensity,
-            "rachev_ratio": rachev_ratio,
+        // Built field by field: as one json! literal it outgrows the macro recursion limit
+        let mut metrics_map = serde_json::Map::new();
+        metrics_map.insert("sharpe_ratio".to_string(), json!(sharpe));
+        metrics_map.insert("sortino_ratio".to_string(), json!(sortino));
+        metrics_map.insert("volatility".to_string(), json!(volatility));
+        metrics_map.insert("max_drawdown".to_string(), json!(max_dd));
+        metrics_map.insert(
+            "max_drawdown_duration_days".to_string(),
+            json!(max_drawdown_duration_days),
+        );
+        metrics_map.insert(
+            "max_drawdown_duration_bars".to_string(),
+            json!(drawdown.duration_bars),
+        );
+        metrics_map.insert(
+            "max_drawdown_recovered".to_string(),
+            json!(drawdown.recovered),
+        );
+        metrics_map.insert(
+            "max_drawdown_recovery_bars".to_string(),
+            json!(drawdown.recovery_index.map(|i| i - drawdown.trough_index)),
+        );
+        metrics_map.insert(
+            "current_drawdown_pct".to_string(),
+            json!(drawdown.current_drawdown_pct),
+        );
+        metrics_map.insert("ulcer_index".to_string(), json!(ulcer_index));
+        metrics_map.insert("martin_ratio".to_string(), json!(martin_ratio));
+        metrics_map.insert(
+            "currently_in_drawdown".to_string(),
+            json!(drawdown.current_drawdown_pct > 0.0),
+        );
+        metrics_map.insert("beta".to_string(), json!(beta));
+        metrics_map.insert("raw_beta".to_string(), json!(beta));
+        metrics_map.insert("adjusted_beta".to_string(), json!(adjusted_beta));
+        metrics_map.insert("beta_shrinkage".to_string(), json!(self.beta_shrinkage));
+        metrics_map.insert("beta_sample_size".to_string(), json!(beta_sample_size));
+        metrics_map.insert("downside_beta".to_string(), json!(downside_beta));
+        metrics_map.insert("sharpe_trend_slope".to_string(), json!(sharpe_trend_slope));
+        metrics_map.insert("upside_beta".to_string(), json!(upside_beta));
+        metrics_map.insert("treynor_ratio".to_string(), json!(treynor_ratio));
+        metrics_map.insert("information_ratio".to_string(), json!(information_ratio));
+        metrics_map.insert("win_rate".to_string(), json!(best_wr));
+        metrics_map.insert("best_strategy".to_string(), json!(best_strategy));
+        metrics_map.insert("momentum_win_rate".to_string(), json!(momentum_wr));
+        metrics_map.insert("mean_reversion_win_rate".to_string(), json!(mean_rev_wr));
+        metrics_map.insert("var_95".to_string(), json!(var));
+        metrics_map.insert("var_99".to_string(), json!(var_99));
+        metrics_map.insert("var_method".to_string(), json!(self.var_method.as_str()));
+        metrics_map.insert("cvar_95".to_string(), json!(cvar));
+        metrics_map.insert("cvar_99".to_string(), json!(cvar_99));
+        metrics_map.insert("recent_return".to_string(), json!(recent_return * 100.0));
+        metrics_map.insert("risk_free_rate".to_string(), json!(risk_free_rate));
+        metrics_map.insert("periods_per_year".to_string(), json!(periods_per_year));
+        metrics_map.insert("hurst_exponent".to_string(), json!(hurst));
+        metrics_map.insert("hurst_regime".to_string(), json!(hurst_regime));
+        metrics_map.insert(
+            "regime_momentum_conflict".to_string(),
+            json!(regime_momentum_conflict),
+        );
+        metrics_map.insert("autocorrelation_lag1".to_string(), json!(ac1));
+        metrics_map.insert("garch_forecast_vol".to_string(), json!(garch_vol));
+        metrics_map.insert("ewma_volatility".to_string(), json!(ewma_vol));
+        metrics_map.insert("ewma_lambda".to_string(), json!(self.ewma_lambda));
+        metrics_map.insert("ewma_realized_vol_ratio".to_string(), json!(ewma_vol_ratio));
+        metrics_map.insert("kelly_fraction".to_string(), json!(kelly));
+        metrics_map.insert("momentum_factor".to_string(), json!(momentum_factor));
+        metrics_map.insert("low_vol_factor_ratio".to_string(), json!(low_vol_factor));
+        metrics_map.insert("skewness".to_string(), json!(skewness));
+        metrics_map.insert("excess_kurtosis".to_string(), json!(kurtosis));
+        metrics_map.insert("beta_stability_shift".to_string(), json!(beta_stability));
+        metrics_map.insert("rolling_beta".to_string(), json!(rolling_beta_tail));
+        metrics_map.insert(
+            "seasonality_avg_return".to_string(),
+            json!(seasonality_signal),
+        );
+        metrics_map.insert("omega_ratio".to_string(), json!(omega_ratio));
+        metrics_map.insert(
+            "mean_reversion_half_life".to_string(),
+            json!(mean_rev_half_life),
+        );
+        metrics_map.insert(
+            "suspected_split_days".to_string(),
+            json!(suspected_split_days),
+        );
+        metrics_map.insert("jump_days".to_string(), json!(jump_days));
+        metrics_map.insert("jump_intensity".to_string(), json!(jump_intensity));
+        metrics_map.insert("jump_dates".to_string(), json!(jump_dates));
+        metrics_map.insert(
+            "jump_detection".to_string(),
+            json!(self.jump_detection.as_str()),
+        );
+        metrics_map.insert(
+            "jump_variance_ratio".to_string(),
+            json!(jump_variance_ratio),
+        );
+        metrics_map.insert("rachev_ratio".to_string(), json!(rachev_ratio));
+        metrics_map.insert("factor_attribution".to_string(), json!(factor_attribution));
+        let metrics = serde_json::Value::Object(metrics_map);
+
+        let confidence_components = json!({
+            "data_confidence": data_confidence,
+            "agreement": agreement,
+            "beta_overlap_factor": beta_overlap_factor,
+            "bullish_signals": bullish_count,
+            "bearish_signals": bearish_count,
+            "bar_count": bars.len(),
         });
 
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason,
             metrics,
+            confidence_components,
         })
     }
 
@@ -1530,7 +2567,86 @@ impl QuantAnalysisEngine {
         Some(serde_json::Value::Object(result))
     }
 
-    /// Extended analysis with factor ETF bars for Fama-French.
+    /// Engle-Granger cointegration test: regress A's closes on B's (aligned on
+    /// timestamp), then run an ADF test on the residual spread.
+    pub fn cointegration_test(
+        &self,
+        a: &[Bar],
+        b: &[Bar],
+    ) -> Result<CointegrationResult, AnalysisError> {
+        let b_closes: std::collections::HashMap<i64, f64> = b
+            .iter()
+            .map(|bar| (bar.timestamp.timestamp(), bar.close))
+            .collect();
+        let (ya, xb): (Vec<f64>, Vec<f64>) = a
+            .iter()
+            .filter_map(|bar| {
+                b_closes
+                    .get(&bar.timestamp.timestamp())
+                    .map(|&close_b| (bar.close, close_b))
+            })
+            .unzip();
+
+        let n = ya.len();
+        if n < MIN_COINTEGRATION_BARS {
+            return Err(AnalysisError::InsufficientData(format!(
+                "cointegration needs {} aligned bars, got {}",
+                MIN_COINTEGRATION_BARS, n
+            )));
+        }
+
+        // Step 1: OLS hedge ratio A = intercept + hedge_ratio * B
+        let mean_a = ya.iter().sum::<f64>() / n as f64;
+        let mean_b = xb.iter().sum::<f64>() / n as f64;
+        let cov: f64 = ya
+            .iter()
+            .zip(&xb)
+            .map(|(a, b)| (a - mean_a) * (b - mean_b))
+            .sum();
+        let var_b: f64 = xb.iter().map(|b| (b - mean_b).powi(2)).sum();
+        if var_b <= 0.0 {
+            return Err(AnalysisError::CalculationError(
+                "second series has no price variance".to_string(),
+            ));
+        }
+        let hedge_ratio = cov / var_b;
+        let intercept = mean_a - hedge_ratio * mean_b;
+
+        // Step 2: ADF on the spread
+        let spread: Vec<f64> = ya
+            .iter()
+            .zip(&xb)
+            .map(|(a, b)| a - hedge_ratio * b - intercept)
+            .collect();
+        // Residuals come from an estimated regression, so the stricter
+        // Engle-Granger critical value applies instead of the plain ADF one
+        let adf_statistic = augmented_dickey_fuller(&spread, COINTEGRATION_ADF_LAGS)
+            .statistic
+            .ok_or_else(|| {
+                AnalysisError::CalculationError("ADF regression on spread is singular".to_string())
+            })?;
+
+        let spread_std = adaptive::std_dev(&spread);
+        let spread_mean = spread.iter().sum::<f64>() / n as f64;
+        let spread_zscore = if spread_std > 0.0 {
+            (spread[n - 1] - spread_mean) / spread_std
+        } else {
+            0.0
+        };
+
+        Ok(CointegrationResult {
+            hedge_ratio,
+            intercept,
+            adf_statistic,
+            critical_value_5pct: ENGLE_GRANGER_CRITICAL_5PCT,
+            is_cointegrated: adf_statistic < ENGLE_GRANGER_CRITICAL_5PCT,
+            spread_zscore,
+            sample_size: n,
+        })
+    }
+
+    /// Extended analysis with factor ETF bars for Fama-French, annualizing
+    /// `timeframe` bars on `asset_class`'s trading calendar.
     #[allow(clippy::too_many_arguments)]
     pub fn analyze_with_factors(
         &self,
@@ -1541,9 +2657,16 @@ impl QuantAnalysisEngine {
         iwd_bars: Option<&[Bar]>,
         iwf_bars: Option<&[Bar]>,
         dynamic_risk_free_rate: Option<f64>,
+        timeframe: Timeframe,
+        asset_class: AssetClass,
     ) -> Result<AnalysisResult, AnalysisError> {
-        let mut result =
-            self.analyze_with_benchmark_and_rate(symbol, bars, spy_bars, dynamic_risk_free_rate)?;
+        let mut result = self.analyze_with_periods(
+            symbol,
+            bars,
+            spy_bars,
+            dynamic_risk_free_rate,
+            timeframe.periods_per_year_for(asset_class),
+        )?;
 
         // Compute Fama-French factors if SPY bars available
         if let Some(spy) = spy_bars {
@@ -1578,3 +2701,405 @@ impl Default for QuantAnalysisEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Deterministic random-walk bars (LCG noise) so runs are reproducible
+    fn synthetic_bars(n: usize) -> Vec<Bar> {
+        synthetic_bars_seeded(n, 42)
+    }
+
+    fn synthetic_bars_seeded(n: usize, mut seed: u64) -> Vec<Bar> {
+        let start = Utc.with_ymd_and_hms(2000, 1, 3, 0, 0, 0).unwrap();
+        let mut price = 100.0;
+        (0..n)
+            .map(|i| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let noise = ((seed >> 33) as f64 / (1u64 << 31) as f64) - 0.5;
+                price *= 1.0 + noise * 0.04 + 0.0003;
+                Bar {
+                    timestamp: start + Duration::days(i as i64),
+                    open: price,
+                    high: price * 1.01,
+                    low: price * 0.99,
+                    close: price,
+                    volume: 1_000_000.0,
+                    vwap: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling_series_parallel_matches_sequential_5000_bars() {
+        let engine = QuantAnalysisEngine::new();
+        let bars = synthetic_bars(5000);
+        let prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let returns = engine.calculate_returns(&prices);
+
+        let single = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let sequential = single.install(|| {
+            engine.compute_rolling_series(&prices, &returns, 0.045, DEFAULT_VAR_ALPHA, 252.0)
+        });
+        let parallel =
+            engine.compute_rolling_series(&prices, &returns, 0.045, DEFAULT_VAR_ALPHA, 252.0);
+
+        assert_eq!(sequential.vols.values(), parallel.vols.values());
+        assert_eq!(sequential.vars.values(), parallel.vars.values());
+        assert_eq!(sequential.cvars.values(), parallel.cvars.values());
+        assert_eq!(sequential.sharpes.values(), parallel.sharpes.values());
+        assert_eq!(sequential.drawdowns.values(), parallel.drawdowns.values());
+        assert_eq!(parallel.vols.len(), returns.len() - SHORT_WINDOW + 1);
+        assert_eq!(parallel.omegas.len(), returns.len() - LONG_WINDOW + 1);
+        assert_eq!(parallel.drawdowns.len(), prices.len() - LONG_WINDOW + 1);
+    }
+
+    #[test]
+    fn test_var_methods() {
+        let bars = synthetic_bars(500);
+        let prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let historical = QuantAnalysisEngine::new();
+        let returns = historical.calculate_returns(&prices);
+
+        let hist_var = historical.calculate_var(&returns, 0.05);
+        let gaussian = QuantAnalysisEngine::new().with_var_method(VarMethod::Gaussian);
+        let gauss_var = gaussian.calculate_var(&returns, 0.05);
+        let cornish = QuantAnalysisEngine::new().with_var_method(VarMethod::CornishFisher);
+        let cf_var = cornish.calculate_var(&returns, 0.05);
+
+        // Uniform noise is close enough to normal that all three should roughly agree
+        assert!(hist_var > 0.0 && gauss_var > 0.0 && cf_var > 0.0);
+        assert!((gauss_var - hist_var).abs() / hist_var < 0.5);
+        assert!((cf_var - gauss_var).abs() / gauss_var < 0.5);
+        assert!(gaussian.calculate_var(&returns, 0.01) > gauss_var);
+
+        let result = cornish
+            .analyze_with_benchmark_and_rate("TEST", &bars, None, None)
+            .unwrap();
+        assert_eq!(result.metrics["var_method"], "cornish_fisher");
+    }
+
+    #[test]
+    fn test_sanitize_returns_flags_reversed_spike() {
+        let engine = QuantAnalysisEngine::new();
+        let bars = synthetic_bars(200);
+        let mut prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        // One bad unadjusted print at double the price, corrected the next bar
+        prices[100] *= 2.0;
+        let returns = engine.calculate_returns(&prices);
+
+        let (sanitized, suspected) = engine.sanitize_returns(&returns);
+        assert_eq!(suspected, 1);
+        assert!(sanitized[99] < returns[99] * 0.5);
+        assert!(sanitized[100] > returns[100]);
+
+        let (flagged_only, suspected) = QuantAnalysisEngine::new()
+            .with_split_winsorizing(false)
+            .sanitize_returns(&returns);
+        assert_eq!(suspected, 1);
+        assert_eq!(flagged_only, returns);
+
+        // A clean series is left untouched
+        let clean = engine.calculate_returns(&bars.iter().map(|b| b.close).collect::<Vec<_>>());
+        assert_eq!(engine.sanitize_returns(&clean).1, 0);
+    }
+
+    #[test]
+    fn test_analyze_5000_bars() {
+        let engine = QuantAnalysisEngine::new();
+        let bars = synthetic_bars(5000);
+        let result = engine
+            .analyze_with_benchmark_and_rate("TEST", &bars, None, None)
+            .unwrap();
+        assert!(result.metrics["var_95"].as_f64().unwrap() > 0.0);
+
+        // Bucket contributions reconcile with the overall normalized score
+        let attribution = result.metrics["factor_attribution"].as_object().unwrap();
+        let total: f64 = attribution
+            .values()
+            .map(|b| b["contribution"].as_f64().unwrap())
+            .sum();
+        let signal_count: u64 = attribution
+            .values()
+            .map(|b| b["signal_count"].as_u64().unwrap())
+            .sum();
+        assert!(total.abs() <= 100.0 + 1e-9);
+        assert_eq!(signal_count as usize, result.reason.split(", ").count());
+        assert!(result.confidence > 0.0);
+
+        let attribution = factor_attribution(&[("Volatility Building", 1, false)], 1);
+        assert_eq!(attribution["volatility_risk"]["signal_count"], 1);
+        assert_eq!(attribution["other"]["signal_count"], 0);
+        let attribution = factor_attribution(&[("Asymmetric Downside Risk", 1, false)], 1);
+        assert_eq!(attribution["tail_risk"]["signal_count"], 1);
+        assert_eq!(attribution["other"]["signal_count"], 0);
+        let attribution =
+            factor_attribution(&[("Risk-Adjusted Performance Deteriorating", 1, false)], 1);
+        assert_eq!(attribution["volatility_risk"]["signal_count"], 1);
+        assert_eq!(attribution["other"]["signal_count"], 0);
+    }
+
+    #[test]
+    fn test_cointegration_test() {
+        let engine = QuantAnalysisEngine::new();
+        let b = synthetic_bars_seeded(500, 7);
+
+        // A tracks 2x B plus a stationary, alternating spread
+        let a: Vec<Bar> = b
+            .iter()
+            .enumerate()
+            .map(|(i, bar)| {
+                let noise = if i % 2 == 0 { 0.5 } else { -0.5 } + (i % 7) as f64 * 0.1;
+                Bar {
+                    close: 2.0 * bar.close + 5.0 + noise,
+                    ..bar.clone()
+                }
+            })
+            .collect();
+        let result = engine.cointegration_test(&a, &b).unwrap();
+        assert!((result.hedge_ratio - 2.0).abs() < 0.05);
+        assert!(result.is_cointegrated, "adf = {}", result.adf_statistic);
+
+        // Two independent random walks should not be cointegrated
+        let c = synthetic_bars_seeded(500, 12345);
+        let result = engine.cointegration_test(&c, &b).unwrap();
+        assert!(!result.is_cointegrated, "adf = {}", result.adf_statistic);
+
+        assert!(engine.cointegration_test(&a[..10], &b[..10]).is_err());
+    }
+
+    #[test]
+    fn test_augmented_dickey_fuller() {
+        // Random-walk prices have a unit root
+        let walk: Vec<f64> = synthetic_bars(500).iter().map(|b| b.close).collect();
+        let result = augmented_dickey_fuller(&walk, 1);
+        assert!(!result.is_stationary);
+        assert_eq!(result.interpretation, "non_stationary");
+
+        // Their returns do not
+        let engine = QuantAnalysisEngine::new();
+        let returns = engine.calculate_returns(&walk);
+        let result = augmented_dickey_fuller(&returns, 1);
+        assert!(result.is_stationary, "adf = {:?}", result.statistic);
+        assert_eq!(result.interpretation, "stationary");
+
+        let result = augmented_dickey_fuller(&[1.0, 2.0, 3.0], 1);
+        assert!(result.statistic.is_none());
+        assert_eq!(result.interpretation, "insufficient_data");
+    }
+
+    #[test]
+    fn test_ewma_volatility_reacts_to_recent_shocks() {
+        let engine = QuantAnalysisEngine::new();
+        let mut returns = [0.005, -0.005].repeat(100);
+        let calm = engine.calculate_ewma_volatility(&returns, DEFAULT_EWMA_LAMBDA, 252.0);
+        assert!((calm - 0.005 * 252f64.sqrt() * 100.0).abs() < 0.01);
+
+        returns.extend([0.04, -0.04, 0.04, -0.04, 0.04]);
+        let shocked = engine.calculate_ewma_volatility(&returns, DEFAULT_EWMA_LAMBDA, 252.0);
+        let realized = engine.calculate_volatility(&returns, 252.0);
+        assert!(shocked > realized * EWMA_VOL_BUILDING_RATIO);
+        assert_eq!(engine.calculate_ewma_volatility(&[], 0.94, 252.0), 0.0);
+    }
+
+    #[test]
+    fn test_conditional_betas() {
+        let engine = QuantAnalysisEngine::new();
+        let bench: Vec<f64> = (0..200)
+            .map(|i| if i % 2 == 0 { 0.01 } else { -0.01 } * (1.0 + (i % 7) as f64 / 10.0))
+            .collect();
+        // Falls twice as hard as the market, rallies only half as much
+        let stock: Vec<f64> = bench
+            .iter()
+            .map(|&b| if b < 0.0 { 2.0 * b } else { 0.5 * b })
+            .collect();
+
+        let (down, up) = engine.calculate_conditional_betas(&stock, &bench);
+        assert!((down.unwrap() - 2.0).abs() < 1e-9);
+        assert!((up.unwrap() - 0.5).abs() < 1e-9);
+
+        let (down, up) = engine.calculate_conditional_betas(&stock[..20], &bench[..20]);
+        assert!(down.is_none() && up.is_none());
+    }
+
+    #[test]
+    fn test_align_benchmark_returns_by_timestamp() {
+        let engine = QuantAnalysisEngine::new();
+        let bench = synthetic_bars_seeded(200, 7);
+        // Recent listing: the stock only has the last 50 benchmark sessions,
+        // minus one missing session in the middle
+        let mut bars = synthetic_bars(200)[150..].to_vec();
+        bars.remove(20);
+        let prices: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let returns = engine.calculate_returns(&prices);
+
+        let (stock, spy) = align_benchmark_returns(&bars, &returns, &bench);
+        assert_eq!(stock.len(), returns.len());
+        assert_eq!(stock, returns);
+        // The return spanning the gap is measured over the same two sessions
+        let expected = bench[171].close / bench[169].close - 1.0;
+        assert!((spy[19] - expected).abs() < 1e-12);
+        assert!((spy[0] - (bench[151].close / bench[150].close - 1.0)).abs() < 1e-12);
+
+        let (stock, _) = align_benchmark_returns(&bars, &returns, &bench[..160]);
+        assert_eq!(stock.len(), 9);
+    }
+
+    #[test]
+    fn test_zero_price_bar_mid_series() {
+        let engine = QuantAnalysisEngine::new();
+        let returns = engine.calculate_returns(&[100.0, 0.0, 102.0, f64::NAN, 103.0]);
+        assert_eq!(returns, vec![0.0, 0.0, 0.0, 0.0]);
+
+        let mut bars = synthetic_bars(300);
+        bars[150].close = 0.0;
+        let result = engine
+            .analyze_with_benchmark_and_rate("TEST", &bars, None, None)
+            .unwrap();
+        for key in ["sharpe_ratio", "volatility", "var_95", "hurst_exponent"] {
+            assert!(result.metrics[key].as_f64().unwrap().is_finite(), "{key}");
+        }
+
+        for bar in bars.iter_mut().step_by(10) {
+            bar.close = 0.0;
+        }
+        assert!(matches!(
+            engine.analyze_with_benchmark_and_rate("TEST", &bars, None, None),
+            Err(AnalysisError::InsufficientData(_))
+        ));
+    }
+
+    #[test]
+    fn test_detect_jumps() {
+        // Calm regime with one jump, then a volatile regime with no jumps
+        let mut returns: Vec<f64> = (0..200)
+            .map(|i| {
+                let scale = if i < 100 { 0.005 } else { 0.03 };
+                if i % 2 == 0 {
+                    scale
+                } else {
+                    -scale
+                }
+            })
+            .collect();
+        returns[50] = 0.06;
+
+        let zscore = QuantAnalysisEngine::new();
+        let (jumps, intensity) = zscore.detect_jumps(&returns, DEFAULT_JUMP_THRESHOLD_SIGMA);
+        assert!(jumps.is_empty() && intensity == 0.0);
+
+        let bipower =
+            QuantAnalysisEngine::new().with_jump_detection(JumpDetection::BipowerVariation);
+        let (jumps, _) = bipower.detect_jumps(&returns, DEFAULT_JUMP_THRESHOLD_SIGMA);
+        assert_eq!(jumps.first(), Some(&50));
+        assert!(jumps
+            .iter()
+            .all(|&i| i == 50 || (100..100 + BIPOWER_WINDOW).contains(&i)));
+        assert!(bipower.jump_variance_ratio(&returns).unwrap() >= 0.0);
+    }
+
+    #[test]
+    fn test_drawdown_stats() {
+        let engine = QuantAnalysisEngine::new();
+        let prices = [100.0, 110.0, 99.0, 88.0, 95.0, 111.0, 105.0];
+        let stats = engine.calculate_drawdown_stats(&prices);
+        assert!((stats.max_drawdown_pct - 20.0).abs() < 1e-9);
+        assert_eq!((stats.peak_index, stats.trough_index), (1, 3));
+        assert_eq!(stats.recovery_index, Some(5));
+        assert_eq!(stats.duration_bars, 4);
+        assert!(stats.recovered);
+        assert!((stats.current_drawdown_pct - 6.0 / 111.0 * 100.0).abs() < 1e-9);
+
+        let stats = engine.calculate_drawdown_stats(&prices[..5]);
+        assert!(!stats.recovered);
+        assert_eq!(stats.duration_bars, 3);
+        assert_eq!(engine.calculate_max_drawdown(&prices), 20.0);
+    }
+
+    #[test]
+    fn test_drawdown_stats_monotonic_series() {
+        let engine = QuantAnalysisEngine::new();
+        let stats = engine.calculate_drawdown_stats(&[100.0, 101.0, 103.0, 106.0, 110.0]);
+        assert_eq!(stats.max_drawdown_pct, 0.0);
+        assert_eq!(stats.duration_bars, 0);
+        assert!(stats.recovered);
+        assert_eq!(stats.recovery_index, None);
+        assert_eq!(stats.current_drawdown_pct, 0.0);
+    }
+
+    #[test]
+    fn test_ulcer_index() {
+        let engine = QuantAnalysisEngine::new();
+        assert_eq!(engine.calculate_ulcer_index(&[100.0, 101.0, 102.0]), 0.0);
+        // Drawdowns of 0%, 10%, 0% -> sqrt(100 / 3)
+        let ui = engine.calculate_ulcer_index(&[100.0, 90.0, 100.0]);
+        assert!((ui - (100.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        // A longer stay underwater hurts more at the same depth
+        assert!(engine.calculate_ulcer_index(&[100.0, 90.0, 90.0, 100.0]) > ui);
+    }
+
+    #[test]
+    fn test_trend_slope() {
+        assert!((trend_slope(&[3.0, 2.5, 2.0, 1.5]).unwrap() + 0.5).abs() < 1e-12);
+        assert_eq!(trend_slope(&[1.0, 1.0, 1.0]), Some(0.0));
+        assert_eq!(trend_slope(&[1.0]), None);
+    }
+
+    #[test]
+    fn test_rolling_beta() {
+        let engine = QuantAnalysisEngine::new();
+        let bench: Vec<f64> = synthetic_bars(200)
+            .windows(2)
+            .map(|w| w[1].close / w[0].close - 1.0)
+            .collect();
+        // Beta 1 for the first half, 2 for the second
+        let stock: Vec<f64> = bench
+            .iter()
+            .enumerate()
+            .map(|(i, r)| if i < 100 { *r } else { 2.0 * r })
+            .collect();
+
+        let rolling = engine.calculate_rolling_beta(&stock, &bench, 30);
+        assert_eq!(rolling.len(), bench.len() - 30 + 1);
+        assert!((rolling[0] - 1.0).abs() < 1e-9);
+        assert!((rolling[rolling.len() - 1] - 2.0).abs() < 1e-9);
+        assert!(engine
+            .calculate_rolling_beta(&stock[..10], &bench, 30)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_regime_momentum_conflict() {
+        // Mean-reverting regime (bearish on the rally) vs stronger positive momentum
+        let mut signals = vec![
+            ("Positive Momentum", 3, true),
+            ("Low VaR Risk", 1, true),
+            ("Mean-Reverting Market (Hurst)", 2, false),
+        ];
+        assert!(reconcile_regime_momentum(&mut signals, Some(0), Some(2)));
+        assert_eq!(
+            signals,
+            vec![
+                ("Regime/Momentum Conflict", 2, true),
+                ("Low VaR Risk", 1, true)
+            ]
+        );
+
+        // Agreeing signals are left alone
+        let mut signals = vec![
+            ("Positive Momentum", 2, true),
+            ("Trending Market (Hurst)", 2, true),
+        ];
+        assert!(!reconcile_regime_momentum(&mut signals, Some(0), Some(1)));
+        assert_eq!(signals.len(), 2);
+        assert!(!reconcile_regime_momentum(&mut signals, None, Some(1)));
+    }
+}
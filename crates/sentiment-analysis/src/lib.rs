@@ -1,10 +1,12 @@
 use analysis_core::{
-    adaptive, AnalysisError, AnalysisResult, NewsArticle, SentimentAnalyzer, SignalStrength,
+    adaptive, AnalysisError, AnalysisResult, Clock, NewsArticle, SentimentAnalyzer, SignalStrength,
+    SystemClock,
 };
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 pub mod velocity;
 pub use velocity::{
@@ -36,6 +38,195 @@ const NEGATION_WORDS: &[&str] = &[
 
 const NEGATION_WINDOW: usize = 3;
 
+/// Finance-relevant emoji common in social-media posts, scored like lexicon words
+const EMOJI_SCORES: &[(char, i32)] = &[('🚀', 1), ('📈', 1), ('📉', -1), ('💎', 1)];
+
+/// FinBERT predictions below this confidence are ignored in favour of the lexicon
+const FINBERT_MIN_CONFIDENCE: f64 = 0.55;
+
+/// Confidence assigned to a lexicon-only score
+const LEXICON_CONFIDENCE: f64 = 0.5;
+
+/// FinBERT's [-1, 1] directional score is scaled by this to match the word-list range
+const FINBERT_SCALE: f64 = 3.0;
+
+/// Signed FinBERT score on the lexicon scale (positive label > 0, negative < 0, neutral 0)
+fn finbert_directional_score(prediction: &ml_client::sentiment::SentimentPrediction) -> f64 {
+    let direction = match prediction.label.as_str() {
+        "positive" => 1.0,
+        "negative" => -1.0,
+        _ => 0.0,
+    };
+    prediction.score * direction * FINBERT_SCALE
+}
+
+/// Blend a lexicon score with an optional FinBERT prediction, returning
+/// `(blended_score, confidence)`.
+///
+/// Weighting rule: when FinBERT is missing or its confidence is below
+/// `FINBERT_MIN_CONFIDENCE`, the lexicon score is used as-is with
+/// `LEXICON_CONFIDENCE`. Otherwise FinBERT gets weight `w = confidence` and the
+/// lexicon `1 - w`, and the returned confidence is the same mix of FinBERT's
+/// confidence and `LEXICON_CONFIDENCE`.
+pub fn blend_sentiment(
+    lexicon_score: f64,
+    finbert_result: Option<&ml_client::sentiment::SentimentPrediction>,
+) -> (f64, f64) {
+    match finbert_result {
+        Some(p) if p.confidence.is_finite() && p.confidence >= FINBERT_MIN_CONFIDENCE => {
+            let w = p.confidence.min(1.0);
+            let blended = w * finbert_directional_score(p) + (1.0 - w) * lexicon_score;
+            (blended, w * w + (1.0 - w) * LEXICON_CONFIDENCE)
+        }
+        _ => (lexicon_score, LEXICON_CONFIDENCE),
+    }
+}
+
+/// Default half-life for the exponential recency decay applied to each article
+const DEFAULT_RECENCY_HALF_LIFE_HOURS: f64 = 48.0;
+
+/// Multiplier applied to a text's score when it carries a cashtag for the analyzed symbol
+const CASHTAG_RELEVANCE_BOOST: f64 = 1.25;
+
+/// Lexicon form of a user-supplied term: its tokens joined by single spaces,
+/// or `None` when nothing scoreable is left
+fn normalize_term(term: &str) -> Option<String> {
+    let tokens = tokenize(term);
+    (!tokens.is_empty()).then(|| tokens.join(" "))
+}
+
+/// Split lowercased text into tokens. Cashtags (`$aapl`) survive as single
+/// tokens and each scored emoji becomes its own token even when glued to a
+/// word, so the negation window counts it like any other word.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.to_lowercase().chars() {
+        let is_emoji = EMOJI_SCORES.iter().any(|(e, _)| *e == c);
+        // Variation selectors / zero-width joiners trail emoji and carry no meaning
+        // Share-class dots stay inside cashtags ($brk.b); trailing ones are trimmed below
+        let in_cashtag = current.starts_with('$');
+        let is_separator = c.is_whitespace()
+            || (c == '.' && !in_cashtag)
+            || matches!(c, ',' | ';' | '!' | '?' | ':' | '(' | ')' | '"')
+            || matches!(c, '\u{fe0f}' | '\u{200d}');
+        if is_emoji || is_separator {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if is_emoji {
+                tokens.push(c.to_string());
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    for token in tokens.iter_mut().filter(|t| t.starts_with('$')) {
+        while token.ends_with('.') {
+            token.pop();
+        }
+    }
+    // A bare "$" carries nothing — drop lone dollar signs
+    tokens.retain(|t| t != "$");
+    tokens
+}
+
+/// Ticker named by a cashtag token (`$aapl` -> `aapl`), if the token is one
+fn cashtag_symbol(token: &str) -> Option<&str> {
+    let sym = token.strip_prefix('$')?;
+    let valid = !sym.is_empty()
+        && sym.len() <= 6
+        && sym.starts_with(|c: char| c.is_ascii_alphabetic())
+        && sym.chars().all(|c| c.is_ascii_alphabetic() || c == '.');
+    valid.then_some(sym)
+}
+
+/// Title token Jaccard similarity at or above which two articles count as the same story
+const DUPLICATE_TITLE_SIMILARITY: f64 = 0.6;
+
+/// Republished copies are only collapsed when published within this many hours of each other
+const DUPLICATE_WINDOW_HOURS: i64 = 24;
+
+/// Normalized title tokens used for near-duplicate detection (lowercase
+/// alphanumerics, very short words dropped)
+fn title_tokens(title: &str) -> HashSet<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(str::to_string)
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Default publisher reputation weights, matched case-insensitively as a
+/// substring of the article author/source (major wires 1.5, mainstream
+/// financial press 1.2, known low-quality outlets 0.6; anything else 1.0).
+const DEFAULT_SOURCE_WEIGHTS: &[(&str, f64)] = &[
+    ("reuters", 1.5),
+    ("bloomberg", 1.5),
+    ("associated press", 1.5),
+    ("dow jones", 1.5),
+    ("wall street journal", 1.2),
+    ("wsj", 1.2),
+    ("financial times", 1.2),
+    ("cnbc", 1.2),
+    ("barron's", 1.2),
+    ("marketwatch", 1.2),
+    ("the economist", 1.2),
+    ("forbes", 1.2),
+    ("yahoo finance", 1.2),
+    ("investor's business daily", 1.2),
+    ("seeking alpha", 0.6),
+    ("benzinga", 0.6),
+    ("zacks", 0.6),
+    ("investorplace", 0.6),
+    ("the motley fool", 0.6),
+    ("motley fool", 0.6),
+    ("247wallst", 0.6),
+    ("24/7 wall st", 0.6),
+    ("globenewswire", 0.6),
+    ("accesswire", 0.6),
+];
+
+/// Publisher reputation weight for an author or source name using the default table.
+pub fn source_weight(author_or_source: &str) -> f64 {
+    lookup_source_weight(
+        DEFAULT_SOURCE_WEIGHTS.iter().map(|(k, v)| (*k, *v)),
+        author_or_source,
+    )
+}
+
+/// Default publisher weight table, suitable as a starting point for
+/// [`SentimentAnalysisEngine::with_source_weights`].
+pub fn default_source_weights() -> HashMap<String, f64> {
+    DEFAULT_SOURCE_WEIGHTS
+        .iter()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect()
+}
+
+/// Longest matching key wins so "motley fool" and "the motley fool" can't
+/// disagree depending on map iteration order.
+fn lookup_source_weight<'a>(table: impl Iterator<Item = (&'a str, f64)>, name: &str) -> f64 {
+    let name_lower = name.to_lowercase();
+    table
+        .filter(|(key, _)| !key.is_empty() && name_lower.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, weight)| weight)
+        .unwrap_or(1.0)
+}
+
 /// News event type with importance weight for signal generation
 #[derive(Debug, Clone, Copy)]
 enum NewsEventType {
@@ -138,11 +329,125 @@ fn classify_event(title: &str, description: Option<&str>) -> NewsEventType {
     }
 }
 
+/// Built-in positive terms with their intensity. 1.0 is a typical directional
+/// word; strong language ("surge", "breakthrough") scores higher and hedged or
+/// ambiguous words ("high", "guidance") lower.
+const POSITIVE_LEXICON: &[(&str, f64)] = &[
+    ("bullish", 1.25),
+    ("rally", 1.0),
+    ("surge", 1.5),
+    ("gain", 1.0),
+    ("profit", 1.0),
+    ("growth", 1.0),
+    ("beat", 1.25),
+    ("upgrade", 1.25),
+    ("outperform", 1.25),
+    ("strong", 1.0),
+    ("positive", 0.75),
+    ("rise", 0.75),
+    ("increase", 0.75),
+    ("breakthrough", 1.5),
+    ("innovation", 0.75),
+    ("success", 1.0),
+    ("exceed", 1.25),
+    ("momentum", 0.75),
+    ("buy", 1.0),
+    ("recommend", 0.75),
+    ("optimistic", 1.0),
+    ("record", 1.25),
+    ("high", 0.5),
+    ("advance", 0.75),
+    // Financial-specific terms
+    ("dividend", 0.5),
+    ("buyback", 1.0),
+    ("repurchase", 1.0),
+    ("accretive", 1.0),
+    ("upside", 1.0),
+    ("recovery", 1.0),
+    ("rebound", 1.0),
+    ("expansion", 0.75),
+    ("robust", 1.0),
+    ("accelerating", 1.0),
+    ("overweight", 1.0),
+    ("raised", 1.0),
+    ("guidance", 0.5),
+    ("upgraded", 1.25),
+    ("initiated", 0.5),
+    ("reiterated", 0.5),
+    ("outpacing", 1.0),
+    ("tailwind", 1.0),
+];
+
+/// Built-in negative terms with their intensity (magnitudes; scored as negative)
+const NEGATIVE_LEXICON: &[(&str, f64)] = &[
+    ("bearish", 1.25),
+    ("decline", 1.0),
+    ("loss", 1.0),
+    ("fall", 0.75),
+    ("plunge", 1.75),
+    ("crash", 2.0),
+    ("miss", 1.25),
+    ("downgrade", 1.25),
+    ("underperform", 1.25),
+    ("weak", 1.0),
+    ("soft", 0.5),
+    ("negative", 0.75),
+    ("drop", 1.0),
+    ("decrease", 0.75),
+    ("concern", 0.5),
+    ("risk", 0.5),
+    ("fail", 1.25),
+    ("disappoint", 1.25),
+    ("slump", 1.5),
+    ("sell", 1.0),
+    ("warning", 1.0),
+    ("pessimistic", 1.0),
+    ("low", 0.5),
+    ("retreat", 0.75),
+    ("fear", 1.0),
+    ("trouble", 1.0),
+    // Financial-specific terms
+    ("dilution", 1.0),
+    ("dilutive", 1.0),
+    ("headwind", 1.0),
+    ("lawsuit", 1.25),
+    ("litigation", 1.0),
+    ("recall", 1.25),
+    ("investigation", 1.25),
+    ("probe", 1.0),
+    ("default", 1.75),
+    ("bankruptcy", 2.0),
+    ("restructuring", 1.0),
+    ("layoff", 1.0),
+    ("downside", 1.0),
+    ("overvalued", 1.0),
+    ("bubble", 1.0),
+    ("underweight", 1.0),
+    ("lowered", 1.0),
+    ("suspended", 1.25),
+];
+
+/// Weight given to custom lexicon terms added without an explicit intensity
+const DEFAULT_TERM_WEIGHT: f64 = 1.0;
+
+fn lexicon(entries: &[(&str, f64)]) -> Vec<(String, f64)> {
+    entries.iter().map(|&(w, s)| (w.to_string(), s)).collect()
+}
+
+#[derive(Clone)]
 pub struct SentimentAnalysisEngine {
-    positive_words: Vec<&'static str>,
-    negative_words: Vec<&'static str>,
+    /// (term, intensity) pairs, terms normalized by `normalize_term`;
+    /// multi-word entries match as phrases
+    positive_words: Vec<(String, f64)>,
+    negative_words: Vec<(String, f64)>,
     /// Optional FinBERT ML client for NLP-based sentiment
     finbert_client: Option<ml_client::SentimentClient>,
+    /// Publisher reputation weights keyed by lowercase author/source substring
+    source_weights: HashMap<String, f64>,
+    /// Half-life (hours) of the `exp(-lambda * age)` recency decay
+    recency_half_life_hours: f64,
+    /// Time source for result timestamps, recency decay and buzz windows
+    clock: Arc<dyn Clock>,
 }
 
 impl SentimentAnalysisEngine {
@@ -154,157 +459,255 @@ impl SentimentAnalysisEngine {
             .map(|url| ml_client::SentimentClient::new(url, std::time::Duration::from_secs(5)));
 
         Self {
-            positive_words: vec![
-                "bullish",
-                "rally",
-                "surge",
-                "gain",
-                "profit",
-                "growth",
-                "beat",
-                "upgrade",
-                "outperform",
-                "strong",
-                "positive",
-                "rise",
-                "increase",
-                "breakthrough",
-                "innovation",
-                "success",
-                "exceed",
-                "momentum",
-                "buy",
-                "recommend",
-                "optimistic",
-                "record",
-                "high",
-                "advance",
-                // Financial-specific terms
-                "dividend",
-                "buyback",
-                "repurchase",
-                "accretive",
-                "upside",
-                "recovery",
-                "rebound",
-                "expansion",
-                "robust",
-                "accelerating",
-                "overweight",
-                "raised",
-                "guidance",
-                "upgraded",
-                "initiated",
-                "reiterated",
-                "outpacing",
-                "tailwind",
-            ],
-            negative_words: vec![
-                "bearish",
-                "decline",
-                "loss",
-                "fall",
-                "plunge",
-                "crash",
-                "miss",
-                "downgrade",
-                "underperform",
-                "weak",
-                "negative",
-                "drop",
-                "decrease",
-                "concern",
-                "risk",
-                "fail",
-                "disappoint",
-                "slump",
-                "sell",
-                "warning",
-                "pessimistic",
-                "low",
-                "retreat",
-                "fear",
-                "trouble",
-                // Financial-specific terms
-                "dilution",
-                "dilutive",
-                "headwind",
-                "lawsuit",
-                "litigation",
-                "recall",
-                "investigation",
-                "probe",
-                "default",
-                "bankruptcy",
-                "restructuring",
-                "layoff",
-                "downside",
-                "overvalued",
-                "bubble",
-                "underweight",
-                "lowered",
-                "suspended",
-            ],
+            positive_words: lexicon(POSITIVE_LEXICON),
+            negative_words: lexicon(NEGATIVE_LEXICON),
             finbert_client,
+            source_weights: default_source_weights(),
+            recency_half_life_hours: DEFAULT_RECENCY_HALF_LIFE_HOURS,
+            clock: Arc::new(SystemClock),
         }
     }
 
-    fn analyze_text(&self, text: &str) -> f64 {
-        let text_lower = text.to_lowercase();
-        // Split into words, stripping common punctuation
-        let words: Vec<&str> = text_lower
-            .split(|c: char| {
-                c.is_whitespace() || c == ',' || c == ';' || c == '.' || c == '!' || c == '?'
-            })
-            .filter(|w| !w.is_empty())
+    /// Set the half-life (hours) of the recency decay; non-positive values are ignored
+    pub fn with_recency_half_life(mut self, hours: f64) -> Self {
+        if hours > 0.0 {
+            self.recency_half_life_hours = hours;
+        }
+        self
+    }
+
+    /// Inject the time source, e.g. a `FixedClock` for reproducible output
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Extend the built-in word lists with domain terms ("tape-out", "phase 3
+    /// readout") at the default intensity of 1.0. Terms are matched
+    /// case-insensitively, multi-word terms as whole phrases, and both respect
+    /// the negation window.
+    pub fn with_custom_lexicon(mut self, positive: Vec<String>, negative: Vec<String>) -> Self {
+        for term in &positive {
+            self.add_positive_term(term);
+        }
+        for term in &negative {
+            self.add_negative_term(term);
+        }
+        self
+    }
+
+    /// Score `term` as positive from the next analysis on, moving it out of the
+    /// negative list if it was there
+    pub fn add_positive_term(&mut self, term: &str) {
+        self.add_term_with_weight(term, DEFAULT_TERM_WEIGHT);
+    }
+
+    /// Score `term` as negative from the next analysis on, moving it out of the
+    /// positive list if it was there
+    pub fn add_negative_term(&mut self, term: &str) {
+        self.add_term_with_weight(term, -DEFAULT_TERM_WEIGHT);
+    }
+
+    /// Score `term` with a signed intensity (e.g. 2.0 for "short squeeze",
+    /// -1.75 for "going concern"), replacing any existing entry for it
+    pub fn add_term_with_weight(&mut self, term: &str, weight: f64) {
+        if !weight.is_finite() || weight == 0.0 {
+            return;
+        }
+        if let Some(term) = normalize_term(term) {
+            self.positive_words.retain(|(w, _)| *w != term);
+            self.negative_words.retain(|(w, _)| *w != term);
+            if weight > 0.0 {
+                self.positive_words.push((term, weight));
+            } else {
+                self.negative_words.push((term, -weight));
+            }
+        }
+    }
+
+    /// Stop scoring `term` (built-in or custom). Returns whether it was in a list.
+    pub fn remove_term(&mut self, term: &str) -> bool {
+        let Some(term) = normalize_term(term) else {
+            return false;
+        };
+        let before = self.positive_words.len() + self.negative_words.len();
+        self.positive_words.retain(|(w, _)| *w != term);
+        self.negative_words.retain(|(w, _)| *w != term);
+        self.positive_words.len() + self.negative_words.len() < before
+    }
+
+    /// Recency weight `exp(-lambda * age_hours)` with `lambda = ln 2 / half_life`.
+    /// Articles timestamped in the future count as brand new.
+    fn decay_weight(&self, age_hours: f64) -> f64 {
+        let lambda = std::f64::consts::LN_2 / self.recency_half_life_hours;
+        (-lambda * age_hours.max(0.0)).exp()
+    }
+
+    /// Replace the publisher reputation table used to weight each article
+    pub fn with_source_weights(mut self, source_weights: HashMap<String, f64>) -> Self {
+        self.source_weights = source_weights;
+        self
+    }
+
+    /// Collapse republished near-identical stories, keeping the copy from the
+    /// highest-reputation source. Returns the surviving articles in their
+    /// original order and the number removed.
+    pub fn dedup_articles(&self, news: &[NewsArticle]) -> (Vec<NewsArticle>, usize) {
+        let tokens: Vec<HashSet<String>> = news.iter().map(|a| title_tokens(&a.title)).collect();
+        let weights: Vec<f64> = news
+            .iter()
+            .map(|a| self.calculate_source_weight(a))
             .collect();
 
-        let positive_set: HashSet<&str> = self.positive_words.iter().copied().collect();
-        let negative_set: HashSet<&str> = self.negative_words.iter().copied().collect();
+        // Visit the most reputable copies first so they win their duplicate group
+        let mut order: Vec<usize> = (0..news.len()).collect();
+        order.sort_by(|&a, &b| {
+            weights[b]
+                .partial_cmp(&weights[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut kept: Vec<usize> = Vec::with_capacity(news.len());
+        for i in order {
+            let is_duplicate = kept.iter().any(|&k| {
+                (news[i].published_utc - news[k].published_utc)
+                    .num_hours()
+                    .abs()
+                    <= DUPLICATE_WINDOW_HOURS
+                    && jaccard(&tokens[i], &tokens[k]) >= DUPLICATE_TITLE_SIMILARITY
+            });
+            if !is_duplicate {
+                kept.push(i);
+            }
+        }
+        kept.sort_unstable();
+
+        let removed = news.len() - kept.len();
+        (kept.into_iter().map(|i| news[i].clone()).collect(), removed)
+    }
+
+    /// Publisher reputation weight for an article (1.0 when the author is unknown)
+    fn calculate_source_weight(&self, article: &NewsArticle) -> f64 {
+        match article.author.as_deref() {
+            Some(author) => lookup_source_weight(
+                self.source_weights.iter().map(|(k, v)| (k.as_str(), *v)),
+                author,
+            ),
+            None => 1.0,
+        }
+    }
+
+    fn analyze_text(&self, text: &str) -> f64 {
+        self.analyze_text_for_symbol(text, None)
+    }
+
+    /// Lexicon score for `text`, boosted when it carries a cashtag for `symbol`
+    fn analyze_text_for_symbol(&self, text: &str, symbol: Option<&str>) -> f64 {
+        let words = tokenize(text);
+
+        // Signed intensity per term; multi-word terms are matched separately as phrases
+        let signed_terms = self
+            .positive_words
+            .iter()
+            .map(|(w, weight)| (w.as_str(), *weight))
+            .chain(
+                self.negative_words
+                    .iter()
+                    .map(|(w, weight)| (w.as_str(), -weight)),
+            );
+        let (phrases, single_words): (Vec<_>, Vec<_>) =
+            signed_terms.partition(|(w, _)| w.contains(' '));
+        let word_weights: HashMap<&str, f64> = single_words.into_iter().collect();
         let negation_set: HashSet<&str> = NEGATION_WORDS.iter().copied().collect();
 
         // Track positions of negation words
         let negation_positions: Vec<usize> = words
             .iter()
             .enumerate()
-            .filter(|(_, w)| negation_set.contains(*w))
+            .filter(|(_, w)| negation_set.contains(w.as_str()))
             .map(|(i, _)| i)
             .collect();
 
-        let mut score: i32 = 0;
+        let is_negated = |start: usize| {
+            negation_positions
+                .iter()
+                .any(|&neg_pos| neg_pos < start && (start - neg_pos) <= NEGATION_WINDOW)
+        };
+
+        let mut score = 0.0;
+        let mut mentions_symbol = false;
+
+        // Multi-word terms first; their words aren't scored again individually
+        let mut in_phrase = vec![false; words.len()];
+        for (phrase, phrase_score) in phrases {
+            let parts: Vec<&str> = phrase.split(' ').collect();
+            for start in 0..words.len().saturating_sub(parts.len() - 1) {
+                let matches = parts
+                    .iter()
+                    .enumerate()
+                    .all(|(k, part)| words[start + k] == *part && !in_phrase[start + k]);
+                if matches {
+                    in_phrase[start..start + parts.len()].fill(true);
+                    score += if is_negated(start) {
+                        -phrase_score
+                    } else {
+                        phrase_score
+                    };
+                }
+            }
+        }
 
         for (i, word) in words.iter().enumerate() {
-            let is_positive = positive_set.contains(*word);
-            let is_negative = negative_set.contains(*word);
+            if in_phrase[i] {
+                continue;
+            }
+            if let (Some(tag), Some(sym)) = (cashtag_symbol(word), symbol) {
+                mentions_symbol |= tag.eq_ignore_ascii_case(sym);
+                continue;
+            }
 
-            if !is_positive && !is_negative {
+            let word_score = if let Some(&weight) = word_weights.get(word.as_str()) {
+                weight
+            } else {
+                let mut chars = word.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => EMOJI_SCORES
+                        .iter()
+                        .find(|(e, _)| *e == c)
+                        .map(|(_, v)| *v as f64)
+                        .unwrap_or(0.0),
+                    _ => 0.0,
+                }
+            };
+
+            if word_score == 0.0 {
                 continue;
             }
 
             // Check if any negation word is within NEGATION_WINDOW before this word
-            let negated = negation_positions
-                .iter()
-                .any(|&neg_pos| neg_pos < i && (i - neg_pos) <= NEGATION_WINDOW);
-
-            if is_positive {
-                score += if negated { -1 } else { 1 };
+            score += if is_negated(i) {
+                -word_score
             } else {
-                score += if negated { 1 } else { -1 };
-            }
+                word_score
+            };
         }
 
-        score as f64
+        if mentions_symbol {
+            score * CASHTAG_RELEVANCE_BOOST
+        } else {
+            score
+        }
     }
 
-    fn analyze_article(&self, article: &NewsArticle) -> f64 {
+    fn analyze_article(&self, article: &NewsArticle, symbol: &str) -> f64 {
         let mut total_score = 0.0;
 
         // Analyze title (weight it more heavily)
-        total_score += self.analyze_text(&article.title) * 2.0;
+        total_score += self.analyze_text_for_symbol(&article.title, Some(symbol)) * 2.0;
 
         // Analyze description if available
         if let Some(desc) = &article.description {
-            total_score += self.analyze_text(desc);
+            total_score += self.analyze_text_for_symbol(desc, Some(symbol));
         }
 
         // Analyze keywords
@@ -315,9 +718,60 @@ impl SentimentAnalysisEngine {
         total_score
     }
 
+    /// Daily sentiment history for the velocity calculator, oldest first.
+    ///
+    /// Each day's score is the recency-decayed average of every article published
+    /// up to the end of that day (ages measured from day end, using the engine's
+    /// half-life), mapped onto -100..100. A quiet day therefore doesn't reset the
+    /// trend, while `article_count` still records only that day's coverage.
+    pub fn daily_sentiment_series(
+        &self,
+        symbol: &str,
+        news: &[NewsArticle],
+    ) -> Vec<SentimentDataPoint> {
+        let (deduped, _) = self.dedup_articles(news);
+        let mut scored: Vec<(DateTime<Utc>, f64)> = deduped
+            .iter()
+            .map(|a| (a.published_utc, self.analyze_article(a, symbol)))
+            .collect();
+        scored.sort_by_key(|(published, _)| *published);
+
+        let mut days: Vec<NaiveDate> = scored.iter().map(|(t, _)| t.date_naive()).collect();
+        days.dedup();
+
+        days.into_iter()
+            .map(|day| {
+                let day_end = (day + Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+                let (mut weighted, mut weight_sum, mut count) = (0.0, 0.0, 0);
+                for (published, score) in scored.iter().take_while(|(t, _)| *t < day_end) {
+                    let age_hours = (day_end - *published).num_seconds() as f64 / 3600.0;
+                    let w = self.decay_weight(age_hours);
+                    weighted += score * w;
+                    weight_sum += w;
+                    if published.date_naive() == day {
+                        count += 1;
+                    }
+                }
+                let avg = if weight_sum > 0.0 {
+                    weighted / weight_sum
+                } else {
+                    0.0
+                };
+                SentimentDataPoint {
+                    timestamp: day
+                        .and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+                        .and_utc(),
+                    sentiment_score: 100.0 * (avg / 3.0).tanh(),
+                    article_count: count,
+                    symbol: symbol.to_string(),
+                }
+            })
+            .collect()
+    }
+
     #[allow(dead_code)]
     fn calculate_recency_weight(&self, article: &NewsArticle) -> f64 {
-        let now = Utc::now();
+        let now = self.clock.now();
         let age_hours = (now - article.published_utc).num_hours();
 
         // Exponential decay: news older than 24 hours gets less weight
@@ -354,28 +808,17 @@ impl SentimentAnalysisEngine {
         }
     }
 
-    /// Try FinBERT NLP analysis on article titles. Returns per-article scores.
-    async fn try_finbert_scores(&self, news: &[NewsArticle]) -> Option<Vec<f64>> {
+    /// Try FinBERT NLP analysis on article titles. Returns per-article predictions.
+    async fn try_finbert_predictions(
+        &self,
+        news: &[NewsArticle],
+    ) -> Option<Vec<ml_client::sentiment::SentimentPrediction>> {
         let client = self.finbert_client.as_ref()?;
         let titles: Vec<String> = news.iter().map(|a| a.title.clone()).collect();
         match client.predict(titles, None).await {
             Ok(response) => {
-                let scores: Vec<f64> = response
-                    .predictions
-                    .iter()
-                    .map(|p| {
-                        p.score
-                            * if p.label == "positive" {
-                                1.0
-                            } else if p.label == "negative" {
-                                -1.0
-                            } else {
-                                0.0
-                            }
-                    })
-                    .collect();
-                tracing::info!("FinBERT scored {} articles", scores.len());
-                Some(scores)
+                tracing::info!("FinBERT scored {} articles", response.predictions.len());
+                Some(response.predictions)
             }
             Err(e) => {
                 tracing::debug!("FinBERT unavailable, falling back to word-list: {}", e);
@@ -390,7 +833,7 @@ impl SentimentAnalysisEngine {
         if news.is_empty() {
             return (0.0, false);
         }
-        let now = Utc::now();
+        let now = self.clock.now();
         let last_24h = news
             .iter()
             .filter(|a| (now - a.published_utc).num_hours() < 24)
@@ -430,43 +873,87 @@ impl SentimentAnalysisEngine {
         news: &[NewsArticle],
     ) -> Result<AnalysisResult, AnalysisError> {
         if news.is_empty() {
-            return Ok(AnalysisResult {
-                symbol: symbol.to_string(),
-                timestamp: Utc::now(),
-                signal: SignalStrength::Neutral,
-                confidence: 0.0,
-                reason: "No news articles available".to_string(),
-                metrics: json!({}),
-            });
+            return Ok(self.no_news_result(symbol));
         }
+        let (deduped, duplicates_removed) = self.dedup_for(symbol, news);
 
-        // Try FinBERT first, fall back to word-list
-        let finbert_scores = self.try_finbert_scores(news).await;
-        let using_finbert = finbert_scores.is_some();
+        // Score with both FinBERT (when reachable) and the word-list, then blend
+        let finbert_predictions = self.try_finbert_predictions(&deduped).await;
+        self.score_articles(symbol, &deduped, duplicates_removed, finbert_predictions)
+    }
+
+    /// Word-list-only sentiment that never calls FinBERT, for offline and
+    /// deterministic runs
+    pub fn analyze_lexicon_only(
+        &self,
+        symbol: &str,
+        news: &[NewsArticle],
+    ) -> Result<AnalysisResult, AnalysisError> {
+        if news.is_empty() {
+            return Ok(self.no_news_result(symbol));
+        }
+        let (deduped, duplicates_removed) = self.dedup_for(symbol, news);
+        self.score_articles(symbol, &deduped, duplicates_removed, None)
+    }
+
+    fn no_news_result(&self, symbol: &str) -> AnalysisResult {
+        AnalysisResult {
+            symbol: symbol.to_string(),
+            timestamp: self.clock.now(),
+            signal: SignalStrength::Neutral,
+            confidence: 0.0,
+            reason: "No news articles available".to_string(),
+            metrics: json!({}),
+            confidence_components: json!({ "article_count": 0 }),
+        }
+    }
+
+    fn dedup_for(&self, symbol: &str, news: &[NewsArticle]) -> (Vec<NewsArticle>, usize) {
+        let (deduped, duplicates_removed) = self.dedup_articles(news);
+        if duplicates_removed > 0 {
+            tracing::debug!(
+                "Removed {} duplicate articles for {}",
+                duplicates_removed,
+                symbol
+            );
+        }
+        (deduped, duplicates_removed)
+    }
+
+    /// Score deduplicated articles, blending in FinBERT predictions when present
+    fn score_articles(
+        &self,
+        symbol: &str,
+        news: &[NewsArticle],
+        duplicates_removed: usize,
+        finbert_predictions: Option<Vec<ml_client::sentiment::SentimentPrediction>>,
+    ) -> Result<AnalysisResult, AnalysisError> {
+        let using_finbert = finbert_predictions.is_some();
 
         // FIRST PASS: Compute raw sentiment scores for all articles
         let mut article_scores: Vec<f64> = Vec::with_capacity(news.len());
+        let mut lexicon_sum = 0.0;
+        let mut finbert_sum = 0.0;
+        let mut finbert_count = 0usize;
+        let mut blend_confidence_sum = 0.0;
         for (i, article) in news.iter().enumerate() {
-            // Sentiment score: prefer FinBERT, fall back to word-list
-            let sentiment_score = if let Some(ref scores) = finbert_scores {
-                scores.get(i).copied().unwrap_or(0.0) * 3.0 // Scale FinBERT [-1,1] to match word-list range
-            } else {
-                self.analyze_article(article)
-            };
+            let lexicon_score = self.analyze_article(article, symbol);
+            let prediction = finbert_predictions.as_ref().and_then(|p| p.get(i));
+            if let Some(p) = prediction {
+                finbert_sum += finbert_directional_score(p);
+                finbert_count += 1;
+            }
+            let (sentiment_score, blend_confidence) = blend_sentiment(lexicon_score, prediction);
+            lexicon_sum += lexicon_score;
+            blend_confidence_sum += blend_confidence;
             article_scores.push(sentiment_score);
         }
+        let lexicon_avg = lexicon_sum / news.len() as f64;
+        let finbert_avg = (finbert_count > 0).then(|| finbert_sum / finbert_count as f64);
+        let blend_confidence = blend_confidence_sum / news.len() as f64;
 
-        // Compute adaptive recency decay based on data span
-        let now = Utc::now();
-        let max_age_hours = news
-            .iter()
-            .map(|a| (now - a.published_utc).num_hours() as f64)
-            .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .unwrap_or(24.0)
-            .max(0.0);
-
-        // Half-life is 1/4 of data span, clamped to reasonable range
-        let half_life_hours = (max_age_hours / 4.0).clamp(12.0, 168.0);
+        let now = self.clock.now();
+        let half_life_hours = self.recency_half_life_hours;
 
         // SECOND PASS: Weighted aggregation with adaptive classification
         let mut total_score = 0.0;
@@ -475,18 +962,23 @@ impl SentimentAnalysisEngine {
         let mut negative_count = 0;
         let mut neutral_count = 0;
         let mut direct_mention_count = 0;
+        let mut source_weight_sum = 0.0;
+        let mut freshness_weighted_count = 0.0;
         let mut event_counts = std::collections::HashMap::new();
+        let mut source_counts: HashMap<&str, usize> = HashMap::new();
+        // Per-event-type (weighted sentiment sum, weight sum, article count)
+        let mut event_sentiment: HashMap<String, (f64, f64, u32)> = HashMap::new();
 
         for (i, article) in news.iter().enumerate() {
             let sentiment_score = article_scores[i];
 
-            // Adaptive recency weight using continuous exponential decay
-            let age_hours = (now - article.published_utc).num_hours() as f64;
-            let recency_weight = if age_hours < 0.0 {
-                1.0
-            } else {
-                (0.5_f64).powf(age_hours / half_life_hours)
-            };
+            // Continuous exponential decay so stale clusters can't drown out fresh headlines
+            let age_hours = (now - article.published_utc).num_seconds() as f64 / 3600.0;
+            let recency_weight = self.decay_weight(age_hours);
+            freshness_weighted_count += recency_weight;
+            *source_counts
+                .entry(article.source.as_deref().unwrap_or("unknown"))
+                .or_insert(0) += 1;
 
             let entity_weight = self.calculate_entity_weight(article, symbol);
 
@@ -497,10 +989,20 @@ impl SentimentAnalysisEngine {
                 .entry(format!("{:?}", event_type))
                 .or_insert(0u32) += 1;
 
-            let combined_weight = recency_weight * entity_weight * event_weight;
+            let source_weight = self.calculate_source_weight(article);
+            source_weight_sum += source_weight;
+
+            let combined_weight = recency_weight * entity_weight * event_weight * source_weight;
             total_score += sentiment_score * combined_weight;
             total_weight += combined_weight;
 
+            let bucket = event_sentiment
+                .entry(format!("{:?}", event_type))
+                .or_insert((0.0, 0.0, 0));
+            bucket.0 += sentiment_score * combined_weight;
+            bucket.1 += combined_weight;
+            bucket.2 += 1;
+
             if entity_weight >= 1.0 {
                 direct_mention_count += 1;
             }
@@ -568,6 +1070,22 @@ impl SentimentAnalysisEngine {
         let buzz_ratio = last_24h as f64 / expected_daily.max(0.5);
         let abnormal_buzz = buzz_z > 2.0; // 2 standard deviations above expected
 
+        // Aspect-level view: net (weighted) sentiment within each event type
+        let sentiment_by_event_type: serde_json::Map<String, serde_json::Value> = event_sentiment
+            .into_iter()
+            .map(|(event, (score_sum, weight_sum, count))| {
+                let net = if weight_sum > 0.0 {
+                    score_sum / weight_sum
+                } else {
+                    0.0
+                };
+                (
+                    event,
+                    json!({ "net_sentiment": net, "article_count": count }),
+                )
+            })
+            .collect();
+
         // --- Sentiment Momentum (Acceleration/Deceleration) ---
         // Compare recent sentiment (last 24h) vs prior period (24-48h ago)
         let (sentiment_momentum, sentiment_acceleration) = if news.len() >= 2 {
@@ -743,14 +1261,22 @@ impl SentimentAnalysisEngine {
             "negative_articles": negative_count,
             "neutral_articles": neutral_count,
             "total_articles": news.len(),
+            "duplicates_removed": duplicates_removed,
+            "articles_by_source": source_counts,
             "direct_mention_articles": direct_mention_count,
+            "avg_source_weight": source_weight_sum / news.len() as f64,
             "using_finbert": using_finbert,
+            "lexicon_score": lexicon_avg,
+            "finbert_score": finbert_avg,
+            "blend_confidence": blend_confidence,
             "buzz_ratio": buzz_ratio,
             "buzz_z_score": buzz_z,
             "abnormal_buzz": abnormal_buzz,
             "sentiment_z_score": sent_z,
             "half_life_hours": half_life_hours,
+            "freshness_weighted_articles": freshness_weighted_count,
             "event_breakdown": event_counts,
+            "sentiment_by_event_type": sentiment_by_event_type,
             "sentiment_momentum": sentiment_momentum,
             "sentiment_acceleration": sentiment_acceleration,
             "contradictory_signal": contradictory_signal,
@@ -758,13 +1284,21 @@ impl SentimentAnalysisEngine {
             "momentum_signals": signals.iter().map(|(name, _, _)| *name).collect::<Vec<&str>>(),
         });
 
+        let confidence_components = json!({
+            "article_count_confidence": article_count_confidence,
+            "consistency": consistency,
+            "finbert_bonus": finbert_bonus,
+            "article_count": news.len(),
+        });
+
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason,
             metrics,
+            confidence_components,
         })
     }
 }
@@ -785,3 +1319,267 @@ impl Default for SentimentAnalysisEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_source_weight_default_table() {
+        assert_eq!(source_weight("Reuters"), 1.5);
+        assert_eq!(source_weight("Bloomberg News"), 1.5);
+        assert_eq!(source_weight("CNBC"), 1.2);
+        assert_eq!(source_weight("The Motley Fool"), 0.6);
+        assert_eq!(source_weight("Some Local Blog"), 1.0);
+    }
+
+    #[test]
+    fn test_custom_source_weights() {
+        let mut weights = default_source_weights();
+        weights.insert("some local blog".to_string(), 0.3);
+        let engine = SentimentAnalysisEngine::new().with_source_weights(weights);
+
+        let article = NewsArticle {
+            id: "1".to_string(),
+            title: "Shares rally".to_string(),
+            author: Some("Some Local Blog".to_string()),
+            published_utc: Utc::now(),
+            article_url: String::new(),
+            description: None,
+            keywords: vec![],
+            tickers: vec!["AAPL".to_string()],
+            source: None,
+        };
+        assert_eq!(engine.calculate_source_weight(&article), 0.3);
+
+        let anonymous = NewsArticle {
+            author: None,
+            ..article
+        };
+        assert_eq!(engine.calculate_source_weight(&anonymous), 1.0);
+    }
+
+    fn prediction(
+        label: &str,
+        score: f64,
+        confidence: f64,
+    ) -> ml_client::sentiment::SentimentPrediction {
+        ml_client::sentiment::SentimentPrediction {
+            label: label.to_string(),
+            positive: 0.0,
+            negative: 0.0,
+            neutral: 0.0,
+            confidence,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_blend_sentiment() {
+        // No FinBERT: lexicon only
+        assert_eq!(blend_sentiment(2.0, None), (2.0, LEXICON_CONFIDENCE));
+
+        // Low-confidence FinBERT is ignored
+        let weak = prediction("negative", 0.9, 0.4);
+        assert_eq!(blend_sentiment(2.0, Some(&weak)), (2.0, LEXICON_CONFIDENCE));
+
+        // Confident FinBERT dominates in proportion to its confidence
+        let strong = prediction("negative", 1.0, 0.9);
+        let (score, confidence) = blend_sentiment(2.0, Some(&strong));
+        assert!((score - (0.9 * -3.0 + 0.1 * 2.0)).abs() < 1e-12);
+        assert!(confidence > LEXICON_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_decay_weight_half_life() {
+        let engine = SentimentAnalysisEngine::new();
+        assert_eq!(engine.decay_weight(0.0), 1.0);
+        assert!((engine.decay_weight(48.0) - 0.5).abs() < 1e-12);
+        assert_eq!(engine.decay_weight(-5.0), 1.0);
+
+        let fast = SentimentAnalysisEngine::new().with_recency_half_life(12.0);
+        assert!((fast.decay_weight(24.0) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dedup_keeps_most_reputable_copy() {
+        let engine = SentimentAnalysisEngine::new();
+        let now = Utc::now();
+        let article = |id: &str, title: &str, author: &str, hours_ago: i64| NewsArticle {
+            id: id.to_string(),
+            title: title.to_string(),
+            author: Some(author.to_string()),
+            published_utc: now - chrono::Duration::hours(hours_ago),
+            article_url: String::new(),
+            description: None,
+            keywords: vec![],
+            tickers: vec!["AAPL".to_string()],
+            source: None,
+        };
+        let news = vec![
+            article(
+                "1",
+                "Apple beats earnings estimates on iPhone strength",
+                "Benzinga",
+                1,
+            ),
+            article(
+                "2",
+                "Apple Beats Earnings Estimates on iPhone Strength",
+                "Reuters",
+                2,
+            ),
+            article("3", "Apple faces antitrust lawsuit in Europe", "CNBC", 3),
+            // Same headline outside the window is a separate story
+            article(
+                "4",
+                "Apple beats earnings estimates on iPhone strength",
+                "Zacks",
+                24 * 90,
+            ),
+        ];
+
+        let (kept, removed) = engine.dedup_articles(&news);
+        assert_eq!(removed, 1);
+        let ids: Vec<&str> = kept.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_metrics_count_articles_by_source() {
+        let engine = SentimentAnalysisEngine::new();
+        let now = Utc::now();
+        let article = |id: &str, title: &str, source: Option<&str>| NewsArticle {
+            id: id.to_string(),
+            title: title.to_string(),
+            author: None,
+            published_utc: now,
+            article_url: String::new(),
+            description: None,
+            keywords: vec![],
+            tickers: vec!["AAPL".to_string()],
+            source: source.map(str::to_string),
+        };
+        let news = vec![
+            article("1", "Apple beats earnings estimates", Some("polygon")),
+            article("2", "Apple expands buyback program", Some("polygon")),
+            article("3", "Apple supplier warns on margins", Some("finnhub")),
+            article("4", "Apple opens new store in Mumbai", None),
+        ];
+
+        let result = engine.analyze_lexicon_only("AAPL", &news).unwrap();
+        let by_source = &result.metrics["articles_by_source"];
+        assert_eq!(by_source["polygon"], 2);
+        assert_eq!(by_source["finnhub"], 1);
+        assert_eq!(by_source["unknown"], 1);
+    }
+
+    #[test]
+    fn test_tokenize_preserves_cashtags() {
+        let tokens = tokenize("Loading up on $AAPL, $BRK.B and $5 calls. Long $NVDA.");
+        assert!(tokens.contains(&"$nvda".to_string()));
+        assert!(tokens.contains(&"$aapl".to_string()));
+        assert!(tokens.contains(&"$brk.b".to_string()));
+        assert_eq!(cashtag_symbol("$aapl"), Some("aapl"));
+        assert_eq!(cashtag_symbol("$5"), None);
+    }
+
+    #[test]
+    fn test_emoji_scoring() {
+        let engine = SentimentAnalysisEngine::new();
+        assert_eq!(engine.analyze_text("to the moon 🚀🚀"), 2.0);
+        assert_eq!(engine.analyze_text("bag holding📉"), -1.0);
+        // Variation selector after the emoji must not hide it
+        assert_eq!(engine.analyze_text("💎\u{fe0f} hands"), 1.0);
+    }
+
+    #[test]
+    fn test_negation_spans_emoji_and_cashtags() {
+        let engine = SentimentAnalysisEngine::new();
+        assert_eq!(engine.analyze_text("not 🚀"), -1.0);
+        assert_eq!(engine.analyze_text("not $TSLA rally"), -1.0);
+    }
+
+    #[test]
+    fn test_custom_lexicon_terms_and_phrases() {
+        let mut engine = SentimentAnalysisEngine::new().with_custom_lexicon(
+            vec!["Tape-Out".to_string(), "Phase 3 readout".to_string()],
+            vec!["clinical hold".to_string()],
+        );
+        assert_eq!(engine.analyze_text("Successful tape-out at TSMC"), 1.0);
+        assert_eq!(engine.analyze_text("Strong phase 3 readout"), 2.0);
+        assert_eq!(engine.analyze_text("FDA places clinical hold"), -1.0);
+        // Phrases respect the negation window like single words
+        assert_eq!(engine.analyze_text("no phase 3 readout yet"), -1.0);
+
+        // Moving a term between lists and removing it apply to the next call
+        engine.add_negative_term("tape-out");
+        assert_eq!(engine.analyze_text("tape-out"), -1.0);
+        assert!(engine.remove_term("TAPE-OUT"));
+        assert_eq!(engine.analyze_text("tape-out"), 0.0);
+        assert!(!engine.remove_term("tape-out"));
+    }
+
+    #[test]
+    fn test_lexicon_intensity_weights() {
+        let mut engine = SentimentAnalysisEngine::new();
+        // Strong language outweighs hedged language, negation still flips the sign
+        assert_eq!(engine.analyze_text("crash"), -2.0);
+        assert_eq!(engine.analyze_text("concern"), -0.5);
+        assert!(engine.analyze_text("plunge") < engine.analyze_text("decline"));
+        assert_eq!(engine.analyze_text("not a crash"), 2.0);
+        assert_eq!(engine.analyze_text("surge in bankruptcy fears"), 1.5 - 2.0);
+
+        engine.add_term_with_weight("short squeeze", 2.0);
+        engine.add_term_with_weight("going concern", -1.75);
+        assert_eq!(engine.analyze_text("short squeeze"), 2.0);
+        // The phrase takes precedence over its "concern" word
+        assert_eq!(engine.analyze_text("going concern doubt"), -1.75);
+    }
+
+    #[test]
+    fn test_daily_sentiment_series() {
+        let engine = SentimentAnalysisEngine::new();
+        let day = |d: u32, h: u32, title: &str| NewsArticle {
+            id: format!("{}-{}", d, h),
+            title: title.to_string(),
+            author: None,
+            published_utc: Utc.with_ymd_and_hms(2024, 3, d, h, 0, 0).unwrap(),
+            article_url: String::new(),
+            description: None,
+            keywords: vec![],
+            tickers: vec!["ACME".to_string()],
+            source: None,
+        };
+        let news = vec![
+            day(4, 15, "Acme shares surge after record profit"),
+            day(1, 9, "Acme faces lawsuit over weak guidance"),
+            day(1, 14, "Acme hit by downgrade and losses"),
+            day(2, 10, "Acme slump deepens"),
+        ];
+        let series = engine.daily_sentiment_series("ACME", &news);
+
+        assert_eq!(series.len(), 3);
+        assert!(series.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!(
+            series.iter().map(|p| p.article_count).collect::<Vec<_>>(),
+            vec![2, 1, 1]
+        );
+        assert!(series[0].sentiment_score < 0.0);
+        // The older negative coverage has decayed by day 4, so the trend flips positive
+        assert!(series[2].sentiment_score > 0.0);
+        assert!(series
+            .iter()
+            .all(|p| (-100.0..=100.0).contains(&p.sentiment_score)));
+    }
+
+    #[test]
+    fn test_cashtag_boost_for_matching_symbol() {
+        let engine = SentimentAnalysisEngine::new();
+        let boosted = engine.analyze_text_for_symbol("$AAPL rally 📈", Some("AAPL"));
+        let other = engine.analyze_text_for_symbol("$MSFT rally 📈", Some("AAPL"));
+        assert_eq!(other, 2.0);
+        assert_eq!(boosted, 2.0 * CASHTAG_RELEVANCE_BOOST);
+    }
+}
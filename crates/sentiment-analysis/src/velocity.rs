@@ -136,9 +136,13 @@ impl SentimentVelocityCalculator {
 
     /// Calculate sentiment dynamics from historical data
     pub fn calculate(&self, history: &[SentimentDataPoint]) -> SentimentDynamics {
-        if history.len() < self.min_data_points {
+        // Days with no articles carry a placeholder score, not an observation — drop them
+        let observed: Vec<&SentimentDataPoint> =
+            history.iter().filter(|p| p.article_count > 0).collect();
+
+        if observed.len() < self.min_data_points {
             return SentimentDynamics {
-                current_sentiment: history.last().map(|p| p.sentiment_score).unwrap_or(0.0),
+                current_sentiment: observed.last().map(|p| p.sentiment_score).unwrap_or(0.0),
                 velocity: 0.0,
                 acceleration: 0.0,
                 narrative_shift: None,
@@ -149,7 +153,7 @@ impl SentimentVelocityCalculator {
         }
 
         // Sort by timestamp (oldest first)
-        let mut sorted: Vec<_> = history.to_vec();
+        let mut sorted: Vec<SentimentDataPoint> = observed.into_iter().cloned().collect();
         sorted.sort_by_key(|p| p.timestamp);
 
         // Get current sentiment (most recent)
@@ -215,7 +219,9 @@ impl SentimentVelocityCalculator {
         (sentiment_diff / time_diff_hours) * 24.0
     }
 
-    /// Calculate acceleration (rate of change of velocity)
+    /// Calculate acceleration as the change in velocity between two consecutive
+    /// windows (older half vs newer half), per day, over the time between the
+    /// window midpoints
     fn calculate_acceleration(&self, sorted: &[SentimentDataPoint]) -> f64 {
         if sorted.len() < 3 {
             return 0.0;
@@ -224,22 +230,25 @@ impl SentimentVelocityCalculator {
         let n = sorted.len();
         let mid = n / 2;
 
-        // Calculate velocity for first half
-        let first_half = &sorted[..mid.max(2)];
+        // Windows share the middle point so each has at least two observations
+        let first_half = &sorted[..(mid + 1).max(2)];
+        let second_half = &sorted[mid..];
         let velocity_first = self.calculate_velocity(first_half);
-
-        // Calculate velocity for second half
-        let second_half = &sorted[mid.saturating_sub(1)..];
         let velocity_second = self.calculate_velocity(second_half);
 
-        // Acceleration is change in velocity
-        let time_span_hours = (sorted[n - 1].timestamp - sorted[0].timestamp).num_hours() as f64;
-        if time_span_hours <= 0.0 {
+        let window_center = |w: &[SentimentDataPoint]| {
+            let start = w[0].timestamp;
+            start + (w[w.len() - 1].timestamp - start) / 2
+        };
+        let gap_hours =
+            (window_center(second_half) - window_center(first_half)).num_minutes() as f64 / 60.0;
+        // Points clustered at one timestamp give no time base to divide by
+        if gap_hours <= 0.0 {
             return 0.0;
         }
 
         // Normalize acceleration to per-day^2
-        ((velocity_second - velocity_first) / time_span_hours) * 24.0
+        ((velocity_second - velocity_first) / gap_hours) * 24.0
     }
 
     /// Detect if there's been a narrative shift
@@ -446,6 +455,49 @@ mod tests {
         assert!(result.acceleration > 0.0, "Acceleration should be positive");
     }
 
+    #[test]
+    fn test_accelerating_positive_signal() {
+        let calculator = SentimentVelocityCalculator::default();
+        let data = create_test_data(&[0.0, 2.0, 6.0, 14.0, 30.0, 62.0]);
+        let result = calculator.calculate(&data);
+
+        assert!(result.acceleration > 0.0);
+        assert_eq!(result.signal, VelocitySignal::AcceleratingPositive);
+    }
+
+    #[test]
+    fn test_sparse_days_ignored() {
+        let calculator = SentimentVelocityCalculator::default();
+        let mut data = create_test_data(&[-20.0, 0.0, 0.0, 30.0, 0.0, 70.0]);
+        // Quiet days report zero articles and a meaningless 0.0 score
+        data[2].article_count = 0;
+        data[4].article_count = 0;
+        let result = calculator.calculate(&data);
+
+        assert!(result.velocity.is_finite() && result.acceleration.is_finite());
+        assert!(result.velocity > 0.0);
+
+        // All-quiet history must not divide by zero
+        let mut quiet = create_test_data(&[0.0, 0.0, 0.0, 0.0]);
+        quiet.iter_mut().for_each(|p| p.article_count = 0);
+        let result = calculator.calculate(&quiet);
+        assert_eq!(result.acceleration, 0.0);
+        assert_eq!(result.signal, VelocitySignal::Stable);
+
+        // Same-timestamp points give no time base
+        let now = Utc::now();
+        let clustered: Vec<_> = create_test_data(&[10.0, 20.0, 30.0])
+            .into_iter()
+            .map(|p| SentimentDataPoint {
+                timestamp: now,
+                ..p
+            })
+            .collect();
+        let result = calculator.calculate(&clustered);
+        assert_eq!(result.velocity, 0.0);
+        assert_eq!(result.acceleration, 0.0);
+    }
+
     #[test]
     fn test_accelerating_negative() {
         let calculator = SentimentVelocityCalculator::default();
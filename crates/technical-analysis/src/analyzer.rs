@@ -1,15 +1,30 @@
 use analysis_core::{
-    adaptive, AnalysisError, AnalysisResult, Bar, SignalStrength, TechnicalAnalyzer,
+    adaptive, AnalysisError, AnalysisResult, Bar, Clock, SignalStrength, SystemClock,
+    TechnicalAnalyzer,
 };
 use async_trait::async_trait;
-use chrono::Utc;
 use rayon;
 use serde_json::json;
+use std::sync::Arc;
 
 use crate::indicators::*;
 use crate::patterns::*;
 
-pub struct TechnicalAnalysisEngine;
+/// VWAP band width, in standard deviations, beyond which price counts as stretched
+const VWAP_BAND_SIGMA: f64 = 2.0;
+
+/// Confirmed candlestick patterns ending within this many bars of the last one
+/// feed the enhanced signals
+const CANDLE_PATTERN_RECENT_BARS: usize = 3;
+
+/// Minimum strength for a confirmed candlestick pattern to become a signal
+const CANDLE_PATTERN_MIN_STRENGTH: f64 = 0.6;
+
+pub struct TechnicalAnalysisEngine {
+    vwap_anchor: VwapAnchor,
+    /// Time source for result timestamps (wall clock unless overridden)
+    clock: Arc<dyn Clock>,
+}
 
 fn pattern_name(p: &crate::patterns::CandlestickPattern) -> &'static str {
     match p {
@@ -45,7 +60,62 @@ struct SignalData {
 
 impl TechnicalAnalysisEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            vwap_anchor: VwapAnchor::StartOfRange,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Bar the enhanced analysis anchors its VWAP and VWAP bands to
+    /// (default: start of the bar range)
+    pub fn with_vwap_anchor(mut self, anchor: VwapAnchor) -> Self {
+        self.vwap_anchor = anchor;
+        self
+    }
+
+    /// Inject the time source, e.g. a `FixedClock` for reproducible output
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Reversal candlestick patterns across `bars`, each confirmed by the trend
+    /// leading into it
+    pub fn detect_candlestick_patterns(&self, bars: &[Bar]) -> Vec<CandlePattern> {
+        detect_candlestick_patterns(bars)
+    }
+
+    /// Latest RSI over `period` bars, or `None` with fewer than `period + 1` bars
+    pub fn rsi(&self, bars: &[Bar], period: usize) -> Option<f64> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        last_finite(&rsi(&closes, period))
+    }
+
+    /// Latest ADX over `period` bars, or `None` with fewer than `2 * period + 1` bars
+    pub fn adx(&self, bars: &[Bar], period: usize) -> Option<f64> {
+        last_finite(&adx(bars, period).adx)
+    }
+
+    /// Latest simple moving average of closes over `period` bars
+    pub fn sma(&self, bars: &[Bar], period: usize) -> Option<f64> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        last_finite(&sma(&closes, period))
+    }
+
+    /// Latest ATR over `period` bars, or `None` with fewer than `period + 1` bars
+    pub fn atr(&self, bars: &[Bar], period: usize) -> Option<f64> {
+        last_finite(&atr(bars, period))
+    }
+
+    /// Position of the last close within 2σ Bollinger Bands over `period` bars:
+    /// 0 at the lower band, 1 at the upper band, 0.5 when the bands collapse
+    pub fn bb_percent_b(&self, bars: &[Bar], period: usize) -> Option<f64> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let bb = bollinger_bands(&closes, period, 2.0);
+        let upper = *bb.upper.last()?;
+        let lower = *bb.lower.last()?;
+        let current_price = *closes.last()?;
+        Some(percent_b(current_price, upper, lower))
     }
 
     /// Build the core set of signals shared by both analyze_sync and analyze_enhanced.
@@ -270,12 +340,7 @@ impl TechnicalAnalysisEngine {
                 } else {
                     0.0
                 };
-                let percent_b = if (upper - lower) != 0.0 {
-                    (current_price - lower) / (upper - lower)
-                } else {
-                    0.5
-                };
-                (Some(width), Some(percent_b))
+                (Some(width), Some(percent_b(current_price, upper, lower)))
             } else {
                 (None, None)
             };
@@ -326,7 +391,7 @@ impl TechnicalAnalysisEngine {
         };
 
         let signal = SignalStrength::from_score(normalized_score as i32);
-        let confidence = compute_confidence(&data.signals, bars.len());
+        let (confidence, confidence_components) = compute_confidence(&data.signals, bars.len());
 
         let reason = data
             .signals
@@ -339,11 +404,12 @@ impl TechnicalAnalysisEngine {
 
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason,
             metrics,
+            confidence_components,
         })
     }
 
@@ -802,10 +868,11 @@ impl TechnicalAnalysisEngine {
 
         // --- Ichimoku Cloud Signals ---
         let mut ichimoku_signal: Option<&str> = None;
+        let mut ichimoku_metrics = serde_json::Value::Null;
         if bars.len() >= 52 {
             let ichi = ichimoku(bars);
             let current_price = *closes.last().unwrap();
-            // Price vs Cloud: use the last span_a and span_b values
+            // Price vs Cloud: the spans plotted at the current bar (computed 26 bars ago)
             if let (Some(&span_a), Some(&span_b)) =
                 (ichi.senkou_span_a.last(), ichi.senkou_span_b.last())
             {
@@ -820,8 +887,12 @@ impl TechnicalAnalysisEngine {
                 } else {
                     ichimoku_signal = Some("in_cloud");
                 }
-                // Future cloud color
-                if span_a > span_b {
+            }
+            // Future cloud color: the spans projected past the last bar
+            if let (Some(&lead_a), Some(&lead_b)) =
+                (ichi.leading_span_a.last(), ichi.leading_span_b.last())
+            {
+                if lead_a > lead_b {
                     data.signals
                         .push(("Ichimoku Cloud Bullish (Green)", 1, true));
                 } else {
@@ -830,6 +901,7 @@ impl TechnicalAnalysisEngine {
                 }
             }
             // Tenkan/Kijun cross
+            let mut tk_cross: Option<&str> = None;
             if ichi.tenkan_sen.len() >= 2 && ichi.kijun_sen.len() >= 2 {
                 let n = ichi.tenkan_sen.len();
                 let t_now = ichi.tenkan_sen[n - 1];
@@ -838,12 +910,80 @@ impl TechnicalAnalysisEngine {
                 let k_prev = ichi.kijun_sen[n - 2];
                 if t_now > k_now && t_prev <= k_prev {
                     data.signals.push(("Tenkan-Kijun Bullish Cross", 3, true));
+                    tk_cross = Some("bullish");
                 } else if t_now < k_now && t_prev >= k_prev {
                     data.signals.push(("Tenkan-Kijun Bearish Cross", 3, false));
+                    tk_cross = Some("bearish");
+                }
+            }
+            // Cloud thickness relative to price as a trend-strength proxy: a thick
+            // cloud is strong support/resistance, so it backs the side price is on
+            let offset = closes.len() - ichi.senkou_span_a.len();
+            let thickness: Vec<f64> = ichi
+                .senkou_span_a
+                .iter()
+                .zip(&ichi.senkou_span_b)
+                .zip(&closes[offset..])
+                .map(|((a, b), c)| if *c > 0.0 { (a - b).abs() / c } else { 0.0 })
+                .collect();
+            let thickness_pct = thickness
+                .last()
+                .map(|&t| adaptive::percentile_rank(t, &thickness));
+            if thickness_pct.is_some_and(|p| p > 0.75) {
+                match ichimoku_signal {
+                    Some("above_cloud") => {
+                        data.signals.push(("Thick Ichimoku Cloud Support", 1, true))
+                    }
+                    Some("below_cloud") => {
+                        data.signals
+                            .push(("Thick Ichimoku Cloud Resistance", 1, false))
+                    }
+                    _ => {}
                 }
             }
+            ichimoku_metrics = json!({
+                "tenkan_sen": ichi.tenkan_sen.last(),
+                "kijun_sen": ichi.kijun_sen.last(),
+                "senkou_span_a": ichi.senkou_span_a.last(),
+                "senkou_span_b": ichi.senkou_span_b.last(),
+                "chikou_span": ichi.chikou_span.last(),
+                "leading_span_a": ichi.leading_span_a.last(),
+                "leading_span_b": ichi.leading_span_b.last(),
+                "cloud_thickness": thickness.last(),
+                "cloud_thickness_percentile": thickness_pct,
+                "tk_cross": tk_cross,
+            });
         }
 
+        // --- Trend-Confirmed Candlestick Reversals ---
+        // Patterns on the last bar that build_signals already counted are skipped
+        let recent_start = bars.len().saturating_sub(CANDLE_PATTERN_RECENT_BARS);
+        let recent_candles: Vec<CandlePattern> = self
+            .detect_candlestick_patterns(bars)
+            .into_iter()
+            .filter(|p| p.index >= recent_start)
+            .collect();
+        for p in &recent_candles {
+            let already_counted = data
+                .patterns
+                .iter()
+                .any(|m| m.index == p.index && m.bullish == p.bullish);
+            if p.strength >= CANDLE_PATTERN_MIN_STRENGTH && !already_counted {
+                data.signals.push((p.name, 2, p.bullish));
+            }
+        }
+        let candlestick_patterns: Vec<serde_json::Value> = recent_candles
+            .iter()
+            .map(|p| {
+                json!({
+                    "name": p.name,
+                    "index": p.index,
+                    "bullish": p.bullish,
+                    "strength": p.strength,
+                })
+            })
+            .collect();
+
         // --- Fibonacci Retracement Signals ---
         let mut fib_near_level: Option<&str> = None;
         if let Some(fib) = fibonacci_retracement(bars, 60.min(bars.len())) {
@@ -873,20 +1013,31 @@ impl TechnicalAnalysisEngine {
             }
         }
 
-        // --- VWAP Signal ---
+        // --- Anchored VWAP + VWAP Bands ---
         let mut vwap_position: Option<&str> = None;
+        let mut vwap_distance_sigma: Option<f64> = None;
+        let avwap = anchored_vwap(bars, self.vwap_anchor);
+        if let (Some(&last_vwap), Some(&last_std), Some(&current)) =
+            (avwap.vwap.last(), avwap.std_dev.last(), closes.last())
         {
-            let vwap_values = vwap(bars);
-            if let (Some(&last_vwap), Some(&current)) = (vwap_values.last(), closes.last()) {
-                if last_vwap > 0.0 {
-                    if current > last_vwap * 1.005 {
-                        data.signals.push(("Price Above VWAP", 1, true));
-                        vwap_position = Some("above");
-                    } else if current < last_vwap * 0.995 {
-                        data.signals.push(("Price Below VWAP", 1, false));
-                        vwap_position = Some("below");
-                    }
+            if last_vwap > 0.0 {
+                if current > last_vwap * 1.005 {
+                    data.signals.push(("Price Above VWAP", 1, true));
+                    vwap_position = Some("above");
+                } else if current < last_vwap * 0.995 {
+                    data.signals.push(("Price Below VWAP", 1, false));
+                    vwap_position = Some("below");
+                }
+            }
+            if last_std > 0.0 {
+                let sigma = (current - last_vwap) / last_std;
+                // Beyond the outer band price is stretched and tends to revert
+                if sigma >= VWAP_BAND_SIGMA {
+                    data.signals.push(("Above Upper VWAP Band", 2, false));
+                } else if sigma <= -VWAP_BAND_SIGMA {
+                    data.signals.push(("Below Lower VWAP Band", 2, true));
                 }
+                vwap_distance_sigma = Some(sigma);
             }
         }
 
@@ -1100,7 +1251,7 @@ impl TechnicalAnalysisEngine {
         };
 
         let signal = SignalStrength::from_score(normalized_score as i32);
-        let confidence = compute_confidence(&data.signals, bars.len());
+        let (confidence, confidence_components) = compute_confidence(&data.signals, bars.len());
 
         let reason = data
             .signals
@@ -1119,8 +1270,13 @@ impl TechnicalAnalysisEngine {
             "weekly_rsi": weekly_rsi_val,
             "relative_strength_rising": rs_rising,
             "ichimoku_signal": ichimoku_signal,
+            "ichimoku": ichimoku_metrics,
             "fibonacci_near_level": fib_near_level,
+            "candlestick_patterns": candlestick_patterns,
             "vwap_position": vwap_position,
+            "anchored_vwap": avwap.vwap.last(),
+            "vwap_anchor_index": avwap.anchor_index,
+            "vwap_distance_sigma": vwap_distance_sigma,
             "volume_profile_support": vol_profile_support,
             "volume_profile_resistance": vol_profile_resistance,
             "accumulation_distribution": accumulation_distribution,
@@ -1135,11 +1291,12 @@ impl TechnicalAnalysisEngine {
 
         Ok(AnalysisResult {
             symbol: symbol.to_string(),
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             signal,
             confidence,
             reason,
             metrics,
+            confidence_components,
         })
     }
 }
@@ -1157,15 +1314,31 @@ impl Default for TechnicalAnalysisEngine {
     }
 }
 
+/// Last value of an indicator series, skipping a non-finite tail value
+fn last_finite(values: &[f64]) -> Option<f64> {
+    values.last().copied().filter(|v| v.is_finite())
+}
+
+/// %B of `price` within a band: 0 at `lower`, 1 at `upper`
+fn percent_b(price: f64, upper: f64, lower: f64) -> f64 {
+    if (upper - lower) != 0.0 {
+        (price - lower) / (upper - lower)
+    } else {
+        0.5
+    }
+}
+
 /// Compute technical analysis confidence based on signal agreement + data quality.
 ///
 /// Uses the same approach as fundamental/quant engines:
 /// - Signal agreement: what fraction of signals agree with the majority direction?
 /// - Data quality: more bars = more reliable indicators
 /// - Capped at 0.95 for conservative bias (like other engines)
-fn compute_confidence(signals: &[(&str, i32, bool)], bar_count: usize) -> f64 {
+///
+/// Returns the scalar confidence alongside its components.
+fn compute_confidence(signals: &[(&str, i32, bool)], bar_count: usize) -> (f64, serde_json::Value) {
     if signals.is_empty() {
-        return 0.3;
+        return (0.3, json!({ "signal_count": 0, "bar_count": bar_count }));
     }
 
     // Signal agreement: what % of weighted signals align with the majority?
@@ -1203,5 +1376,14 @@ fn compute_confidence(signals: &[(&str, i32, bool)], bar_count: usize) -> f64 {
     // Weighted combination: agreement matters most
     let confidence = agreement * 0.55 + data_quality * 0.30 + coverage * 0.15;
 
-    confidence.clamp(0.15, 0.95)
+    (
+        confidence.clamp(0.15, 0.95),
+        json!({
+            "agreement": agreement,
+            "data_quality": data_quality,
+            "coverage": coverage,
+            "signal_count": signals.len(),
+            "bar_count": bar_count,
+        }),
+    )
 }
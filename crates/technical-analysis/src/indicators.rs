@@ -469,6 +469,106 @@ pub fn vwap(bars: &[Bar]) -> Vec<f64> {
     vwap_values
 }
 
+/// Bar an anchored VWAP starts accumulating from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VwapAnchor {
+    /// First bar of the series
+    StartOfRange,
+    /// The most recent of the highest high and lowest low over the trailing
+    /// `lookback` bars
+    LastPivot { lookback: usize },
+    /// A fixed bar index (clamped to the series)
+    Index(usize),
+}
+
+impl VwapAnchor {
+    /// Index into `bars` this anchor resolves to
+    pub fn resolve(&self, bars: &[Bar]) -> usize {
+        let n = bars.len();
+        if n == 0 {
+            return 0;
+        }
+        match *self {
+            VwapAnchor::StartOfRange => 0,
+            VwapAnchor::Index(i) => i.min(n - 1),
+            VwapAnchor::LastPivot { lookback } => {
+                let start = n.saturating_sub(lookback.max(1));
+                let window = &bars[start..];
+                let high_idx = window
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| {
+                        a.1.high
+                            .partial_cmp(&b.1.high)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap_or(0);
+                let low_idx = window
+                    .iter()
+                    .enumerate()
+                    .min_by(|a, b| {
+                        a.1.low
+                            .partial_cmp(&b.1.low)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap_or(0);
+                start + high_idx.max(low_idx)
+            }
+        }
+    }
+}
+
+/// Anchored VWAP with its volume-weighted standard deviation (VWAP bands)
+pub struct AnchoredVwap {
+    pub anchor_index: usize,
+    /// One value per bar from `anchor_index` to the end of the series
+    pub vwap: Vec<f64>,
+    pub std_dev: Vec<f64>,
+}
+
+/// VWAP accumulated from `anchor`, using each bar's own VWAP as its price when
+/// the feed provides one and the typical price otherwise
+pub fn anchored_vwap(bars: &[Bar], anchor: VwapAnchor) -> AnchoredVwap {
+    let anchor_index = anchor.resolve(bars);
+    let tail = bars.get(anchor_index..).unwrap_or(&[]);
+
+    let mut vwap_values = Vec::with_capacity(tail.len());
+    let mut std_values = Vec::with_capacity(tail.len());
+    let mut cumulative_pv = 0.0;
+    let mut cumulative_p2v = 0.0;
+    let mut cumulative_volume = 0.0;
+
+    for bar in tail {
+        let price = bar
+            .vwap
+            .filter(|v| v.is_finite() && *v > 0.0)
+            .unwrap_or((bar.high + bar.low + bar.close) / 3.0);
+        cumulative_pv += price * bar.volume;
+        cumulative_p2v += price * price * bar.volume;
+        cumulative_volume += bar.volume;
+
+        let (vwap, variance) = if cumulative_volume > 0.0 {
+            let mean = cumulative_pv / cumulative_volume;
+            (mean, cumulative_p2v / cumulative_volume - mean * mean)
+        } else {
+            (price, 0.0)
+        };
+        vwap_values.push(finite_or(vwap, price));
+        std_values.push(finite_or(variance.max(0.0).sqrt(), 0.0));
+    }
+
+    AnchoredVwap {
+        anchor_index,
+        vwap: vwap_values,
+        std_dev: std_values,
+    }
+}
+
+/// Periods the Senkou spans are plotted ahead (and the Chikou span behind)
+pub const ICHIMOKU_DISPLACEMENT: usize = 26;
+
 /// Ichimoku Cloud components
 pub struct IchimokuResult {
     pub tenkan_sen: Vec<f64>,    // Conversion Line (9-period)
@@ -476,6 +576,11 @@ pub struct IchimokuResult {
     pub senkou_span_a: Vec<f64>, // Leading Span A
     pub senkou_span_b: Vec<f64>, // Leading Span B
     pub chikou_span: Vec<f64>,   // Lagging Span
+    /// Span A computed from the last 26 bars, i.e. the cloud for the periods
+    /// after the final bar that has no bar to align to yet
+    pub leading_span_a: Vec<f64>,
+    /// Span B counterpart of `leading_span_a`
+    pub leading_span_b: Vec<f64>,
 }
 
 /// Calculate the high/low midpoint over a period
@@ -498,6 +603,8 @@ pub fn ichimoku(bars: &[Bar]) -> IchimokuResult {
         senkou_span_a: vec![],
         senkou_span_b: vec![],
         chikou_span: vec![],
+        leading_span_a: vec![],
+        leading_span_b: vec![],
     };
     if bars.len() < 52 {
         return empty;
@@ -524,23 +631,28 @@ pub fn ichimoku(bars: &[Bar]) -> IchimokuResult {
 
     // Senkou Span A = midpoint of tenkan & kijun (plotted 26 periods ahead)
     // Senkou Span B = 52-period midpoint (plotted 26 periods ahead)
-    // We store them aligned to the current bar (i.e., the value that WAS plotted 26 bars ago)
-    for i in 25..n {
-        let src = i - 25; // value from 26 bars ago, shifted forward to current
-        span_a.push((tenkan[src] + kijun[src]) / 2.0);
-        span_b.push(if src >= 51 {
-            period_midpoint(bars, src, 52)
-        } else {
-            bars[src].close
-        });
+    // We store them aligned to the bar they are plotted at, so span_a[k] belongs to
+    // bar k + 26 and was computed from bar k. Spans from the last 26 bars fall past
+    // the end of the series and go to leading_span_a / leading_span_b instead.
+    let span_at = |src: usize| {
+        (
+            (tenkan[src] + kijun[src]) / 2.0,
+            period_midpoint(bars, src, 52),
+        )
+    };
+    for src in 0..n - ICHIMOKU_DISPLACEMENT {
+        let (a, b) = span_at(src);
+        span_a.push(a);
+        span_b.push(b);
     }
+    let (leading_a, leading_b): (Vec<f64>, Vec<f64>) =
+        (n - ICHIMOKU_DISPLACEMENT..n).map(span_at).unzip();
 
     // Chikou span = close shifted 26 periods back (so for display, chikou[i] is close[i+26])
-    let chikou: Vec<f64> = if n > 26 {
-        bars[26..].iter().map(|b| b.close).collect()
-    } else {
-        vec![]
-    };
+    let chikou: Vec<f64> = bars[ICHIMOKU_DISPLACEMENT..]
+        .iter()
+        .map(|b| b.close)
+        .collect();
 
     IchimokuResult {
         tenkan_sen: tenkan,
@@ -548,6 +660,8 @@ pub fn ichimoku(bars: &[Bar]) -> IchimokuResult {
         senkou_span_a: span_a,
         senkou_span_b: span_b,
         chikou_span: chikou,
+        leading_span_a: leading_a,
+        leading_span_b: leading_b,
     }
 }
 
@@ -294,6 +294,35 @@ mod tests {
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn test_anchored_vwap_start_of_range_matches_vwap() {
+        let bars = sample_bars();
+        let anchored = anchored_vwap(&bars, VwapAnchor::StartOfRange);
+
+        assert_eq!(anchored.anchor_index, 0);
+        assert_eq!(anchored.vwap.len(), bars.len());
+        for (a, b) in anchored.vwap.iter().zip(vwap(&bars)) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert_eq!(anchored.std_dev[0], 0.0);
+        assert!(*anchored.std_dev.last().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_anchored_vwap_last_pivot() {
+        let bars = sample_bars();
+        // Steady uptrend: the latest high is the most recent pivot
+        let anchored = anchored_vwap(&bars, VwapAnchor::LastPivot { lookback: 10 });
+
+        assert_eq!(anchored.anchor_index, bars.len() - 1);
+        assert_eq!(anchored.vwap.len(), 1);
+        assert_eq!(
+            VwapAnchor::Index(100).resolve(&bars),
+            bars.len() - 1,
+            "fixed anchors clamp to the series"
+        );
+    }
+
     #[test]
     fn test_stochastic_basic() {
         let bars = sample_bars();
@@ -319,4 +348,58 @@ mod tests {
         // Should handle insufficient data gracefully
         assert_eq!(result.k.len(), 0);
     }
+
+    #[test]
+    fn test_ichimoku_displacement() {
+        let bars: Vec<Bar> = (0..80)
+            .map(|i| {
+                let close = 100.0 + i as f64;
+                Bar {
+                    timestamp: Utc::now() - chrono::Duration::days(80 - i as i64),
+                    open: close,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close,
+                    volume: 1000.0,
+                    vwap: None,
+                }
+            })
+            .collect();
+        let ichi = ichimoku(&bars);
+
+        // Spans for the first 26 bars come from bars that precede the series
+        assert_eq!(ichi.senkou_span_a.len(), bars.len() - ICHIMOKU_DISPLACEMENT);
+        assert_eq!(ichi.leading_span_a.len(), ICHIMOKU_DISPLACEMENT);
+        assert_eq!(ichi.chikou_span.len(), bars.len() - ICHIMOKU_DISPLACEMENT);
+
+        // The span at the last bar was computed from the bar 26 periods earlier
+        let src = bars.len() - 1 - ICHIMOKU_DISPLACEMENT;
+        let expected_a = (ichi.tenkan_sen[src] + ichi.kijun_sen[src]) / 2.0;
+        assert_eq!(*ichi.senkou_span_a.last().unwrap(), expected_a);
+
+        // The projected cloud ends at the spans of the final bar
+        let last = bars.len() - 1;
+        let expected_lead = (ichi.tenkan_sen[last] + ichi.kijun_sen[last]) / 2.0;
+        assert_eq!(*ichi.leading_span_a.last().unwrap(), expected_lead);
+    }
+
+    #[test]
+    fn test_engine_indicator_getters() {
+        let engine = crate::TechnicalAnalysisEngine::new();
+        let bars = sample_bars();
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+
+        assert_eq!(engine.rsi(&bars, 5), rsi(&closes, 5).last().copied());
+        assert_eq!(engine.sma(&bars, 10), sma(&closes, 10).last().copied());
+        assert_eq!(engine.atr(&bars, 5), atr(&bars, 5).last().copied());
+        assert!(engine.adx(&bars, 5).is_some());
+
+        let percent_b = engine.bb_percent_b(&bars, 10).unwrap();
+        assert!(percent_b > 0.5, "steady uptrend closes in the upper band");
+
+        // Too few bars for the period
+        assert_eq!(engine.rsi(&bars, 20), None);
+        assert_eq!(engine.adx(&bars, 14), None);
+        assert_eq!(engine.bb_percent_b(&bars, 20), None);
+    }
 }
@@ -4,6 +4,8 @@ pub mod patterns;
 
 #[cfg(test)]
 mod indicators_tests;
+#[cfg(test)]
+mod patterns_tests;
 
 pub use analyzer::*;
 pub use indicators::*;
@@ -23,6 +23,19 @@ pub struct PatternMatch {
     pub bullish: bool,
 }
 
+/// Bars before a pattern used to establish the trend it reverses
+const REVERSAL_CONTEXT_BARS: usize = 10;
+
+/// A reversal pattern confirmed by the trend leading into it
+#[derive(Debug, Clone)]
+pub struct CandlePattern {
+    pub name: &'static str,
+    /// Index of the pattern's final bar
+    pub index: usize,
+    pub bullish: bool,
+    pub strength: f64, // 0.0 to 1.0
+}
+
 /// Detect if a candle is a doji
 fn is_doji(bar: &Bar) -> bool {
     let body = (bar.close - bar.open).abs();
@@ -398,6 +411,95 @@ pub fn detect_patterns(bars: &[Bar]) -> Vec<PatternMatch> {
     patterns
 }
 
+/// Trend over the bars leading up to (not including) `start`
+fn prior_trend(bars: &[Bar], start: usize) -> Trend {
+    let from = start.saturating_sub(REVERSAL_CONTEXT_BARS);
+    detect_trend(&bars[from..start], REVERSAL_CONTEXT_BARS)
+}
+
+/// Scan every bar for classic reversal patterns — engulfing, hammer/hanging man,
+/// doji, morning/evening star — keeping only those that reverse the trend before
+/// them: a hammer only counts after a downtrend, a hanging man only after an
+/// uptrend, and so on. Patterns with no prior trend are dropped.
+pub fn detect_candlestick_patterns(bars: &[Bar]) -> Vec<CandlePattern> {
+    let mut found = Vec::new();
+
+    for i in REVERSAL_CONTEXT_BARS..bars.len() {
+        let window = &bars[..=i];
+        let bar = &bars[i];
+
+        // Single-bar patterns: context is the trend into this bar
+        let trend = prior_trend(bars, i);
+        if let Some(p) = is_hammer(bar) {
+            match trend {
+                Trend::Downtrend => found.push(CandlePattern {
+                    name: "Hammer",
+                    index: i,
+                    bullish: true,
+                    strength: p.strength,
+                }),
+                Trend::Uptrend => found.push(CandlePattern {
+                    name: "Hanging Man",
+                    index: i,
+                    bullish: false,
+                    strength: p.strength,
+                }),
+                Trend::Sideways => {}
+            }
+        } else if is_doji(bar) && trend != Trend::Sideways {
+            found.push(CandlePattern {
+                name: "Doji",
+                index: i,
+                bullish: trend == Trend::Downtrend,
+                strength: 0.5,
+            });
+        }
+
+        // Two-bar: trend into the first bar of the pair
+        if let Some(p) = is_engulfing(window) {
+            match (p.bullish, prior_trend(bars, i - 1)) {
+                (true, Trend::Downtrend) => found.push(CandlePattern {
+                    name: "Bullish Engulfing",
+                    index: i,
+                    bullish: true,
+                    strength: p.strength,
+                }),
+                (false, Trend::Uptrend) => found.push(CandlePattern {
+                    name: "Bearish Engulfing",
+                    index: i,
+                    bullish: false,
+                    strength: p.strength,
+                }),
+                _ => {}
+            }
+        }
+
+        // Three-bar: trend into the first bar of the star
+        let star_trend = prior_trend(bars, i - 2);
+        if star_trend == Trend::Downtrend {
+            if let Some(p) = is_morning_star(window) {
+                found.push(CandlePattern {
+                    name: "Morning Star",
+                    index: i,
+                    bullish: true,
+                    strength: p.strength,
+                });
+            }
+        } else if star_trend == Trend::Uptrend {
+            if let Some(p) = is_evening_star(window) {
+                found.push(CandlePattern {
+                    name: "Evening Star",
+                    index: i,
+                    bullish: false,
+                    strength: p.strength,
+                });
+            }
+        }
+    }
+
+    found
+}
+
 /// Detect trend direction using highs and lows
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Trend {
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use super::super::patterns::*;
+    use analysis_core::Bar;
+    use chrono::Utc;
+
+    fn bar(i: usize, open: f64, high: f64, low: f64, close: f64) -> Bar {
+        Bar {
+            timestamp: Utc::now() - chrono::Duration::days(30 - i as i64),
+            open,
+            high,
+            low,
+            close,
+            volume: 1000000.0,
+            vwap: None,
+        }
+    }
+
+    // Twelve bars falling (or rising) by one point a day
+    fn trend_bars(falling: bool) -> Vec<Bar> {
+        (0..12)
+            .map(|i| {
+                let step = if falling { -(i as f64) } else { i as f64 };
+                let close = 100.0 + step;
+                let open = if falling { close + 0.5 } else { close - 0.5 };
+                bar(
+                    i,
+                    open,
+                    close.max(open) + 0.25,
+                    close.min(open) - 0.25,
+                    close,
+                )
+            })
+            .collect()
+    }
+
+    // Small body near the high with a long lower shadow
+    fn hammer_shape(i: usize, level: f64) -> Bar {
+        bar(i, level, level + 0.6, level - 3.0, level + 0.5)
+    }
+
+    #[test]
+    fn test_hammer_after_downtrend_is_bullish() {
+        let mut bars = trend_bars(true);
+        bars.push(hammer_shape(12, 88.0));
+
+        let patterns = detect_candlestick_patterns(&bars);
+        let hammer = patterns.iter().find(|p| p.name == "Hammer").unwrap();
+        assert_eq!(hammer.index, 12);
+        assert!(hammer.bullish);
+        assert!(!patterns.iter().any(|p| p.name == "Hanging Man"));
+    }
+
+    #[test]
+    fn test_hammer_shape_after_uptrend_is_hanging_man() {
+        let mut bars = trend_bars(false);
+        bars.push(hammer_shape(12, 112.0));
+
+        let patterns = detect_candlestick_patterns(&bars);
+        let hanging = patterns.iter().find(|p| p.name == "Hanging Man").unwrap();
+        assert!(!hanging.bullish);
+        assert!(!patterns.iter().any(|p| p.name == "Hammer"));
+    }
+
+    #[test]
+    fn test_hammer_without_prior_trend_is_ignored() {
+        let mut bars: Vec<Bar> = (0..12).map(|i| bar(i, 100.0, 101.0, 99.0, 100.5)).collect();
+        bars.push(hammer_shape(12, 100.0));
+
+        assert!(detect_candlestick_patterns(&bars).is_empty());
+    }
+
+    #[test]
+    fn test_bullish_engulfing_after_downtrend() {
+        let mut bars = trend_bars(true);
+        // Bearish day, then a bullish day whose body swallows it
+        bars.push(bar(12, 89.0, 89.2, 87.8, 88.0));
+        bars.push(bar(13, 87.5, 90.2, 87.3, 90.0));
+
+        let patterns = detect_candlestick_patterns(&bars);
+        let engulfing = patterns
+            .iter()
+            .find(|p| p.name == "Bullish Engulfing")
+            .unwrap();
+        assert_eq!(engulfing.index, 13);
+        assert!(engulfing.bullish);
+    }
+
+    #[test]
+    fn test_evening_star_after_uptrend() {
+        let mut bars = trend_bars(false);
+        // Strong up day, small-bodied star, strong down day into the first body
+        bars.push(bar(12, 111.0, 114.2, 110.8, 114.0));
+        bars.push(bar(13, 114.5, 115.2, 114.3, 114.7));
+        bars.push(bar(14, 114.2, 114.4, 111.3, 111.5));
+
+        let patterns = detect_candlestick_patterns(&bars);
+        let star = patterns.iter().find(|p| p.name == "Evening Star").unwrap();
+        assert_eq!(star.index, 14);
+        assert!(!star.bullish);
+    }
+}
@@ -0,0 +1,15 @@
+-- Stored analysis results: full UnifiedAnalysis per symbol/run for signal history -- PostgreSQL version
+
+CREATE TABLE IF NOT EXISTS analysis_results (
+    id BIGSERIAL PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    analysis_date TEXT NOT NULL,
+    overall_signal TEXT NOT NULL,
+    overall_confidence DOUBLE PRECISION NOT NULL,
+    conviction_tier TEXT,
+    market_regime TEXT,
+    analysis_json TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_analysis_results_symbol ON analysis_results(symbol, analysis_date);
@@ -0,0 +1,15 @@
+-- Stored analysis results: full UnifiedAnalysis per symbol/run for signal history
+
+CREATE TABLE IF NOT EXISTS analysis_results (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL,
+    analysis_date TEXT NOT NULL,
+    overall_signal TEXT NOT NULL,
+    overall_confidence REAL NOT NULL,
+    conviction_tier TEXT,
+    market_regime TEXT,
+    analysis_json TEXT NOT NULL,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_analysis_results_symbol ON analysis_results(symbol, analysis_date);